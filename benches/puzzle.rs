@@ -0,0 +1,81 @@
+// Times rule checking and the solver on every built-in level and on a synthetic
+// 10x10 board, to catch slowdowns in code that runs every frame or on every level.
+//
+// Usage: cargo bench [-- FILTER]
+//
+// Only benchmarks whose name matches FILTER are run. Criterion keeps the previous
+// results in target/criterion and reports the change against them.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ludum_dare_54::level::{
+    all_levels, count_diagnoal_houses, count_houses_in_3x3, validate_solution, CellType, Puzzle,
+    Solution,
+};
+use ludum_dare_54::solver::{count_solutions, solve};
+
+// Larger than any built-in level. Generating a 10x10 board with a unique solution
+// takes too long, so this one is built around a fixed set of houses: two in every
+// line, none sharing a side. Lakes and mountains count what the houses give them,
+// so the houses are one solution, though not necessarily the only one.
+fn synthetic_puzzle() -> Puzzle {
+    const SIZE: usize = 10;
+    let has_house: Vec<Vec<bool>> = (0..SIZE)
+        .map(|row| (0..SIZE).map(|col| (row + 2 * col) % 5 == 0).collect())
+        .collect();
+    let mut puzzle = Puzzle::new(
+        vec![vec![CellType::Grass; SIZE]; SIZE],
+        vec![2; SIZE],
+        vec![2; SIZE],
+    )
+    .expect("the synthetic board is rectangular");
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            if has_house[row][col] {
+                continue;
+            }
+            puzzle.field[row][col] = match (7 * row + 3 * col) % 6 {
+                0 => CellType::Lake(count_houses_in_3x3(row, col, &has_house, &puzzle)),
+                1 => CellType::Mountain(count_diagnoal_houses(row, col, &has_house, &puzzle)),
+                2 => CellType::Tree,
+                _ => CellType::Grass,
+            };
+        }
+    }
+    puzzle
+}
+
+fn bench_puzzle(c: &mut Criterion, name: &str, puzzle: &Puzzle) {
+    let Some(solution) = solve(puzzle) else {
+        println!("{name}: unsolvable, skipped");
+        return;
+    };
+    // Validation runs on boards in progress too, so time one that is half done.
+    let partial = Solution {
+        placements: solution.placements[..solution.placements.len() / 2].to_vec(),
+    };
+    c.bench_function(&format!("validate_solution/partial/{name}"), |b| {
+        b.iter(|| validate_solution(black_box(&partial), puzzle))
+    });
+    c.bench_function(&format!("validate_solution/solved/{name}"), |b| {
+        b.iter(|| validate_solution(black_box(&solution), puzzle))
+    });
+    c.bench_function(&format!("solve/{name}"), |b| {
+        b.iter(|| solve(black_box(puzzle)))
+    });
+    // The limit the validate bin uses to tell unique puzzles apart.
+    c.bench_function(&format!("count_solutions/{name}"), |b| {
+        b.iter(|| count_solutions(black_box(puzzle), 2))
+    });
+}
+
+fn puzzles(c: &mut Criterion) {
+    for level in all_levels() {
+        bench_puzzle(c, &level.id, &level.puzzle);
+    }
+    bench_puzzle(c, "synthetic-10x10", &synthetic_puzzle());
+}
+
+criterion_group!(benches, puzzles);
+criterion_main!(benches);
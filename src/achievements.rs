@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+
+use crate::game_screen::{LevelCompleted, LevelStats};
+use crate::lang::Lang;
+use crate::level::all_levels;
+use crate::save::SaveData;
+use crate::toast::ShowToast;
+use crate::{CustomLevel, GameState};
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, unlock_achievements);
+    }
+}
+
+// Smallest board, in both directions, that counts for `CleanBigBoard`.
+const CLEAN_BOARD_SIZE: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Achievement {
+    FirstLevel,
+    CleanBigBoard,
+    AllLevels,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 3] = [
+        Achievement::FirstLevel,
+        Achievement::CleanBigBoard,
+        Achievement::AllLevels,
+    ];
+
+    // Stored in the save file, so it must not change once released.
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::FirstLevel => "first_level",
+            Achievement::CleanBigBoard => "clean_big_board",
+            Achievement::AllLevels => "all_levels",
+        }
+    }
+
+    // `Lang` keys of the name and the description.
+    pub fn title(self) -> &'static str {
+        match self {
+            Achievement::FirstLevel => "achievement.first_level",
+            Achievement::CleanBigBoard => "achievement.clean_big_board",
+            Achievement::AllLevels => "achievement.all_levels",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstLevel => "achievement.first_level.description",
+            Achievement::CleanBigBoard => "achievement.clean_big_board.description",
+            Achievement::AllLevels => "achievement.all_levels.description",
+        }
+    }
+}
+
+// Unlocked achievements, loaded from and written back to `SaveData`.
+#[derive(Resource, Default)]
+pub struct Achievements {
+    unlocked: Vec<Achievement>,
+}
+
+impl Achievements {
+    pub fn from_ids(ids: &[String]) -> Self {
+        Self {
+            unlocked: Achievement::ALL
+                .into_iter()
+                .filter(|achievement| ids.iter().any(|id| id == achievement.id()))
+                .collect(),
+        }
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.unlocked
+            .iter()
+            .map(|achievement| achievement.id().to_string())
+            .collect()
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    pub fn clear(&mut self) {
+        self.unlocked.clear();
+    }
+}
+
+fn unlock_achievements(
+    mut level_completed: EventReader<LevelCompleted>,
+    game_state: Res<GameState>,
+    level_stats: Res<LevelStats>,
+    save_data: Res<SaveData>,
+    mut achievements: ResMut<Achievements>,
+    lang: Res<Lang>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    for _ in level_completed.iter() {
+        let (rows, cols) = game_state.puzzle.dims();
+        // The save data may not list the level just completed yet.
+        let all_levels_done = game_state.current_level != CustomLevel::index()
+            && all_levels()
+                .iter()
+                .all(|level| level.id == game_state.id || save_data.is_completed(&level.id));
+        for achievement in Achievement::ALL {
+            let earned = match achievement {
+                Achievement::FirstLevel => true,
+                Achievement::CleanBigBoard => {
+                    rows >= CLEAN_BOARD_SIZE
+                        && cols >= CLEAN_BOARD_SIZE
+                        && !level_stats.assisted
+                        && level_stats.mistakes == 0
+                }
+                Achievement::AllLevels => all_levels_done,
+            };
+            if earned && !achievements.is_unlocked(achievement) {
+                achievements.unlocked.push(achievement);
+                toasts.send(ShowToast::with(
+                    "toast.achievement_unlocked",
+                    vec![("title", lang.t(achievement.title()).to_string())],
+                ));
+            }
+        }
+    }
+}
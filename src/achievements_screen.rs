@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+
+use crate::achievements::{Achievement, Achievements};
+use crate::lang::Lang;
+use crate::{AppState, ButtonInteractions};
+
+pub struct AchievementsScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for AchievementsScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_achievements_screen)
+            .add_systems(Update, button_system.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_achievements_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const LOCKED_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+
+// Tag component used to tag entities added on the achievements screen.
+#[derive(Component)]
+struct OnAchievementsScreen;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum AchievementsButtonAction {
+    Back,
+}
+
+fn item_achievement(
+    builder: &mut ChildBuilder,
+    achievement: Achievement,
+    unlocked: bool,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    let (title_color, description_color) = if unlocked {
+        (crate::CUSTOM_ORANGE, Color::BLACK)
+    } else {
+        (LOCKED_COLOR, LOCKED_COLOR)
+    };
+    builder
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(15.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                lang.t(achievement.title()),
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 60.0,
+                    color: title_color,
+                },
+            ));
+            builder.spawn(TextBundle::from_section(
+                lang.t(achievement.description()),
+                TextStyle {
+                    font: server.load("NotoSerif-SemiBold.ttf"),
+                    font_size: 24.0,
+                    color: description_color,
+                },
+            ));
+        });
+}
+
+fn create_achievements_screen(
+    mut commands: Commands,
+    achievements: Res<Achievements>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                image: UiImage::new(server.load("full.png")),
+                ..default()
+            },
+            OnAchievementsScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn(
+                TextBundle::from_section(
+                    lang.t("achievements.title"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 120.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+
+            for achievement in Achievement::ALL {
+                item_achievement(
+                    builder,
+                    achievement,
+                    achievements.is_unlocked(achievement),
+                    &lang,
+                    &server,
+                );
+            }
+
+            builder.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(50.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Px(30.0)),
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    image: UiImage::new(server.load("UI/button_back.png")),
+                    ..default()
+                },
+                AchievementsButtonAction::Back,
+            ));
+        });
+}
+
+fn destroy_achievements_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<OnAchievementsScreen>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<AchievementsButtonAction>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                AchievementsButtonAction::Back => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+            };
+        }
+    }
+}
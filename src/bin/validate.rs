@@ -0,0 +1,108 @@
+// Checks that levels are solvable and have a unique solution, without starting the game.
+//
+// Usage: validate [LEVEL_FILE]
+//
+// Without arguments all built-in levels are checked. A level file uses the format
+// accepted by `parse_puzzle`.
+
+use std::process::ExitCode;
+
+use ludum_dare_54::level::{all_levels, parse_puzzle, validate_solution, GameLevel, Puzzle};
+use ludum_dare_54::solver::{count_solutions, search_effort, solve};
+
+fn check_level(level: &GameLevel) -> bool {
+    println!("== {} ==", level.name);
+    if let Err(err) = level.puzzle.validate_dimensions() {
+        println!("Result: malformed, {err}\n");
+        return false;
+    }
+    print!("{}", level.puzzle);
+    if !level.puzzle.counts_feasible() {
+        println!("Result: line counts can't be met\n");
+        return false;
+    }
+    if parse_puzzle(&level.puzzle.to_level_string()).as_ref() != Ok(&level.puzzle) {
+        println!("Result: does not survive a round trip through the level string format\n");
+        return false;
+    }
+    let code = match level.puzzle.encode() {
+        Ok(code) => code,
+        Err(err) => {
+            println!("Result: {err}\n");
+            return false;
+        }
+    };
+    if Puzzle::decode(&code).as_ref() != Ok(&level.puzzle) {
+        println!("Result: does not survive a round trip through its level code\n");
+        return false;
+    }
+    println!("Level code: {code}");
+    let Some(solution) = solve(&level.puzzle) else {
+        println!("Result: unsolvable\n");
+        return false;
+    };
+    print!("{}", validate_solution(&solution, &level.puzzle));
+    // A board one house short of the solution must never count as complete.
+    let complete_when_short = (0..solution.placements.len()).any(|index| {
+        let mut short = solution.clone();
+        short.placements.remove(index);
+        validate_solution(&short, &level.puzzle).complete
+    });
+    if !validate_solution(&solution, &level.puzzle).complete || complete_when_short {
+        println!("Result: solution is not judged correctly\n");
+        return false;
+    }
+    println!(
+        "Search steps: {} ({:?})",
+        search_effort(&level.puzzle),
+        level.difficulty()
+    );
+    if count_solutions(&level.puzzle, 2) > 1 {
+        println!("Result: ambiguous\n");
+        return false;
+    }
+    println!("Result: unique solution\n");
+    true
+}
+
+fn main() -> ExitCode {
+    let levels = match std::env::args().nth(1) {
+        Some(path) => {
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(err) => {
+                    eprintln!("Failed to read {path}: {err}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            match parse_puzzle(&contents) {
+                Ok(puzzle) => vec![GameLevel {
+                    id: path.clone(),
+                    name: path,
+                    puzzle,
+                    par: None,
+                }],
+                Err(err) => {
+                    eprintln!("Failed to parse {path}: {err}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => all_levels().to_vec(),
+    };
+
+    // Check every level even after a failure so all problems are reported at once.
+    let mut failures = levels.iter().filter(|level| !check_level(level)).count();
+    // The game tells levels apart by id, e.g. in the save file.
+    for (index, level) in levels.iter().enumerate() {
+        if levels[..index].iter().any(|other| other.id == level.id) {
+            println!("Duplicate level id: {}\n", level.id);
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        println!("{failures} level(s) failed");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
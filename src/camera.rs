@@ -0,0 +1,120 @@
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowResized};
+
+use crate::game_screen::board_extent;
+use crate::level::Puzzle;
+use crate::pause_menu::not_paused;
+use crate::{AppState, GameState};
+
+pub struct GameCameraPlugin;
+
+impl Plugin for GameCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (camera_zoom, camera_pan).run_if(in_state(AppState::InGame).and_then(not_paused)),
+        )
+        .add_systems(
+            Update,
+            fit_camera_to_board
+                .run_if(in_state(AppState::InGame).and_then(on_event::<WindowResized>())),
+        )
+        .add_systems(Update, apply_camera_pan_zoom)
+        .add_systems(OnEnter(AppState::InGame), fit_camera_to_board)
+        .add_systems(OnEnter(AppState::SwitchLevel), reset_camera)
+        .add_systems(OnExit(AppState::InGame), reset_camera);
+    }
+}
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+
+// How much a single wheel "line" changes the zoom.
+const ZOOM_STEP: f32 = 0.1;
+// Pixel scroll events (touchpads) report much larger values than line events.
+const PIXELS_PER_LINE: f32 = 100.0;
+// Space kept free around the board: the sides and the HUD above and below it.
+const FIT_MARGIN: Vec2 = Vec2::new(40.0, 130.0);
+
+// User-controlled pan and zoom, applied on top of the scale that fits the
+// whole board into the window.
+#[derive(Component)]
+pub struct GameCamera {
+    pub zoom: f32,
+    pub pan: Vec2,
+    pub fit: f32,
+}
+
+impl Default for GameCamera {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan: Vec2::ZERO,
+            fit: 1.0,
+        }
+    }
+}
+
+fn camera_zoom(
+    mut scroll_events: EventReader<MouseWheel>,
+    mut camera_query: Query<&mut GameCamera>,
+) {
+    let mut camera = camera_query.single_mut();
+    for event in scroll_events.iter() {
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_LINE,
+        };
+        // Scrolling up zooms in, which means a smaller projection scale.
+        camera.zoom = (camera.zoom * (1.0 - ZOOM_STEP * lines)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+}
+
+fn camera_pan(
+    mouse: Res<Input<MouseButton>>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut camera_query: Query<&mut GameCamera>,
+) {
+    let mut camera = camera_query.single_mut();
+    let delta: Vec2 = motion_events.iter().map(|event| event.delta).sum();
+    if mouse.pressed(MouseButton::Middle) {
+        // Screen space grows downwards, world space grows upwards.
+        let scale = camera.fit * camera.zoom;
+        camera.pan += Vec2::new(-delta.x, delta.y) * scale;
+    }
+}
+
+fn apply_camera_pan_zoom(
+    mut camera_query: Query<
+        (&GameCamera, &mut OrthographicProjection, &mut Transform),
+        Changed<GameCamera>,
+    >,
+) {
+    for (camera, mut projection, mut transform) in camera_query.iter_mut() {
+        projection.scale = camera.fit * camera.zoom;
+        transform.translation.x = camera.pan.x;
+        transform.translation.y = camera.pan.y;
+    }
+}
+
+fn fit_camera_to_board(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    game_state: Res<GameState>,
+    mut camera_query: Query<&mut GameCamera>,
+) {
+    let window = window_query.single();
+    camera_query.single_mut().fit = fit_scale(window, &game_state.puzzle);
+}
+
+// Projection scale at which the whole board fits in the window next to the HUD.
+pub fn fit_scale(window: &Window, puzzle: &Puzzle) -> f32 {
+    let available = (Vec2::new(window.width(), window.height()) - 2.0 * FIT_MARGIN).max(Vec2::ONE);
+    let extent = board_extent(puzzle);
+    // Never magnify small boards beyond their natural size.
+    (extent / available).max_element().max(1.0)
+}
+
+fn reset_camera(mut camera_query: Query<&mut GameCamera>) {
+    *camera_query.single_mut() = GameCamera::default();
+}
@@ -0,0 +1,187 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::game_screen::CurrentValidation;
+use crate::lang::Lang;
+use crate::level::Solution;
+use crate::{ButtonInteractions, GameState};
+
+pub struct CheckReportPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for CheckReportPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RequestCheck>()
+            .init_resource::<CheckedBoard>()
+            .add_systems(
+                Update,
+                (show_check_report, close_stale_check_report, button_system)
+                    .chain()
+                    .run_if(in_state(self.0)),
+            )
+            .add_systems(OnExit(self.0), destroy_check_report);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const PANEL_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.75);
+
+// Sent by the check button. Lists everything wrong with the board in a panel.
+#[derive(Event)]
+pub struct RequestCheck;
+
+// Tag component used to tag entities added for the check report.
+#[derive(Component)]
+struct OnCheckReport;
+
+#[derive(Component)]
+struct CloseCheckReportButton;
+
+// The board the open report describes. The report closes once the board changes,
+// as it no longer matches it.
+#[derive(Resource, Default)]
+struct CheckedBoard(Option<Solution>);
+
+// The board to check and what the validation found on it.
+#[derive(SystemParam)]
+struct Board<'w> {
+    game_state: Res<'w, GameState>,
+    validation: Res<'w, CurrentValidation>,
+}
+
+fn show_check_report(
+    mut commands: Commands,
+    mut requests: EventReader<RequestCheck>,
+    board: Board,
+    mut checked_board: ResMut<CheckedBoard>,
+    report_query: Query<Entity, With<OnCheckReport>>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    if requests.iter().count() == 0 {
+        return;
+    }
+    for entity in report_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    checked_board.0 = Some(board.game_state.solution.clone());
+    let report = board
+        .validation
+        .0
+        .report(&board.game_state.puzzle)
+        .iter()
+        .map(|line| lang.t_with(line.key, &line.args))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(20.0),
+                    top: Val::Px(120.0),
+                    max_width: Val::Px(420.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(15.0)),
+                    ..default()
+                },
+                background_color: PANEL_BACKGROUND.into(),
+                // Keep clicks on the panel from reaching the board underneath.
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(60),
+                ..default()
+            },
+            OnCheckReport,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                lang.t("check.title"),
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 60.0,
+                    color: crate::CUSTOM_ORANGE,
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                report,
+                TextStyle {
+                    font: server.load("NotoSerif-SemiBold.ttf"),
+                    font_size: 22.0,
+                    color: Color::WHITE,
+                },
+            ));
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(120.0),
+                            height: Val::Px(50.0),
+                            margin: UiRect::top(Val::Px(15.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: NORMAL_BUTTON.into(),
+                        image: UiImage::new(server.load("UI/button_empty.png")),
+                        ..default()
+                    },
+                    CloseCheckReportButton,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        lang.t("check.close"),
+                        TextStyle {
+                            font: server.load(crate::TEXT_FONT_NAME),
+                            font_size: 40.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+fn close_stale_check_report(
+    commands: Commands,
+    game_state: Res<GameState>,
+    mut checked_board: ResMut<CheckedBoard>,
+    report_query: Query<Entity, With<OnCheckReport>>,
+) {
+    let Some(board) = &checked_board.0 else {
+        return;
+    };
+    if *board != game_state.solution {
+        checked_board.0 = None;
+        destroy_check_report(commands, report_query);
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut commands: Commands,
+    mut interaction_query: ButtonInteractions<CloseCheckReportButton>,
+    report_query: Query<Entity, With<OnCheckReport>>,
+) {
+    for (interaction, mut color, _) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            for entity in report_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn destroy_check_report(mut commands: Commands, query: Query<Entity, With<OnCheckReport>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
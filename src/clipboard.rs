@@ -0,0 +1,36 @@
+// Copies text to the system clipboard. Failures are only logged, as there is nothing
+// the player can do about them.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn copy_to_clipboard(text: &str) {
+    let result = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+    if let Err(err) = result {
+        bevy::log::warn!("Failed to copy to the clipboard: {err}");
+    }
+}
+
+// Reads text from the system clipboard.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read_clipboard() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|err| err.to_string())
+}
+
+// Browsers only offer the asynchronous clipboard API. The returned promise is dropped,
+// so the copy finishes in the background.
+#[cfg(target_arch = "wasm32")]
+pub fn copy_to_clipboard(text: &str) {
+    match web_sys::window() {
+        Some(window) => {
+            let _ = window.navigator().clipboard().write_text(text);
+        }
+        None => bevy::log::warn!("Failed to copy to the clipboard: no window"),
+    }
+}
+
+// Reading the clipboard in a browser needs a user gesture handled by the page, which
+// the game does not have.
+#[cfg(target_arch = "wasm32")]
+pub fn read_clipboard() -> Result<String, String> {
+    Err("pasting is not supported in the browser".to_string())
+}
@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::lang::Lang;
+use crate::ButtonInteractions;
+
+pub struct ConfirmDialogPlugin;
+
+impl Plugin for ConfirmDialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowConfirmDialog>()
+            .add_event::<DialogConfirmed>()
+            .add_systems(Update, (create_confirm_dialog, button_system).chain());
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+// Actions that are only carried out once the player confirms them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmAction {
+    LeaveLevel,
+    ResetProgress,
+    RevealSolution,
+}
+
+// Asks the player a yes/no question, given as a `Lang` key. Answering yes sends
+// `DialogConfirmed`.
+#[derive(Event)]
+pub struct ShowConfirmDialog {
+    pub message: &'static str,
+    pub action: ConfirmAction,
+}
+
+#[derive(Event)]
+pub struct DialogConfirmed(pub ConfirmAction);
+
+// Tag component used to tag entities added for the confirm dialog.
+#[derive(Component)]
+pub struct OnConfirmDialog;
+
+#[derive(Component)]
+enum DialogButtonAction {
+    Yes(ConfirmAction),
+    No,
+}
+
+// Run condition for systems that must not react to input behind the dialog.
+pub fn no_confirm_dialog(dialog_query: Query<(), With<OnConfirmDialog>>) -> bool {
+    dialog_query.is_empty()
+}
+
+fn create_confirm_dialog(
+    mut commands: Commands,
+    mut requests: EventReader<ShowConfirmDialog>,
+    dialog_query: Query<(), With<OnConfirmDialog>>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    // Only one question is asked at a time.
+    let Some(request) = requests.iter().last() else {
+        return;
+    };
+    if !dialog_query.is_empty() {
+        return;
+    }
+
+    let button_style = Style {
+        width: Val::Px(150.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(15.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font: server.load(crate::TEXT_FONT_NAME),
+        font_size: 50.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                // Keep clicks from reaching the buttons underneath.
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(150),
+                ..default()
+            },
+            OnConfirmDialog,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    lang.t(request.message),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 80.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, action) in [
+                        ("dialog.yes", DialogButtonAction::Yes(request.action)),
+                        ("dialog.no", DialogButtonAction::No),
+                    ] {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_empty.png")),
+                                    ..default()
+                                },
+                                action,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    lang.t(label),
+                                    button_text_style.clone(),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut commands: Commands,
+    mut interaction_query: ButtonInteractions<DialogButtonAction>,
+    dialog_query: Query<Entity, With<OnConfirmDialog>>,
+    mut confirmed: EventWriter<DialogConfirmed>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            if let DialogButtonAction::Yes(action) = *action {
+                confirmed.send(DialogConfirmed(action));
+            }
+            for entity in dialog_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+use chrono::{Datelike, NaiveDate};
+
+use crate::game_screen::LevelCompleted;
+use crate::generator::generate_puzzle;
+use crate::level::GameLevel;
+use crate::pause_menu::not_paused;
+use crate::save::SaveData;
+use crate::solver::solve;
+use crate::toast::ShowToast;
+use crate::{AppState, CustomLevel, GameState};
+
+pub struct DailyChallengePlugin;
+
+impl Plugin for DailyChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DailyChallenge>()
+            .add_systems(OnEnter(AppState::SwitchLevel), reset_daily_timer)
+            .add_systems(
+                Update,
+                tick_daily_timer.run_if(in_state(AppState::InGame).and_then(not_paused)),
+            )
+            .add_systems(Update, record_daily_best);
+    }
+}
+
+pub const DAILY_ROWS: usize = 6;
+pub const DAILY_COLS: usize = 6;
+
+// The daily challenge that was last started and how long the current attempt has
+// been going for.
+#[derive(Resource, Default)]
+pub struct DailyChallenge {
+    pub date: Option<NaiveDate>,
+    pub elapsed: f32,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> NaiveDate {
+    let now = js_sys::Date::new_0();
+    NaiveDate::from_ymd_opt(
+        now.get_full_year() as i32,
+        now.get_month() + 1,
+        now.get_date(),
+    )
+    .expect("JS Date is a valid date")
+}
+
+// Days since the start of the common era, so every date gets its own board.
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    date.num_days_from_ce() as u64
+}
+
+pub fn daily_level_id(date: NaiveDate) -> String {
+    format!("daily-{date}")
+}
+
+pub fn daily_level_name(date: NaiveDate) -> String {
+    format!("Daily {date}")
+}
+
+// Par is the size of the generated board's unique solution.
+pub fn daily_level(date: NaiveDate) -> GameLevel {
+    let puzzle = generate_puzzle(DAILY_ROWS, DAILY_COLS, daily_seed(date));
+    let par = solve(&puzzle).map(|solution| solution.placements.len());
+    GameLevel {
+        id: daily_level_id(date),
+        name: daily_level_name(date),
+        puzzle,
+        par,
+    }
+}
+
+// Starts today's challenge as a custom level.
+pub fn start_daily_challenge(
+    daily_challenge: &mut DailyChallenge,
+    game_state: &mut GameState,
+    custom_level: &mut CustomLevel,
+    app_state: &mut NextState<AppState>,
+) {
+    let date = today();
+    daily_challenge.date = Some(date);
+    custom_level.0 = Some(daily_level(date));
+    game_state.current_level = CustomLevel::index();
+    app_state.set(AppState::SwitchLevel);
+}
+
+fn reset_daily_timer(mut daily_challenge: ResMut<DailyChallenge>) {
+    daily_challenge.elapsed = 0.0;
+}
+
+fn tick_daily_timer(time: Res<Time>, mut daily_challenge: ResMut<DailyChallenge>) {
+    daily_challenge.elapsed += time.delta_seconds();
+}
+
+fn record_daily_best(
+    mut level_completed: EventReader<LevelCompleted>,
+    daily_challenge: Res<DailyChallenge>,
+    game_state: Res<GameState>,
+    mut save_data: ResMut<SaveData>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    for _ in level_completed.iter() {
+        let Some(date) = daily_challenge.date else {
+            continue;
+        };
+        if game_state.current_level != CustomLevel::index() || game_state.id != daily_level_id(date)
+        {
+            continue;
+        }
+        let key = date.to_string();
+        let time = daily_challenge.elapsed;
+        match save_data.daily_best.get(&key) {
+            Some(&best) if best <= time => {
+                toasts.send(ShowToast::with(
+                    "toast.daily_solved",
+                    vec![
+                        ("time", format!("{time:.0}")),
+                        ("best", format!("{best:.0}")),
+                    ],
+                ));
+            }
+            _ => {
+                save_data.daily_best.insert(key, time);
+                toasts.send(ShowToast::with(
+                    "toast.daily_best",
+                    vec![("time", format!("{time:.0}"))],
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use bevy::diagnostic::{
+    DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::prelude::*;
+
+use crate::game_screen::ValidationTime;
+use crate::level::{
+    adjacent_houses, count_diagnoal_houses, count_houses_in_3x3, house_grid, Position,
+};
+use crate::tile_atlas::TileAtlas;
+use crate::GameState;
+
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((FrameTimeDiagnosticsPlugin, EntityCountDiagnosticsPlugin))
+            .init_resource::<DebugOverlay>()
+            .add_systems(
+                Update,
+                (
+                    toggle_debug_overlay,
+                    update_debug_overlay,
+                    update_debug_cell_counts,
+                )
+                    .chain(),
+            );
+    }
+}
+
+const OVERLAY_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+// Whether the F3 debug overlay is shown.
+#[derive(Resource, Default)]
+pub struct DebugOverlay(pub bool);
+
+#[derive(Component)]
+struct DebugOverlayText;
+
+// Label on a grass cell, showing the houses it sees while the overlay is on: orthogonal
+// neighbors, the 3x3 block a lake counts and the diagonals a mountain counts.
+#[derive(Component)]
+pub struct DebugCellCounts(pub Position);
+
+fn toggle_debug_overlay(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut debug_overlay: ResMut<DebugOverlay>,
+    overlay_query: Query<Entity, With<DebugOverlayText>>,
+    server: Res<AssetServer>,
+) {
+    if !keys.just_pressed(KeyCode::F3) {
+        return;
+    }
+    debug_overlay.0 ^= true;
+    if !debug_overlay.0 {
+        for entity in overlay_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: server.load("NotoSerif-SemiBold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        })
+        .with_background_color(OVERLAY_BACKGROUND),
+        ZIndex::Global(300),
+        DebugOverlayText,
+    ));
+}
+
+fn update_debug_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    validation_time: Res<ValidationTime>,
+    tile_atlas: Res<TileAtlas>,
+    mut text_query: Query<&mut Text, With<DebugOverlayText>>,
+) {
+    let value = |id| {
+        diagnostics
+            .get(id)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!(
+            "FPS: {:.0}\nEntities: {:.0}\nValidation: {:.3} ms\nTile atlas (F4): {}\nCells: adjacent/3x3/diagonal",
+            value(FrameTimeDiagnosticsPlugin::FPS),
+            value(EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+            validation_time.0.as_secs_f64() * 1000.0,
+            match (tile_atlas.enabled, tile_atlas.is_built()) {
+                (true, true) => "on",
+                (true, false) => "on, not built",
+                (false, _) => "off",
+            },
+        );
+    }
+}
+
+fn update_debug_cell_counts(
+    debug_overlay: Res<DebugOverlay>,
+    game_state: Res<GameState>,
+    mut label_query: Query<(&mut Text, &mut Visibility, &DebugCellCounts)>,
+    added_query: Query<(), Added<DebugCellCounts>>,
+) {
+    if !debug_overlay.is_changed() && !game_state.is_changed() && added_query.is_empty() {
+        return;
+    }
+    let puzzle = &game_state.puzzle;
+    let has_house = house_grid(&game_state.solution, puzzle);
+    for (mut text, mut visibility, DebugCellCounts(position)) in label_query.iter_mut() {
+        if !debug_overlay.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        let (row, col) = (position.row, position.col);
+        text.sections[0].value = format!(
+            "{}/{}/{}",
+            adjacent_houses(row, col, &has_house, puzzle).len(),
+            count_houses_in_3x3(row, col, &has_house, puzzle),
+            count_diagnoal_houses(row, col, &has_house, puzzle),
+        );
+        *visibility = Visibility::Inherited;
+    }
+}
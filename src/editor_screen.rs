@@ -0,0 +1,496 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::window::PrimaryWindow;
+
+use crate::camera::{fit_scale, GameCamera};
+use crate::clipboard::copy_to_clipboard;
+use crate::game_screen::{
+    board_translation, cell_depth, iso_offset, item_number_constraints, world_to_board, CELL_LAYER,
+    CELL_SIZE, GRASS_LAYER, TEXT_LAYER,
+};
+use crate::lang::Lang;
+use crate::level::{CellType, GameLevel, Puzzle, DEFAULT_LAKE_COUNT, DEFAULT_MOUNTAIN_COUNT};
+use crate::solver::count_solutions;
+use crate::theme::Theme;
+use crate::{AppState, ButtonInteractions, CustomLevel, GameState};
+
+pub struct EditorScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for EditorScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditorPuzzle>()
+            .add_systems(OnEnter(self.0), create_editor_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_editor_input,
+                    copy_puzzle,
+                    button_system,
+                    update_editor_board
+                        .after(handle_editor_input)
+                        .after(button_system),
+                    update_solution_status.after(update_editor_board),
+                )
+                    .run_if(in_state(self.0)),
+            )
+            .add_systems(OnExit(self.0), destroy_editor_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+
+const MIN_BOARD_SIZE: usize = 2;
+const MAX_BOARD_SIZE: usize = 10;
+
+// The puzzle being edited. It is kept when leaving the editor, so it is still there
+// after test-playing it.
+#[derive(Resource)]
+pub struct EditorPuzzle(pub Puzzle);
+
+impl Default for EditorPuzzle {
+    fn default() -> Self {
+        Self(Puzzle::new(vec![vec![CellType::Grass; 4]; 4], vec![0; 4], vec![0; 4]).unwrap())
+    }
+}
+
+// Tag component used to tag entities added on the editor screen.
+#[derive(Component)]
+struct OnEditorScreen;
+
+// Root of the board sprites, rebuilt whenever the puzzle changes.
+#[derive(Component)]
+struct EditorBoardRoot;
+
+#[derive(Component)]
+struct SolutionStatusText;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum EditorButtonAction {
+    Back,
+    AddRow,
+    RemoveRow,
+    AddCol,
+    RemoveCol,
+    Play,
+    Export,
+}
+
+// The part of the board under the cursor.
+enum EditorTarget {
+    Cell { row: usize, col: usize },
+    RowCount(usize),
+    ColCount(usize),
+}
+
+// Grass -> tree -> lake -> mountain -> rock -> given house -> grass.
+fn next_cell_type(cell_type: CellType, forward: bool) -> CellType {
+    let cycle = [
+        CellType::Grass,
+        CellType::Tree,
+        CellType::Lake(DEFAULT_LAKE_COUNT),
+        CellType::Mountain(DEFAULT_MOUNTAIN_COUNT),
+        CellType::Rock,
+        CellType::House,
+    ];
+    let index = cycle
+        .iter()
+        .position(|&x| x.to_char() == cell_type.to_char())
+        .unwrap_or(0);
+    let step = if forward { 1 } else { cycle.len() - 1 };
+    cycle[(index + step) % cycle.len()]
+}
+
+fn resize_puzzle(puzzle: &mut Puzzle, rows: usize, cols: usize) {
+    let rows = rows.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+    let cols = cols.clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+    puzzle.resize(rows, cols);
+}
+
+// Prints the puzzle as a level string, with its variant rules and counts. It can be
+// saved to a file and opened again like the ones in levels/.
+fn export_puzzle(puzzle: &Puzzle) {
+    print!("{}", puzzle.to_level_string());
+}
+
+// Ctrl+C copies the puzzle as a level string, to paste into the game or a file.
+fn copy_puzzle(keys: Res<Input<KeyCode>>, editor_puzzle: Res<EditorPuzzle>) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::C) {
+        copy_to_clipboard(&editor_puzzle.0.to_level_string());
+    }
+}
+
+fn editor_button(
+    builder: &mut ChildBuilder,
+    label: &str,
+    action: EditorButtonAction,
+    server: &Res<AssetServer>,
+) {
+    builder
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(120.0),
+                    height: Val::Px(50.0),
+                    margin: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                image: UiImage::new(server.load("UI/button_empty.png")),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn create_editor_screen(mut commands: Commands, lang: Res<Lang>, server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::SpaceBetween,
+                    ..default()
+                },
+                ..default()
+            },
+            OnEditorScreen,
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        align_items: AlignItems::Center,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        ButtonBundle {
+                            style: Style {
+                                width: Val::Px(50.0),
+                                height: Val::Px(50.0),
+                                margin: UiRect::all(Val::Px(20.0)),
+                                ..default()
+                            },
+                            background_color: NORMAL_BUTTON.into(),
+                            image: UiImage::new(server.load("UI/button_back.png")),
+                            ..default()
+                        },
+                        EditorButtonAction::Back,
+                    ));
+                    for (key, action) in [
+                        ("editor.remove_row", EditorButtonAction::RemoveRow),
+                        ("editor.add_row", EditorButtonAction::AddRow),
+                        ("editor.remove_col", EditorButtonAction::RemoveCol),
+                        ("editor.add_col", EditorButtonAction::AddCol),
+                        ("editor.play", EditorButtonAction::Play),
+                        ("editor.export", EditorButtonAction::Export),
+                    ] {
+                        editor_button(builder, lang.t(key), action, &server);
+                    }
+                });
+
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: server.load(crate::TEXT_FONT_NAME),
+                                font_size: 50.0,
+                                color: crate::CUSTOM_ORANGE,
+                            },
+                        ),
+                        SolutionStatusText,
+                    ));
+                    builder.spawn(TextBundle::from_section(
+                        lang.t("editor.help"),
+                        TextStyle {
+                            font: server.load(crate::TEXT_FONT_NAME),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+type EditorScreenEntity = Or<(With<OnEditorScreen>, With<EditorBoardRoot>)>;
+
+fn destroy_editor_screen(
+    mut commands: Commands,
+    query: Query<Entity, EditorScreenEntity>,
+    mut camera_query: Query<&mut GameCamera>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *camera_query.single_mut() = GameCamera::default();
+}
+
+fn spawn_editor_board(
+    commands: &mut Commands,
+    puzzle: &Puzzle,
+    theme: &Theme,
+    server: &Res<AssetServer>,
+) {
+    let (rows, cols) = puzzle.dims();
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(board_translation(puzzle))),
+            EditorBoardRoot,
+        ))
+        .with_children(|builder| {
+            for r in 0..rows {
+                for c in 0..cols {
+                    let offset = iso_offset(r, c, CELL_SIZE);
+                    let z = cell_depth(r, c, cols);
+                    let sprite = Sprite {
+                        custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                        anchor: Anchor::CenterLeft,
+                        ..default()
+                    };
+                    let grass_texture = theme.grass(server, r, c, 1);
+                    builder.spawn(SpriteBundle {
+                        sprite: sprite.clone(),
+                        transform: Transform::from_xyz(offset.x, offset.y, z + GRASS_LAYER),
+                        texture: grass_texture,
+                        ..default()
+                    });
+                    let cell_type = puzzle.field[r][c];
+                    if cell_type != CellType::Grass {
+                        builder.spawn(SpriteBundle {
+                            sprite,
+                            transform: Transform::from_xyz(offset.x, offset.y, z + CELL_LAYER),
+                            texture: theme.cell_texture(server, cell_type),
+                            ..default()
+                        });
+                    }
+                    // Lakes and mountains show how many houses they require.
+                    if let Some(count) = cell_type.required_count() {
+                        builder.spawn(Text2dBundle {
+                            text: Text::from_section(
+                                count.to_string(),
+                                TextStyle {
+                                    font: server.load("NotoSerif-SemiBold.ttf"),
+                                    font_size: 40.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            transform: Transform::from_xyz(
+                                offset.x + 0.5 * CELL_SIZE,
+                                offset.y,
+                                z + TEXT_LAYER,
+                            ),
+                            ..default()
+                        });
+                    }
+                }
+            }
+            item_number_constraints(builder, puzzle, server);
+        });
+}
+
+// Redraws the board and refits the camera whenever the puzzle changes.
+fn update_editor_board(
+    mut commands: Commands,
+    editor_puzzle: Res<EditorPuzzle>,
+    board_query: Query<Entity, With<EditorBoardRoot>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<&mut GameCamera>,
+    theme: Res<Theme>,
+    server: Res<AssetServer>,
+) {
+    if !editor_puzzle.is_changed() && !board_query.is_empty() {
+        return;
+    }
+    for entity in board_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_editor_board(&mut commands, &editor_puzzle.0, &theme, &server);
+    camera_query.single_mut().fit = fit_scale(window_query.single(), &editor_puzzle.0);
+}
+
+fn update_solution_status(
+    editor_puzzle: Res<EditorPuzzle>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<SolutionStatusText>>,
+) {
+    if !editor_puzzle.is_changed() {
+        return;
+    }
+    let status = match count_solutions(&editor_puzzle.0, 2) {
+        0 => "editor.no_solution",
+        1 => "editor.unique_solution",
+        _ => "editor.several_solutions",
+    };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lang.t(status).to_string();
+    }
+}
+
+fn handle_editor_input(
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    board_query: Query<&Transform, With<EditorBoardRoot>>,
+    mut editor_puzzle: ResMut<EditorPuzzle>,
+) {
+    let Ok(board_transform) = board_query.get_single() else {
+        return;
+    };
+    let (camera, camera_global_transform) = camera_query.single();
+    let Some(p) = window_query
+        .single()
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_global_transform, cursor))
+        .map(|world| world_to_board(world, board_transform.translation))
+    else {
+        return;
+    };
+
+    let (rows, cols) = editor_puzzle.0.dims();
+    let (col, row) = (p.x.floor(), p.y.floor());
+    // Row counts are drawn just past the last column, column counts just before the
+    // first row.
+    let target = if col >= 0.0 && row >= 0.0 && (col as usize) < cols && (row as usize) < rows {
+        EditorTarget::Cell {
+            row: row as usize,
+            col: col as usize,
+        }
+    } else if col as usize == cols && row >= 0.0 && (row as usize) < rows {
+        EditorTarget::RowCount(row as usize)
+    } else if row == -1.0 && col >= 0.0 && (col as usize) < cols {
+        EditorTarget::ColCount(col as usize)
+    } else {
+        return;
+    };
+
+    let forward = mouse.just_pressed(MouseButton::Left);
+    let backward = mouse.just_pressed(MouseButton::Right);
+    let digit = keys.get_just_pressed().find_map(|key| match key {
+        KeyCode::Key0 | KeyCode::Numpad0 => Some(0),
+        KeyCode::Key1 | KeyCode::Numpad1 => Some(1),
+        KeyCode::Key2 | KeyCode::Numpad2 => Some(2),
+        KeyCode::Key3 | KeyCode::Numpad3 => Some(3),
+        KeyCode::Key4 | KeyCode::Numpad4 => Some(4),
+        KeyCode::Key5 | KeyCode::Numpad5 => Some(5),
+        KeyCode::Key6 | KeyCode::Numpad6 => Some(6),
+        KeyCode::Key7 | KeyCode::Numpad7 => Some(7),
+        KeyCode::Key8 | KeyCode::Numpad8 => Some(8),
+        KeyCode::Key9 | KeyCode::Numpad9 => Some(9),
+        _ => None,
+    });
+    if !forward && !backward && digit.is_none() {
+        return;
+    }
+
+    // Counts wrap around between zero and the length of the line.
+    let update_count = |count: usize, max: usize| match digit {
+        Some(digit) => digit.min(max),
+        None if forward => (count + 1) % (max + 1),
+        None => (count + max) % (max + 1),
+    };
+    let puzzle = &mut editor_puzzle.0;
+    match target {
+        EditorTarget::Cell { row, col } => {
+            let cell = puzzle.field[row][col];
+            match digit {
+                // Digits set the number of houses a lake or mountain requires.
+                Some(digit) => puzzle.field[row][col] = cell.with_count(digit),
+                None => puzzle.field[row][col] = next_cell_type(cell, forward),
+            }
+        }
+        EditorTarget::RowCount(row) => {
+            puzzle.row_count[row] = update_count(puzzle.row_count[row], cols);
+        }
+        EditorTarget::ColCount(col) => {
+            puzzle.col_count[col] = update_count(puzzle.col_count[col], rows);
+        }
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<EditorButtonAction>,
+    mut editor_puzzle: ResMut<EditorPuzzle>,
+    mut game_state: ResMut<GameState>,
+    mut custom_level: ResMut<CustomLevel>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            let (rows, cols) = editor_puzzle.0.dims();
+            match *action {
+                EditorButtonAction::Back => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+                EditorButtonAction::AddRow => {
+                    resize_puzzle(&mut editor_puzzle.0, rows + 1, cols);
+                }
+                EditorButtonAction::RemoveRow => {
+                    resize_puzzle(&mut editor_puzzle.0, rows - 1, cols);
+                }
+                EditorButtonAction::AddCol => {
+                    resize_puzzle(&mut editor_puzzle.0, rows, cols + 1);
+                }
+                EditorButtonAction::RemoveCol => {
+                    resize_puzzle(&mut editor_puzzle.0, rows, cols - 1);
+                }
+                EditorButtonAction::Play => {
+                    custom_level.0 = Some(GameLevel {
+                        id: "custom".into(),
+                        name: "Custom".into(),
+                        puzzle: editor_puzzle.0.clone(),
+                        par: None,
+                    });
+                    game_state.current_level = CustomLevel::index();
+                    app_state.set(AppState::SwitchLevel);
+                }
+                EditorButtonAction::Export => {
+                    export_puzzle(&editor_puzzle.0);
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::loading_screen::LOADING_BACKGROUND;
+use crate::ReduceMotion;
+
+pub struct FadePlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for FadePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), start_fade)
+            .add_systems(Update, update_fade);
+    }
+}
+
+// How long the screen takes to fade in.
+const FADE_SECONDS: f32 = 0.4;
+
+// Covers the screen in the loading screen's color and fades away, so entering the
+// state blends in from the loading screen instead of cutting to it.
+#[derive(Component)]
+struct FadeOverlay {
+    timer: Timer,
+}
+
+fn start_fade(
+    mut commands: Commands,
+    reduce_motion: Res<ReduceMotion>,
+    overlay_query: Query<Entity, With<FadeOverlay>>,
+) {
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if reduce_motion.0 {
+        return;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: LOADING_BACKGROUND.into(),
+            // Clicks go through to the screen underneath while it fades in.
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(200),
+            ..default()
+        },
+        FadeOverlay {
+            timer: Timer::from_seconds(FADE_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+fn update_fade(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut overlay_query: Query<(Entity, &mut FadeOverlay, &mut BackgroundColor)>,
+) {
+    for (entity, mut overlay, mut color) in overlay_query.iter_mut() {
+        overlay.timer.tick(time.delta());
+        if overlay.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            color.0.set_a(overlay.timer.percent_left());
+        }
+    }
+}
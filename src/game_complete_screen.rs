@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use crate::lang::Lang;
+use crate::level::all_levels;
+use crate::save::SaveData;
+use crate::stats::Stats;
+use crate::stats_screen::format_seconds;
+use crate::{AppState, ButtonInteractions, GameState};
+
+pub struct GameCompleteScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for GameCompleteScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_game_complete_screen)
+            .add_systems(Update, button_system.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_game_complete_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+
+// Tag component used to tag entities added on the game complete screen.
+#[derive(Component)]
+struct OnGameCompleteScreen;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum GameCompleteButtonAction {
+    PlayAgain,
+    MainMenu,
+}
+
+fn create_game_complete_screen(
+    mut commands: Commands,
+    save_data: Res<SaveData>,
+    stats: Res<Stats>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    let levels = all_levels();
+    let completed = levels
+        .iter()
+        .filter(|level| save_data.is_completed(&level.id))
+        .count();
+    let stars: u32 = levels
+        .iter()
+        .filter_map(|level| save_data.level_stars.get(&level.id))
+        .map(|&stars| stars as u32)
+        .sum();
+    let lines = [
+        lang.t_with(
+            "main_menu.levels_complete",
+            &[
+                ("completed", completed.to_string()),
+                ("total", levels.len().to_string()),
+            ],
+        ),
+        lang.t_with(
+            "game_complete.stars",
+            &[
+                ("stars", stars.to_string()),
+                ("total", (3 * levels.len()).to_string()),
+            ],
+        ),
+        format!("{}: {}", lang.t("stats.houses_placed"), stats.houses_placed),
+        format!(
+            "{}: {}",
+            lang.t("stats.play_time"),
+            format_seconds(stats.play_time)
+        ),
+    ];
+
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                image: UiImage::new(server.load("full.png")),
+                ..default()
+            },
+            OnGameCompleteScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn(
+                TextBundle::from_section(
+                    lang.t("game_complete.title"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 120.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+            builder.spawn(
+                TextBundle::from_section(
+                    lang.t("game_complete.message"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 60.0,
+                        color: Color::BLACK,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+
+            for line in lines {
+                builder.spawn(TextBundle::from_section(
+                    line,
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 50.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                ));
+            }
+
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        margin: UiRect::top(Val::Px(30.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    for (label, action) in [
+                        (
+                            "game_complete.play_again",
+                            GameCompleteButtonAction::PlayAgain,
+                        ),
+                        ("pause.main_menu", GameCompleteButtonAction::MainMenu),
+                    ] {
+                        builder
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(250.0),
+                                        height: Val::Px(65.0),
+                                        margin: UiRect::all(Val::Px(20.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_empty.png")),
+                                    ..default()
+                                },
+                                action,
+                            ))
+                            .with_children(|builder| {
+                                builder.spawn(TextBundle::from_section(
+                                    lang.t(label),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn destroy_game_complete_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<OnGameCompleteScreen>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<GameCompleteButtonAction>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                GameCompleteButtonAction::PlayAgain => {
+                    game_state.current_level = 0;
+                    app_state.set(AppState::SwitchLevel);
+                }
+                GameCompleteButtonAction::MainMenu => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+            };
+        }
+    }
+}
@@ -1,40 +1,111 @@
+use crate::check_report::RequestCheck;
+use crate::confirm_dialog::{no_confirm_dialog, ConfirmAction, DialogConfirmed, ShowConfirmDialog};
+use crate::debug_overlay::DebugCellCounts;
+use crate::hint::{hint_button_label, HintButtonText, RequestHint, RequestStartingLine};
+use crate::lang::Lang;
 use crate::level::*;
+use crate::pause_menu::not_paused;
+use crate::replay::{no_replay_playing, ReplayAction, ReplayRecorder};
+use crate::save::SaveData;
+use crate::solver::solve;
+use crate::theme::Theme;
+use crate::tile_atlas::{spawn_tile, TileAtlas};
 use crate::AppState;
+use crate::ColorblindMode;
 use crate::GameState;
 use crate::GlobalVolumeSettings;
+use crate::ReduceMotion;
+use crate::VolumeBus;
 use crate::VolumeSettings;
-use crate::SKY_COLOR;
 use bevy::audio::PlaybackMode;
 use bevy::audio::Volume;
+use bevy::ecs::query::Has;
+use bevy::ecs::system::SystemParam;
 use bevy::math::Vec2;
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
 use bevy::sprite::*;
+use bevy::utils::Instant;
 use bevy::window::PrimaryWindow;
 use rand::prelude::*;
 use std::default::Default;
+use std::time::Duration;
 
 pub struct GameScreenPlugin<S: States + Copy>(pub S);
 
 impl<S: States + Copy> Plugin for GameScreenPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(self.0), create_game_screen)
+        app.add_event::<LevelCompleted>()
+            .add_event::<FlashLineLabel>()
+            .add_event::<CellInputDenied>()
+            .init_resource::<ShowPlacedCounts>()
+            .init_resource::<PlacementMode>()
+            .init_resource::<StatusPalette>()
+            .init_resource::<PreviousLineStatus>()
+            .init_resource::<LevelStats>()
+            .init_resource::<ValidationTime>()
+            .add_systems(Update, update_status_palette)
+            .add_systems(OnEnter(self.0), (create_game_screen, spawn_sky_background))
             .add_systems(
                 Update,
                 (
                     update_game_screen,
+                    update_board_cursor_render,
+                    update_hover_highlight,
+                    (start_invalid_flash, animate_invalid_flash).chain(),
                     update_placements_render,
-                    update_buildings_required,
-                    update_incorrect_placements,
+                    animate_houses.after(update_placements_render),
                     update_cell_hints,
-                    detect_complete_level,
-                    handle_mouse_input,
-                    button_system,
+                    update_confetti,
+                    update_sky_background,
+                    animate_tiles,
+                    animate_tile_entrance,
+                    handle_mouse_input.run_if(
+                        not_paused
+                            .and_then(no_confirm_dialog)
+                            .and_then(no_replay_playing),
+                    ),
+                    button_system.run_if(not_paused.and_then(no_confirm_dialog)),
+                    leave_level_on_confirm,
+                    start_solution_reveal,
+                    reveal_solution.run_if(not_paused),
+                    update_placement_mode_text,
+                    update_house_kind_text,
+                    update_sound_button,
                     // ui_apply_fixed_z
                 )
                     .run_if(in_state(self.0)),
             )
-            .add_systems(OnExit(self.0), destroy_game_screen);
+            // Systems reading the validation of the current board.
+            .add_systems(
+                Update,
+                (
+                    update_validation.after(handle_mouse_input),
+                    play_line_sounds.after(update_validation),
+                    shake_overflowing_labels
+                        .after(update_validation)
+                        .before(play_line_sounds),
+                    animate_label_shake,
+                    start_label_flash,
+                    animate_label_flash.after(start_label_flash),
+                    update_buildings_required.after(update_validation),
+                    update_solution_status_text.after(update_validation),
+                    update_incorrect_placements.after(update_validation),
+                    pulse_adjacency_violations.after(update_incorrect_placements),
+                    detect_complete_level.after(update_validation),
+                    spawn_confetti.after(detect_complete_level),
+                    update_level_stats
+                        .after(update_validation)
+                        .run_if(not_paused),
+                    show_completion_stars.after(detect_complete_level),
+                    update_par_text.after(update_level_stats),
+                )
+                    .run_if(in_state(self.0)),
+            )
+            .add_systems(
+                OnExit(self.0),
+                (destroy_game_screen, reset_previous_line_status),
+            );
     }
 }
 
@@ -57,31 +128,127 @@ pub struct OnGameScreen;
 #[derive(Component)]
 enum GameScreenButtonAction {
     Back,
+    PreviousLevel,
+    NextLevel,
     ToggleSound,
+    TogglePlacementMode,
+    ToggleHouseKind,
+    Hint,
+    Check,
+    StartingLine,
+    Solve,
     Complete,
 }
 
+// What a primary click or tap on a grass cell does.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementMode {
+    #[default]
+    Place,
+    Mark,
+}
+
+impl PlacementMode {
+    // Translation key of the placement mode button label.
+    fn label_key(self) -> &'static str {
+        match self {
+            PlacementMode::Place => "hud.place",
+            PlacementMode::Mark => "hud.mark",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PlacementModeText;
+
+// Translation key of the house kind button label.
+fn house_kind_label_key(kind: HouseKind) -> &'static str {
+    match kind {
+        HouseKind::Small => "hud.house_small",
+        HouseKind::Large => "hud.house_large",
+    }
+}
+
+#[derive(Component)]
+pub struct HouseKindText;
+
+// How placements that break a rule are treated. Peaceful places the house and flags
+// it, strict refuses to place it.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RuleMode {
+    #[default]
+    Peaceful,
+    Strict,
+}
+
+impl RuleMode {
+    // Translation key of the settings button label.
+    pub fn label_key(self) -> &'static str {
+        match self {
+            RuleMode::Peaceful => "settings.rules_peaceful",
+            RuleMode::Strict => "settings.rules_strict",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            RuleMode::Peaceful => RuleMode::Strict,
+            RuleMode::Strict => RuleMode::Peaceful,
+        }
+    }
+}
+
+// The HUD button that cycles the volume, showing an icon for the current volume.
+#[derive(Component)]
+pub struct SoundButton;
+
 pub const CELL_SIZE: f32 = 150.0;
 
 pub const GRASS_LAYER: f32 = 0.0;
 pub const MARKER_LAYER: f32 = 100.0;
+pub const CURSOR_LAYER: f32 = 150.0;
 pub const CELL_LAYER: f32 = 200.0;
+pub const FLASH_LAYER: f32 = 250.0;
 pub const CROSS_LAYER: f32 = 300.0;
 pub const TEXT_LAYER: f32 = 400.0;
 pub const AXIS_LAYER: f32 = 500.0;
 
-// Update if the size of the field grows beyond 10x10.
-pub const MAX_HOUSE_COUNT: usize = 100;
+// Depth of cell (r, c) within a layer, added to the layer's z:
+//
+//   depth = (r + cols - c + 1) * DEPTH_PER_DIAGONAL + r * DEPTH_PER_ROW
+//
+// r + cols - c grows by one per screen row going down the isometric board, so
+// everything on a cell is drawn behind the cells in front of it (below it on screen)
+// and in front of the ones behind it. Houses and terrain share CELL_LAYER, which is
+// above GRASS_LAYER, so a house covers its own tile but not a mountain in front of it.
+// Cells on the same screen row don't overlap at rest, but a house overshoots its size
+// while appearing, so they are still told apart by r. This holds while boards have
+// fewer than DEPTH_PER_DIAGONAL / DEPTH_PER_ROW rows, and the deepest cell stays well
+// below the 50 between layers.
+//
+// levels/house_depth.txt has a house on every side of a mountain to check this by eye.
+const DEPTH_PER_DIAGONAL: f32 = 0.1;
+const DEPTH_PER_ROW: f32 = 0.001;
+
+pub fn cell_depth(r: usize, c: usize, cols: usize) -> f32 {
+    ((cols - c + 1) + r) as f32 * DEPTH_PER_DIAGONAL + r as f32 * DEPTH_PER_ROW
+}
 
 const NORMAL_BUTTON: Color = Color::WHITE;
 const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
 const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const DISABLED_BUTTON: Color = Color::rgba(0.5, 0.5, 0.5, 0.5);
+
+// A button that ignores presses, such as the previous level arrow on the first level.
+#[derive(Component)]
+struct DisabledButton;
 
 #[derive(Component, Default)]
 pub struct GameScreenRoot {
     random_number: Vec<Vec<u32>>,
 }
 
+// Line under the level name telling which kind of rule is still unmet.
 #[derive(Component)]
 pub struct SolutionStatusText;
 
@@ -101,77 +268,261 @@ pub struct IncorrectPlacement {
     col: usize,
 }
 
+// Line between two orthogonally adjacent cells, shown when both hold houses.
+#[derive(Component)]
+pub struct AdjacencyLink {
+    from: Position,
+    to: Position,
+}
+
+// Resting color of the lake and mountain numbers.
+const CONSTRAINT_TEXT_COLOR: Color = Color::GRAY;
+
+// Colors of row/column counts and lake/mountain numbers that are short of, match or
+// exceed their target.
+#[derive(Resource, Clone, Copy)]
+pub struct StatusPalette {
+    pub underflow: Color,
+    pub matched: Color,
+    pub overflow: Color,
+}
+
+impl StatusPalette {
+    pub const DEFAULT: Self = Self {
+        underflow: Color::WHITE,
+        matched: Color::rgb(0.4, 1.0, 0.3),
+        overflow: Color::rgb(1.0, 0.3, 0.2),
+    };
+
+    // Blue and orange stay distinguishable with red-green color blindness.
+    pub const COLORBLIND: Self = Self {
+        underflow: Color::WHITE,
+        matched: Color::rgb(0.3, 0.6, 1.0),
+        overflow: Color::rgb(1.0, 0.6, 0.1),
+    };
+
+    pub fn for_mode(colorblind_mode: ColorblindMode) -> Self {
+        if colorblind_mode.0 {
+            Self::COLORBLIND
+        } else {
+            Self::DEFAULT
+        }
+    }
+}
+
+impl Default for StatusPalette {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn update_status_palette(colorblind_mode: Res<ColorblindMode>, mut palette: ResMut<StatusPalette>) {
+    if colorblind_mode.is_changed() {
+        *palette = StatusPalette::for_mode(*colorblind_mode);
+    }
+}
+
+const ADJACENCY_COLOR: Color = Color::rgb(1.0, 0.3, 0.2);
+const ADJACENCY_LINK_WIDTH: f32 = 8.0;
+// Pulses per second of the crosses and links over conflicting houses.
+const ADJACENCY_PULSE_RATE: f32 = 1.5;
+
 #[derive(Component)]
 pub struct ConstraintViolationRender {
     row: usize,
     col: usize,
 }
 
+// Tints of the hint markers, cycled through by repeated marking of a cell.
+pub const HINT_COLORS: [Color; 3] = [
+    Color::rgb(1.0, 0.35, 0.3),
+    Color::rgb(0.35, 0.55, 1.0),
+    Color::rgb(0.4, 0.9, 0.35),
+];
+
 #[derive(Component)]
 pub struct CellHint {
     row: usize,
     col: usize,
 }
 
+// A pooled house sprite and the cell it currently shows, if any. A sprite keeps its
+// cell for as long as the house stays placed, so removing another house never
+// moves it mid-animation.
+#[derive(Component, Default)]
+pub struct HouseSprite {
+    position: Option<Position>,
+    kind: HouseKind,
+}
+
+// Large houses are drawn bigger as well as with their own texture.
+const LARGE_HOUSE_SCALE: f32 = 1.2;
+
+pub const HOUSE_ANIMATION_SECONDS: f32 = 0.2;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HouseAnimationKind {
+    Appear,
+    Disappear,
+}
+
+// Progress of the bounce-in or fade-out of a house sprite.
+#[derive(Component)]
+pub struct HouseAnimation {
+    kind: HouseAnimationKind,
+    elapsed: f32,
+}
+
+impl HouseAnimation {
+    fn start(kind: HouseAnimationKind) -> Self {
+        Self { kind, elapsed: 0.0 }
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed / HOUSE_ANIMATION_SECONDS).min(1.0)
+    }
+}
+
+// Terrain sprite that cycles through a few frames, for cell types listed in
+// `Theme::animation_frames`. Other terrain keeps a single static sprite.
+#[derive(Component)]
+pub struct AnimatedTile {
+    timer: Timer,
+    frames: Vec<Handle<Image>>,
+    frame: usize,
+}
+
+pub const TILE_FRAME_SECONDS: f32 = 0.8;
+
+// Rise and fade in of a tile when the board is created. Tiles start one diagonal
+// (r + c) after another, so the board assembles from the left corner to the right.
 #[derive(Component)]
-pub struct HouseIndex {
-    index: usize,
+pub struct TileEntrance {
+    delay: f32,
+    elapsed: f32,
+    // Final y of the tile, where it is left once the entrance is over.
+    target_y: f32,
 }
 
-pub fn get_cell_texture(server: &Res<AssetServer>, cell_type: CellType) -> Handle<Image> {
-    match cell_type {
-        CellType::Grass => server.load("grass_iso_1.png"),
-        CellType::Tree => server.load("forest_iso.png"),
-        CellType::Lake => server.load("lake_iso.png"),
-        CellType::Mountain => server.load("mountain_iso.png"),
+// How long a single tile takes to rise, and how long the whole board takes.
+pub const TILE_ENTRANCE_SECONDS: f32 = 0.25;
+pub const BOARD_ENTRANCE_SECONDS: f32 = 0.5;
+pub const TILE_ENTRANCE_DROP: f32 = CELL_SIZE * 0.25;
+
+impl TileEntrance {
+    fn new(r: usize, c: usize, puzzle: &Puzzle, target_y: f32) -> Self {
+        let (rows, cols) = puzzle.dims();
+        let last_diagonal = (rows + cols - 2).max(1) as f32;
+        Self {
+            delay: (r + c) as f32 / last_diagonal
+                * (BOARD_ENTRANCE_SECONDS - TILE_ENTRANCE_SECONDS),
+            elapsed: 0.0,
+            target_y,
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        ((self.elapsed - self.delay) / TILE_ENTRANCE_SECONDS).clamp(0.0, 1.0)
     }
 }
 
+// Bounding box of every tile sprite, relative to the left corner of cell (0, 0).
+// Tiles are CELL_SIZE squares anchored on their left corner, so the box runs from the
+// left corner of the leftmost cell to the right edge of the rightmost one, and half a
+// tile beyond the lowest and highest left corners. Rows run down to the right and
+// columns up to the right, so a board that is taller than wide reaches further down.
+pub fn board_bounds(puzzle: &Puzzle) -> Rect {
+    let (rows, cols) = puzzle.dims();
+    let (last_row, last_col) = (rows.saturating_sub(1), cols.saturating_sub(1));
+    let top = iso_offset(0, last_col, CELL_SIZE).y;
+    let bottom = iso_offset(last_row, 0, CELL_SIZE).y;
+    let right = iso_offset(last_row, last_col, CELL_SIZE).x;
+    Rect::new(
+        0.0,
+        bottom - CELL_SIZE * 0.5,
+        right + CELL_SIZE,
+        top + CELL_SIZE * 0.5,
+    )
+}
+
+// Size of the isometric board in world units, covering every tile sprite.
+pub fn board_extent(puzzle: &Puzzle) -> Vec2 {
+    board_bounds(puzzle).size()
+}
+
+// Offset of the left corner of cell (r, c) from the left corner of cell (0, 0) in the
+// isometric layout, with y pointing up.
+pub fn iso_offset(r: usize, c: usize, cell_size: f32) -> Vec2 {
+    Vec2::new(
+        (c as f32 + r as f32) * cell_size * 0.5,
+        (c as f32 - r as f32) * cell_size * 0.25,
+    )
+}
+
 pub fn item_cell(
     builder: &mut ChildBuilder,
-    r: usize,
-    c: usize,
+    position: Position,
     puzzle: &Puzzle,
     rid: u32,
+    theme: &Theme,
+    tile_atlas: &TileAtlas,
     server: &Res<AssetServer>,
 ) {
+    let Position { row: r, col: c } = position;
     let (_rows, cols) = puzzle.dims();
     let cell_type = puzzle.field[r][c];
 
-    let z = ((cols - c + 1) + r) as f32 * 0.1;
-
-    let texture = get_cell_texture(&server, cell_type);
+    let z = cell_depth(r, c, cols);
 
-    let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
-    let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
+    let Vec2 { x: ix, y: iy } = iso_offset(r, c, CELL_SIZE);
 
-    let grass_texture = if (r + c) % 2 == 0 {
-        server.load(format!("grass_iso_dark_{rid}.png"))
-    } else {
-        server.load(format!("grass_iso_light_{rid}.png"))
-    };
-    builder.spawn(SpriteBundle {
-        sprite: Sprite {
-            custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
-            anchor: Anchor::CenterLeft,
-            ..Default::default()
-        },
-        transform: Transform::from_xyz(ix, iy, z + GRASS_LAYER),
-        texture: grass_texture,
-        ..Default::default()
-    });
+    spawn_tile(
+        builder,
+        tile_atlas,
+        server,
+        &theme.grass_name(r, c, rid),
+        Vec2::new(CELL_SIZE, CELL_SIZE),
+        Transform::from_xyz(ix, iy, z + GRASS_LAYER),
+    )
+    .insert(TileEntrance::new(r, c, puzzle, iy));
 
     if cell_type != CellType::Grass {
-        builder.spawn(SpriteBundle {
-            sprite: Sprite {
-                custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
-                anchor: Anchor::CenterLeft,
-                ..Default::default()
-            },
-            transform: Transform::from_xyz(ix, iy, z + CELL_LAYER),
-            texture,
-            ..Default::default()
-        });
+        let transform = Transform::from_xyz(ix, iy, z + CELL_LAYER);
+        match theme.animation_frames(server, cell_type) {
+            Some(frames) => {
+                // Neighboring tiles start on different frames so they don't move in sync.
+                let frame = (r + c) % frames.len();
+                builder.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                            anchor: Anchor::CenterLeft,
+                            ..Default::default()
+                        },
+                        transform,
+                        texture: frames[frame].clone(),
+                        ..Default::default()
+                    },
+                    AnimatedTile {
+                        timer: Timer::from_seconds(TILE_FRAME_SECONDS, TimerMode::Repeating),
+                        frames,
+                        frame,
+                    },
+                    TileEntrance::new(r, c, puzzle, iy),
+                ));
+            }
+            None => {
+                spawn_tile(
+                    builder,
+                    tile_atlas,
+                    server,
+                    &theme.cell_texture_name(cell_type),
+                    Vec2::new(CELL_SIZE, CELL_SIZE),
+                    transform,
+                )
+                .insert(TileEntrance::new(r, c, puzzle, iy));
+            }
+        }
     }
 
     builder.spawn((
@@ -182,32 +533,87 @@ pub fn item_cell(
                 ..Default::default()
             },
             transform: Transform::from_xyz(ix, iy, z + CROSS_LAYER),
-            texture: server.load("cross_iso.png"),
+            texture: server.load(theme.cross),
             ..Default::default()
         },
         IncorrectPlacement { row: r, col: c },
     ));
 
-    let constraint_text = match cell_type {
-        CellType::Lake => "3",
-        CellType::Mountain => "2",
-        _ => "",
-    };
+    // Links to the neighbors to the right and below, so every pair gets exactly one.
+    let (rows, cols) = puzzle.dims();
+    for (nr, nc) in [(r, c + 1), (r + 1, c)] {
+        if nr >= rows || nc >= cols {
+            continue;
+        }
+        let delta = iso_offset(nr, nc, CELL_SIZE) - Vec2::new(ix, iy);
+        let nz = cell_depth(nr, nc, cols);
+        builder.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: ADJACENCY_COLOR,
+                    custom_size: Some(Vec2::new(delta.length(), ADJACENCY_LINK_WIDTH)),
+                    ..Default::default()
+                },
+                // Sprites are anchored on the left, so the cell center is half a cell right.
+                transform: Transform::from_xyz(
+                    ix + CELL_SIZE * 0.5 + delta.x * 0.5,
+                    iy + delta.y * 0.5,
+                    z.max(nz) + CROSS_LAYER,
+                )
+                .with_rotation(Quat::from_rotation_z(delta.y.atan2(delta.x))),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            },
+            AdjacencyLink {
+                from: Position { row: r, col: c },
+                to: Position { row: nr, col: nc },
+            },
+        ));
+    }
+
+    let constraint_text = cell_type
+        .required_count()
+        .map(|count| count.to_string())
+        .unwrap_or_default();
     let text_bundle = Text2dBundle {
         text: Text::from_section(
             constraint_text,
             TextStyle {
                 font: server.load("NotoSerif-SemiBold.ttf"),
                 font_size: 32.0,
-                color: Color::GRAY,
-                ..default()
+                color: CONSTRAINT_TEXT_COLOR,
             },
         )
         .with_alignment(TextAlignment::Center),
         transform: Transform::from_xyz(ix + CELL_SIZE * 0.35, iy + CELL_SIZE * 0.3, z + TEXT_LAYER),
         ..default()
     };
-    builder.spawn((text_bundle, ConstraintViolationRender { row: r, col: c }));
+    builder.spawn((
+        text_bundle,
+        ConstraintViolationRender { row: r, col: c },
+        // Labels only rise, their color belongs to update_buildings_required.
+        TileEntrance::new(r, c, puzzle, iy + CELL_SIZE * 0.3),
+    ));
+
+    if cell_type == CellType::Grass {
+        builder.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: server.load("NotoSerif-SemiBold.ttf"),
+                        font_size: 16.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(ix + CELL_SIZE * 0.5, iy, z + TEXT_LAYER),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            DebugCellCounts(Position { row: r, col: c }),
+        ));
+    }
 
     builder.spawn((
         SpriteBundle {
@@ -217,7 +623,7 @@ pub fn item_cell(
                 ..Default::default()
             },
             transform: Transform::from_xyz(ix, iy, z + MARKER_LAYER),
-            texture: server.load(format!("marker_iso_{rid}.png")),
+            texture: theme.marker(server, rid),
             visibility: Visibility::Hidden,
             ..Default::default()
         },
@@ -225,6 +631,16 @@ pub fn item_cell(
     ));
 }
 
+// Where the count of `row` is drawn: past the end of the row, after the last column.
+pub fn row_label_position(row: usize, cols: usize) -> Vec2 {
+    iso_offset(row, cols, CELL_SIZE) + Vec2::new(0.35, 0.05) * CELL_SIZE
+}
+
+// Where the count of `col` is drawn: past the start of the column, before the first row.
+pub fn col_label_position(col: usize) -> Vec2 {
+    iso_offset(0, col, CELL_SIZE) + Vec2::new(0.15, 0.3) * CELL_SIZE
+}
+
 pub fn item_number_constraints(
     builder: &mut ChildBuilder,
     puzzle: &Puzzle,
@@ -236,43 +652,81 @@ pub fn item_number_constraints(
         font: server.load("NotoSerif-SemiBold.ttf"),
         font_size: 40.0,
         color: Color::WHITE,
-        ..default()
     };
 
     for r in 0..rows {
-        let c = cols;
-        let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
-        let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
-
         let text_bundle = Text2dBundle {
             text: Text::from_section(puzzle.row_count[r].to_string(), text_style.clone())
                 .with_alignment(TextAlignment::Center),
-            transform: Transform::from_xyz(
-                ix + 0.35 * CELL_SIZE,
-                iy + 0.05 * CELL_SIZE,
-                AXIS_LAYER,
-            ),
+            transform: Transform::from_translation(row_label_position(r, cols).extend(AXIS_LAYER)),
             ..default()
         };
         builder.spawn((text_bundle, RowBuildingsRequired { row: r }));
     }
 
     for c in 0..cols {
-        let r = 0;
-        let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
-        let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
-
         let text_bundle = Text2dBundle {
             text: Text::from_section(puzzle.col_count[c].to_string(), text_style.clone())
                 .with_alignment(TextAlignment::Center),
-            transform: Transform::from_xyz(ix + 0.15 * CELL_SIZE, iy + 0.3 * CELL_SIZE, AXIS_LAYER),
+            transform: Transform::from_translation(col_label_position(c).extend(AXIS_LAYER)),
             ..default()
         };
         builder.spawn((text_bundle, ColBuildingsRequired { col: c }));
     }
 }
 
-pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>) {
+fn item_level_arrow(
+    builder: &mut ChildBuilder,
+    label: &str,
+    action: GameScreenButtonAction,
+    enabled: bool,
+    server: &Res<AssetServer>,
+) {
+    let mut button = builder.spawn((
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(50.0),
+                height: Val::Px(50.0),
+                margin: UiRect::horizontal(Val::Px(20.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: if enabled {
+                NORMAL_BUTTON.into()
+            } else {
+                DISABLED_BUTTON.into()
+            },
+            image: UiImage::new(server.load("UI/button_empty.png")),
+            ..default()
+        },
+        action,
+    ));
+    button.with_children(|builder| {
+        builder.spawn(TextBundle::from_section(
+            label,
+            TextStyle {
+                font: server.load(crate::TEXT_FONT_NAME),
+                font_size: 50.0,
+                color: Color::WHITE,
+            },
+        ));
+    });
+    if !enabled {
+        button.insert(DisabledButton);
+    }
+}
+
+pub fn create_hud(
+    commands: &mut Commands,
+    game_state: &GameState,
+    save_data: &SaveData,
+    placement_mode: PlacementMode,
+    solvable: bool,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    let current_level = game_state.current_level;
     commands
         .spawn((
             NodeBundle {
@@ -284,8 +738,6 @@ pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>
                     justify_content: JustifyContent::SpaceBetween,
                     ..default()
                 },
-                // image: UiImage::new(server.load("full.png")),
-                // background_color: BackgroundColor(SKY_COLOR),
                 ..default()
             },
             OnGameScreen,
@@ -320,32 +772,245 @@ pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>
                         GameScreenButtonAction::Back,
                     ));
 
-                    builder.spawn(TextBundle::from_section(
-                        name,
-                        TextStyle {
-                            font: server.load(crate::TEXT_FONT_NAME),
-                            font_size: 80.0,
-                            color: crate::CUSTOM_ORANGE,
+                    // The arrows do what the Left and Right keys do.
+                    builder
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
                             ..default()
-                        },
-                    ));
-                    builder.spawn((
-                        ButtonBundle {
+                        })
+                        .with_children(|builder| {
+                            item_level_arrow(
+                                builder,
+                                "<",
+                                GameScreenButtonAction::PreviousLevel,
+                                save_data.previous_level(current_level).is_some(),
+                                server,
+                            );
+                            builder.spawn(TextBundle::from_section(
+                                lang.level_name(&game_state.id, &game_state.name),
+                                TextStyle {
+                                    font: server.load(crate::TEXT_FONT_NAME),
+                                    font_size: 80.0,
+                                    color: crate::CUSTOM_ORANGE,
+                                },
+                            ));
+                            item_level_arrow(
+                                builder,
+                                ">",
+                                GameScreenButtonAction::NextLevel,
+                                save_data.next_level(current_level).is_some(),
+                                server,
+                            );
+                        });
+                    builder
+                        .spawn(NodeBundle {
                             style: Style {
-                                width: Val::Px(50.0),
-                                height: Val::Px(50.0),
-                                margin: UiRect::all(Val::Px(20.0)),
-                                align_self: AlignSelf::Center,
+                                flex_direction: FlexDirection::Row,
+                                align_items: AlignItems::Center,
                                 ..default()
                             },
-                            background_color: NORMAL_BUTTON.into(),
-                            image: UiImage::new(server.load("UI/button_snd_low.png")),
                             ..default()
-                        },
-                        GameScreenButtonAction::ToggleSound,
-                    ));
+                        })
+                        .with_children(|builder| {
+                            builder
+                                .spawn((
+                                    ButtonBundle {
+                                        style: Style {
+                                            width: Val::Px(120.0),
+                                            height: Val::Px(50.0),
+                                            margin: UiRect::right(Val::Px(20.0)),
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            ..default()
+                                        },
+                                        background_color: NORMAL_BUTTON.into(),
+                                        image: UiImage::new(server.load("UI/button_empty.png")),
+                                        ..default()
+                                    },
+                                    GameScreenButtonAction::Hint,
+                                ))
+                                .with_children(|builder| {
+                                    builder.spawn((
+                                        TextBundle::from_section(
+                                            hint_button_label(lang, game_state.hints_remaining),
+                                            TextStyle {
+                                                font: server.load(crate::TEXT_FONT_NAME),
+                                                font_size: 40.0,
+                                                color: Color::WHITE,
+                                            },
+                                        ),
+                                        HintButtonText,
+                                    ));
+                                });
+                            let mut solve_button = builder.spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(120.0),
+                                        height: Val::Px(50.0),
+                                        margin: UiRect::right(Val::Px(20.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: if solvable {
+                                        NORMAL_BUTTON.into()
+                                    } else {
+                                        DISABLED_BUTTON.into()
+                                    },
+                                    image: UiImage::new(server.load("UI/button_empty.png")),
+                                    ..default()
+                                },
+                                GameScreenButtonAction::Solve,
+                            ));
+                            solve_button.with_children(|builder| {
+                                builder.spawn(TextBundle::from_section(
+                                    lang.t("hud.solve"),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 40.0,
+                                        color: Color::WHITE,
+                                    },
+                                ));
+                            });
+                            if !solvable {
+                                solve_button.insert(DisabledButton);
+                            }
+                            builder
+                                .spawn((
+                                    ButtonBundle {
+                                        style: Style {
+                                            width: Val::Px(120.0),
+                                            height: Val::Px(50.0),
+                                            justify_content: JustifyContent::Center,
+                                            align_items: AlignItems::Center,
+                                            ..default()
+                                        },
+                                        background_color: NORMAL_BUTTON.into(),
+                                        image: UiImage::new(server.load("UI/button_empty.png")),
+                                        ..default()
+                                    },
+                                    GameScreenButtonAction::TogglePlacementMode,
+                                ))
+                                .with_children(|builder| {
+                                    builder.spawn((
+                                        TextBundle::from_section(
+                                            lang.t(placement_mode.label_key()),
+                                            TextStyle {
+                                                font: server.load(crate::TEXT_FONT_NAME),
+                                                font_size: 40.0,
+                                                color: Color::WHITE,
+                                            },
+                                        ),
+                                        PlacementModeText,
+                                    ));
+                                });
+                            builder.spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(50.0),
+                                        height: Val::Px(50.0),
+                                        margin: UiRect::all(Val::Px(20.0)),
+                                        align_self: AlignSelf::Center,
+                                        ..default()
+                                    },
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_snd_low.png")),
+                                    ..default()
+                                },
+                                GameScreenButtonAction::ToggleSound,
+                                SoundButton,
+                            ));
+                        });
+                });
+
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 40.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                ),
+                SolutionStatusText,
+            ));
+
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    let mut actions = vec![
+                        ("hud.check", GameScreenButtonAction::Check),
+                        ("hud.start", GameScreenButtonAction::StartingLine),
+                    ];
+                    // Only puzzles with the house kinds rule let the player pick one.
+                    if game_state.puzzle.house_kinds {
+                        actions.push((
+                            house_kind_label_key(game_state.house_kind),
+                            GameScreenButtonAction::ToggleHouseKind,
+                        ));
+                    }
+                    for (label, action) in actions {
+                        let is_house_kind =
+                            matches!(action, GameScreenButtonAction::ToggleHouseKind);
+                        builder
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(120.0),
+                                        height: Val::Px(50.0),
+                                        margin: UiRect::horizontal(Val::Px(10.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_empty.png")),
+                                    ..default()
+                                },
+                                action,
+                            ))
+                            .with_children(|builder| {
+                                let mut text = builder.spawn(TextBundle::from_section(
+                                    lang.t(label),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 40.0,
+                                        color: Color::WHITE,
+                                    },
+                                ));
+                                if is_house_kind {
+                                    text.insert(HouseKindText);
+                                }
+                            });
+                    }
                 });
 
+            // Levels without a par leave out the move counter.
+            if let Some(par) = game_state.par {
+                builder.spawn((
+                    TextBundle::from_section(
+                        par_text(lang, 0, par, false),
+                        TextStyle {
+                            font: server.load(crate::TEXT_FONT_NAME),
+                            font_size: 32.0,
+                            color: Color::WHITE,
+                        },
+                    ),
+                    ParText,
+                ));
+            }
+
             builder.spawn((
                 ButtonBundle {
                     style: Style {
@@ -361,52 +1026,245 @@ pub fn create_hud(commands: &mut Commands, name: &str, server: &Res<AssetServer>
                 CompleteBanner,
                 GameScreenButtonAction::Complete,
             ));
+
+            builder.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "",
+                        TextStyle {
+                            font: server.load(crate::TEXT_FONT_NAME),
+                            font_size: 80.0,
+                            color: crate::CUSTOM_ORANGE,
+                        },
+                    ),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                CompletionStars,
+            ));
         });
 }
 
 #[derive(Component)]
 pub struct CompleteBanner;
 
-pub fn create_game_screen(
-    mut commands: Commands,
-    game_state: Res<GameState>,
-    server: Res<AssetServer>,
-) {
-    let game_screen_entity = commands.spawn(SpatialBundle::default()).id();
-    // This component is added to the entity in the end of this function.
-    let mut game_screen_root = GameScreenRoot::default();
+// Live move counter next to the level's par.
+#[derive(Component)]
+struct ParText;
 
-    create_hud(&mut commands, &game_state.name, &server);
+fn par_text(lang: &Lang, moves: usize, par: usize, finished_within_par: bool) -> String {
+    let key = if finished_within_par {
+        "hud.par_met"
+    } else {
+        "hud.par"
+    };
+    lang.t_with(
+        key,
+        &[("moves", moves.to_string()), ("par", par.to_string())],
+    )
+}
 
-    let puzzle = &game_state.puzzle;
-    let (rows, cols) = puzzle.dims();
-    let mut rng = StdRng::seed_from_u64(game_state.current_level as u64);
-    game_screen_root.random_number = vec![vec![0; cols]; rows];
-    for r in 0..rows {
-        for c in 0..cols {
-            game_screen_root.random_number[r][c] = rng.gen();
-        }
-    }
+// How long the solution takes to appear on the board after asking for it.
+pub const SOLUTION_REVEAL_SECONDS: f32 = 1.0;
+
+// The solver's solution for the current level, and how much of it has been put on
+// the board since the player asked for it.
+#[derive(Resource, Default)]
+pub struct SolutionReveal {
+    solution: Option<Solution>,
+    // Time since the reveal started, or None while the player is still solving.
+    elapsed: Option<f32>,
+    revealed: usize,
+}
 
-    // assert_eq!(rows, cols);
-    for r in 0..rows {
-        for c in 0..cols {
-            let rid = game_screen_root.random_number[r][c] % 3 + 1;
-            commands
-                .entity(game_screen_entity)
-                .with_children(|builder| {
-                    item_cell(builder, r, c, &puzzle, rid, &server);
-                });
-        }
+// Star rating shown under the banner once the level is complete.
+#[derive(Component)]
+pub struct CompletionStars;
+
+// Moves and time spent on the current attempt at the level, for its star rating.
+#[derive(Resource, Default)]
+pub struct LevelStats {
+    // Houses placed and removed.
+    pub moves: usize,
+    pub elapsed: Duration,
+    // Set once the solution was revealed, which rules out stars.
+    pub assisted: bool,
+    // Placements that put a house next to another one.
+    pub mistakes: usize,
+    // Hints asked for, each costing a star.
+    pub hints_used: usize,
+    // Houses put on the board, counting ones removed again.
+    pub houses_placed: usize,
+    // Houses on the board when last counted, to notice placements and removals.
+    placed: usize,
+    // Houses next to another one when last counted.
+    violations: usize,
+}
+
+impl LevelStats {
+    // None for levels solved with help. Hints cost a star each, down to one star.
+    pub fn stars(&self, puzzle: &Puzzle) -> Option<u8> {
+        (!self.assisted).then(|| {
+            let stars = star_rating(self.moves, self.elapsed, puzzle);
+            stars.saturating_sub(self.hints_used as u8).max(1)
+        })
     }
+}
 
-    commands
+// Filled and empty stars for a rating out of 3.
+pub fn star_text(stars: u8) -> String {
+    (0..3)
+        .map(|star| if star < stars { '★' } else { '☆' })
+        .collect()
+}
+
+const CURSOR_COLOR: Color = Color::rgba(1.0, 1.0, 0.5, 0.6);
+
+// The cell selected by gamepad input. It is only shown once a gamepad was used.
+#[derive(Resource)]
+pub struct BoardCursor {
+    pub position: Position,
+    pub visible: bool,
+}
+
+impl Default for BoardCursor {
+    fn default() -> Self {
+        Self {
+            position: Position { row: 0, col: 0 },
+            visible: false,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct BoardCursorSprite;
+
+const HOVER_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.35);
+
+// Marks the cell a click would hit, since that is hard to tell on the diamond grid.
+#[derive(Component)]
+pub struct HoverHighlight;
+
+const INVALID_FLASH_COLOR: Color = Color::rgba(1.0, 0.15, 0.1, 0.6);
+const INVALID_FLASH_SECONDS: f32 = 0.35;
+
+// Red overlay flashed on a cell that a click could do nothing with, fading out as
+// its timer runs down.
+#[derive(Component)]
+pub struct InvalidFlash {
+    timer: Timer,
+}
+
+// Sent when input on a cell was refused: it holds no grass, or strict rules turned
+// the house down.
+#[derive(Event)]
+pub struct CellInputDenied(pub Position);
+
+// Gradient filling the window behind the board.
+#[derive(Component)]
+pub struct SkyBackground;
+
+const SKY_BOTTOM_COLOR: Color = Color::rgb(0.95, 0.97, 1.0);
+// The 2D camera only sees down to z = -0.1, and the lowest tile sits at 0.1.
+const SKY_LAYER: f32 = -0.05;
+
+fn sky_mesh() -> Mesh {
+    let mut mesh = Mesh::from(shape::Quad::new(Vec2::ONE));
+    // Quad vertices go bottom left, top left, top right, bottom right.
+    let top = crate::SKY_COLOR.as_linear_rgba_f32();
+    let bottom = SKY_BOTTOM_COLOR.as_linear_rgba_f32();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![bottom, top, top, bottom]);
+    mesh
+}
+
+// The level on the board and the progress that decides which levels it leads to.
+#[derive(SystemParam)]
+pub struct PlayedLevel<'w> {
+    game_state: Res<'w, GameState>,
+    save_data: Res<'w, SaveData>,
+}
+
+pub fn create_game_screen(
+    mut commands: Commands,
+    played_level: PlayedLevel,
+    placement_mode: Res<PlacementMode>,
+    theme: Res<Theme>,
+    tile_atlas: Res<TileAtlas>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    let PlayedLevel {
+        game_state,
+        save_data,
+    } = played_level;
+    let game_screen_entity = commands.spawn(SpatialBundle::default()).id();
+    // This component is added to the entity in the end of this function.
+    let mut game_screen_root = GameScreenRoot::default();
+
+    let solution = solve(&game_state.puzzle);
+    create_hud(
+        &mut commands,
+        &game_state,
+        &save_data,
+        *placement_mode,
+        solution.is_some(),
+        &lang,
+        &server,
+    );
+    commands.insert_resource(SolutionReveal {
+        solution,
+        ..default()
+    });
+    commands.insert_resource(CurrentValidation(validate_solution(
+        &game_state.solution,
+        &game_state.puzzle,
+    )));
+    commands.insert_resource(LevelStats {
+        placed: game_state.solution.placements.len(),
+        violations: validate_solution(&game_state.solution, &game_state.puzzle)
+            .placement_violations
+            .len(),
+        ..default()
+    });
+
+    let puzzle = &game_state.puzzle;
+    let (rows, cols) = puzzle.dims();
+    let mut rng = StdRng::seed_from_u64(game_state.current_level as u64);
+    game_screen_root.random_number = vec![vec![0; cols]; rows];
+    for r in 0..rows {
+        for c in 0..cols {
+            game_screen_root.random_number[r][c] = rng.gen();
+        }
+    }
+
+    for r in 0..rows {
+        for c in 0..cols {
+            let rid = game_screen_root.random_number[r][c] % 3 + 1;
+            commands
+                .entity(game_screen_entity)
+                .with_children(|builder| {
+                    item_cell(
+                        builder,
+                        Position { row: r, col: c },
+                        puzzle,
+                        rid,
+                        &theme,
+                        &tile_atlas,
+                        &server,
+                    );
+                });
+        }
+    }
+
+    commands
         .entity(game_screen_entity)
         .with_children(|builder| {
-            for index in 0..MAX_HOUSE_COUNT {
+            // A sprite only ever shows the house of a single cell, so one per cell is
+            // enough even while removed houses fade out.
+            for _ in 0..rows * cols {
                 builder.spawn((
                     SpriteBundle {
-                        texture: server.load("house_iso.png"),
+                        texture: server.load(theme.house),
                         sprite: Sprite {
                             custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
                             anchor: Anchor::CenterLeft,
@@ -415,7 +1273,8 @@ pub fn create_game_screen(
                         visibility: Visibility::Hidden,
                         ..Default::default()
                     },
-                    HouseIndex { index },
+                    HouseSprite::default(),
+                    HouseAnimation::start(HouseAnimationKind::Disappear),
                 ));
             }
         });
@@ -423,23 +1282,78 @@ pub fn create_game_screen(
     commands
         .entity(game_screen_entity)
         .with_children(|builder| {
-            item_number_constraints(builder, &puzzle, &server);
+            item_number_constraints(builder, puzzle, &server);
         });
 
+    commands
+        .entity(game_screen_entity)
+        .with_children(|builder| {
+            builder.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: CURSOR_COLOR,
+                        custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                        anchor: Anchor::CenterLeft,
+                        ..Default::default()
+                    },
+                    texture: theme.cell_texture(&server, CellType::Grass),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                BoardCursorSprite,
+            ));
+            builder.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: HOVER_COLOR,
+                        custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                        anchor: Anchor::CenterLeft,
+                        ..Default::default()
+                    },
+                    texture: theme.cell_texture(&server, CellType::Grass),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                HoverHighlight,
+            ));
+            builder.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: INVALID_FLASH_COLOR,
+                        custom_size: Some(Vec2::new(CELL_SIZE, CELL_SIZE)),
+                        anchor: Anchor::CenterLeft,
+                        ..Default::default()
+                    },
+                    texture: theme.cell_texture(&server, CellType::Grass),
+                    visibility: Visibility::Hidden,
+                    ..Default::default()
+                },
+                InvalidFlash {
+                    timer: Timer::from_seconds(INVALID_FLASH_SECONDS, TimerMode::Once),
+                },
+            ));
+        });
+    commands.insert_resource(BoardCursor::default());
+
     commands.entity(game_screen_entity).insert(game_screen_root);
+}
 
-    // let ambient_id = commands.spawn((
-    //     AudioBundle {
-    //         source: server.load("ambient.mp3"),
-    //         settings: PlaybackSettings {
-    //             mode: PlaybackMode::Loop,
-    //             ..default()
-    //         },
-    //         ..default()
-    //     },
-    //     VolumeSettings { volume: 0.1 },
-    // )).id();
-    // commands.entity(game_screen_entity).add_child(ambient_id);
+// Sized and moved with the camera by update_sky_background.
+fn spawn_sky_background(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: meshes.add(sky_mesh()).into(),
+            material: materials.add(ColorMaterial::default()),
+            transform: Transform::from_xyz(0.0, 0.0, SKY_LAYER),
+            ..default()
+        },
+        SkyBackground,
+        OnGameScreen,
+    ));
 }
 
 pub fn destroy_game_screen(
@@ -458,42 +1372,600 @@ pub fn destroy_game_screen(
     }
 }
 
+// Translation of the board root that centers the board's bounding box on the origin,
+// both across and up and down.
+pub fn board_translation(puzzle: &Puzzle) -> Vec3 {
+    (-board_bounds(puzzle).center()).extend(0.0)
+}
+
+// Converts a world position to fractional (col, row) coordinates on a board whose root
+// has the given translation.
+pub fn world_to_board(world: Vec2, board_translation: Vec3) -> Vec2 {
+    let pi = world - board_translation.xy();
+    let po = Vec2::new(pi.x + 2.0 * pi.y, pi.x - 2.0 * pi.y);
+    po / CELL_SIZE
+}
+
 pub fn update_game_screen(
     game_state: Res<GameState>,
     mut game_screen_query: Query<(Entity, &GameScreenRoot, &mut Transform)>,
 ) {
     let (_, _, mut transform) = game_screen_query.single_mut();
-    let puzzle = &game_state.puzzle;
-    let (rows, cols) = puzzle.dims();
-    let (puzzle_width, _puzzle_height) = (cols as f32 * CELL_SIZE, rows as f32 * CELL_SIZE);
-    transform.translation = Vec3::new(-puzzle_width / 2.0, 0.0, 0.0);
+    transform.translation = board_translation(&game_state.puzzle);
+}
+
+pub fn update_board_cursor_render(
+    game_state: Res<GameState>,
+    cursor: Res<BoardCursor>,
+    mut cursor_query: Query<(&mut Transform, &mut Visibility), With<BoardCursorSprite>>,
+) {
+    let (_rows, cols) = game_state.puzzle.dims();
+    let (mut transform, mut visibility) = cursor_query.single_mut();
+    if !cursor.visible {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *transform = cursor_transform(cursor.position, cols);
+    *visibility = Visibility::Inherited;
+}
+
+// Places a cell-sized overlay on top of the cell at `position`.
+fn cursor_transform(position: Position, cols: usize) -> Transform {
+    let (c, r) = (position.col, position.row);
+    let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
+    let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
+
+    let z = cell_depth(r, c, cols);
+
+    Transform::from_xyz(ix, iy, z + CURSOR_LAYER)
+}
+
+// Moves the highlight to the cell under the mouse, using the same mapping as
+// handle_mouse_input, and hides it off the board.
+fn update_hover_highlight(
+    game_state: Res<GameState>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    game_screen_query: Query<&Transform, (With<GameScreenRoot>, Without<HoverHighlight>)>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<HoverHighlight>>,
+) {
+    let Ok((mut transform, mut visibility)) = highlight_query.get_single_mut() else {
+        return;
+    };
+    let game_screen_transform = game_screen_query.single();
+    let (camera, camera_global_transform) = camera_query.single();
+    let window = window_query.single();
+    let (rows, cols) = game_state.puzzle.dims();
+
+    let hovered = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_global_transform, cursor))
+        .map(|world| world_to_board(world, game_screen_transform.translation))
+        .filter(|p| {
+            p.cmpge(Vec2::ZERO).all() && p.cmplt(Vec2::new(cols as f32, rows as f32)).all()
+        });
+    let Some(p) = hovered else {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+    let position = Position {
+        row: p.y as usize,
+        col: p.x as usize,
+    };
+    let target = cursor_transform(position, cols);
+    if *transform != target {
+        *transform = target;
+    }
+    *visibility = Visibility::Inherited;
+}
+
+fn start_invalid_flash(
+    game_state: Res<GameState>,
+    mut denied: EventReader<CellInputDenied>,
+    mut flash_query: Query<(&mut InvalidFlash, &mut Transform, &mut Visibility)>,
+) {
+    let Some(CellInputDenied(position)) = denied.iter().last() else {
+        return;
+    };
+    let (_rows, cols) = game_state.puzzle.dims();
+    for (mut flash, mut transform, mut visibility) in flash_query.iter_mut() {
+        flash.timer.reset();
+        *transform = cursor_transform(*position, cols);
+        // Drawn over the terrain, which is what the click landed on.
+        transform.translation.z += FLASH_LAYER - CURSOR_LAYER;
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn animate_invalid_flash(
+    time: Res<Time>,
+    mut flash_query: Query<(&mut InvalidFlash, &mut Sprite, &mut Visibility)>,
+) {
+    for (mut flash, mut sprite, mut visibility) in flash_query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        sprite
+            .color
+            .set_a(INVALID_FLASH_COLOR.a() * flash.timer.percent_left());
+    }
 }
 
 pub fn update_placements_render(
     game_state: Res<GameState>,
-    mut houses_query: Query<(&mut Transform, &mut Visibility, &HouseIndex)>,
+    theme: Res<Theme>,
+    server: Res<AssetServer>,
+    mut houses_query: Query<(
+        &mut Transform,
+        &mut Visibility,
+        &mut Handle<Image>,
+        &mut HouseSprite,
+        &mut HouseAnimation,
+    )>,
 ) {
     let (_rows, cols) = game_state.puzzle.dims();
-    for (mut transform, mut visibility, house_index) in houses_query.iter_mut() {
-        if house_index.index < game_state.solution.placements.len() {
-            let position = game_state.solution.placements[house_index.index].position;
-            *visibility = Visibility::Inherited;
+    let placements = &game_state.solution.placements;
+    // Placing a house of the other kind gives it a sprite of its own.
+    let is_placed = |position: Position, kind: HouseKind| {
+        placements
+            .iter()
+            .any(|x| x.position == position && x.kind == kind)
+    };
+
+    // Fade out sprites whose house was removed, and bring back ones placed again
+    // while still fading.
+    let mut shown = Vec::new();
+    for (_, _, _, sprite, mut animation) in houses_query.iter_mut() {
+        let Some(position) = sprite.position else {
+            continue;
+        };
+        let kind = if is_placed(position, sprite.kind) {
+            shown.push(position);
+            HouseAnimationKind::Appear
+        } else {
+            HouseAnimationKind::Disappear
+        };
+        if animation.kind != kind {
+            *animation = HouseAnimation::start(kind);
+        }
+    }
 
-            let (c, r) = (position.col, position.row);
-            let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
-            let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
+    // Assign free sprites to newly placed houses.
+    let mut free_sprites = houses_query
+        .iter_mut()
+        .filter(|(_, _, _, sprite, _)| sprite.position.is_none());
+    for placement in placements {
+        let position = placement.position;
+        if shown.contains(&position) {
+            continue;
+        }
+        // The pool has a sprite per cell, so there is always a free one.
+        let Some((mut transform, mut visibility, mut texture, mut sprite, mut animation)) =
+            free_sprites.next()
+        else {
+            break;
+        };
+        let (c, r) = (position.col, position.row);
+        let ix = (c as f32 + r as f32) * CELL_SIZE * 0.5;
+        let iy = (c as f32 - r as f32) * CELL_SIZE * 0.25;
 
-            let z = ((cols - c + 1) + r) as f32 * 0.1;
+        let z = cell_depth(r, c, cols);
+
+        *transform = Transform::from_xyz(ix, iy, z + CELL_LAYER);
+        *visibility = Visibility::Inherited;
+        if sprite.kind != placement.kind {
+            sprite.kind = placement.kind;
+            *texture = theme.house_texture(&server, placement.kind);
+        }
+        sprite.position = Some(position);
+        *animation = HouseAnimation::start(HouseAnimationKind::Appear);
+        shown.push(position);
+    }
+}
 
-            *transform = Transform::from_xyz(ix, iy, z + CELL_LAYER);
+pub fn animate_houses(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut houses_query: Query<(
+        &mut Transform,
+        &mut Visibility,
+        &mut Sprite,
+        &mut HouseSprite,
+        &mut HouseAnimation,
+    )>,
+) {
+    for (mut transform, mut visibility, mut sprite, mut house, mut animation) in
+        houses_query.iter_mut()
+    {
+        let Some(position) = house.position else {
+            continue;
+        };
+        animation.elapsed += time.delta_seconds();
+        let t = if reduce_motion.0 {
+            1.0
         } else {
+            animation.progress()
+        };
+        let (scale, alpha) = match animation.kind {
+            // Ease-out-back: overshoots slightly before settling at full size.
+            HouseAnimationKind::Appear => {
+                let (c1, c3) = (1.70158, 2.70158);
+                (1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2), 1.0)
+            }
+            HouseAnimationKind::Disappear => (1.0, 1.0 - t),
+        };
+        let scale = match house.kind {
+            HouseKind::Small => scale,
+            HouseKind::Large => scale * LARGE_HOUSE_SCALE,
+        };
+        // The sprite is anchored at its left edge, so shift it to scale around its center.
+        let ix = (position.col as f32 + position.row as f32) * CELL_SIZE * 0.5;
+        transform.scale = Vec3::new(scale, scale, 1.0);
+        transform.translation.x = ix + CELL_SIZE * 0.5 * (1.0 - scale);
+        sprite.color.set_a(alpha);
+
+        if animation.kind == HouseAnimationKind::Disappear && t >= 1.0 {
+            house.position = None;
             *visibility = Visibility::Hidden;
         }
     }
 }
 
+// Validation of the current solution, recomputed only when the game state changes.
+#[derive(Resource)]
+pub struct CurrentValidation(pub ValidationResult);
+
+// How long the last validation took, shown by the debug overlay.
+#[derive(Resource, Default)]
+pub struct ValidationTime(pub Duration);
+
+fn update_validation(
+    game_state: Res<GameState>,
+    mut validation: ResMut<CurrentValidation>,
+    mut validation_time: ResMut<ValidationTime>,
+) {
+    if game_state.is_changed() {
+        let start = Instant::now();
+        validation.0 = validate_solution(&game_state.solution, &game_state.puzzle);
+        validation_time.0 = start.elapsed();
+    }
+}
+
+// Lang key describing the first kind of rule the board doesn't meet yet, checking
+// the line counts first, then adjacency, then lakes and mountains.
+pub fn solution_status(validation: &ValidationResult, puzzle: &Puzzle) -> &'static str {
+    let lines = validation.row_status.iter().chain(&validation.col_status);
+    if lines.clone().any(|&status| status == LineStatus::Overflow) {
+        return "status.line_over";
+    }
+    if lines.clone().any(|&status| status == LineStatus::Underflow) {
+        return "status.line_short";
+    }
+    if let Some(violation) = validation.placement_violations.first() {
+        return match violation.violation {
+            ViolationType::AdjacentHouse => "status.houses_share_side",
+            ViolationType::DiagonalHouse => "status.houses_touch_diagonally",
+            ViolationType::KnightHouse => "status.houses_knight_apart",
+        };
+    }
+    for violation in &validation.constraint_violations {
+        let position = violation.position;
+        let cell_type = puzzle.field[position.row][position.col];
+        let status = match (cell_type, violation.violation) {
+            (_, ConstraintViolationType::Match) => continue,
+            (CellType::Lake(_), ConstraintViolationType::Underflow) => "status.lake_short",
+            (CellType::Lake(_), ConstraintViolationType::Overflow) => "status.lake_over",
+            (_, ConstraintViolationType::Underflow) => "status.mountain_short",
+            (_, ConstraintViolationType::Overflow) => "status.mountain_over",
+        };
+        return status;
+    }
+    "status.all_met"
+}
+
+// Counts house placements and removals, mistakes, and the time spent until the level
+// is complete.
+fn update_level_stats(
+    time: Res<Time>,
+    game_state: Res<GameState>,
+    validation: Res<CurrentValidation>,
+    mut level_stats: ResMut<LevelStats>,
+) {
+    if game_state.is_changed() {
+        let placed = game_state.solution.placements.len();
+        let violations = validation.0.placement_violations.len();
+        level_stats.moves += placed.abs_diff(level_stats.placed);
+        level_stats.houses_placed += placed.saturating_sub(level_stats.placed);
+        if placed > level_stats.placed && violations > level_stats.violations {
+            level_stats.mistakes += 1;
+        }
+        level_stats.placed = placed;
+        level_stats.violations = violations;
+    }
+    if !validation.0.complete {
+        level_stats.elapsed += time.delta();
+    }
+}
+
+fn show_completion_stars(
+    mut level_completed: EventReader<LevelCompleted>,
+    game_state: Res<GameState>,
+    level_stats: Res<LevelStats>,
+    mut stars_query: Query<(&mut Text, &mut Visibility), With<CompletionStars>>,
+) {
+    if level_completed.iter().count() == 0 {
+        return;
+    }
+    let Some(stars) = level_stats.stars(&game_state.puzzle) else {
+        return;
+    };
+    for (mut text, mut visibility) in stars_query.iter_mut() {
+        text.sections[0].value = star_text(stars);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn update_par_text(
+    game_state: Res<GameState>,
+    validation: Res<CurrentValidation>,
+    level_stats: Res<LevelStats>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<ParText>>,
+) {
+    let Some(par) = game_state.par else {
+        return;
+    };
+    // Revealing the solution doesn't count as finishing within par.
+    let within_par = validation.0.complete && !level_stats.assisted && level_stats.moves <= par;
+    let value = par_text(&lang, level_stats.moves, par, within_par);
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+fn update_solution_status_text(
+    validation: Res<CurrentValidation>,
+    game_state: Res<GameState>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<SolutionStatusText>>,
+) {
+    if !validation.is_changed() && !lang.is_changed() {
+        return;
+    }
+    let status = lang.t(solution_status(&validation.0, &game_state.puzzle));
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = status.to_string();
+    }
+}
+
+// Row and column statuses of the previous validation, to play a cue when a line
+// becomes satisfied or overfilled.
+#[derive(Resource, Default)]
+pub struct PreviousLineStatus {
+    status: Option<(Vec<LineStatus>, Vec<LineStatus>)>,
+    // Seconds since the last cue, so dragging across cells doesn't repeat it.
+    since_cue: f32,
+}
+
+pub const LINE_CUE_COOLDOWN: f32 = 0.25;
+
+fn play_line_sounds(
+    mut commands: Commands,
+    time: Res<Time>,
+    validation: Res<CurrentValidation>,
+    mut previous: ResMut<PreviousLineStatus>,
+    server: Res<AssetServer>,
+) {
+    previous.since_cue += time.delta_seconds();
+    if !validation.is_changed() {
+        return;
+    }
+    let validation_result = &validation.0;
+    let current = (
+        validation_result.row_status.clone(),
+        validation_result.col_status.clone(),
+    );
+    // The first validation of a level has nothing to compare with.
+    let Some((rows, cols)) = previous.status.replace(current) else {
+        return;
+    };
+    let became = |status: LineStatus| {
+        rows.iter()
+            .zip(&validation_result.row_status)
+            .chain(cols.iter().zip(&validation_result.col_status))
+            .any(|(before, after)| before != after && *after == status)
+    };
+    let (source, speed) = if became(LineStatus::Overflow) {
+        ("erase.wav", 0.8)
+    } else if became(LineStatus::Match) {
+        ("hint.wav", 1.5)
+    } else {
+        return;
+    };
+    if previous.since_cue < LINE_CUE_COOLDOWN {
+        return;
+    }
+    previous.since_cue = 0.0;
+    commands.spawn((
+        AudioBundle {
+            source: server.load(source),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: Volume::new_absolute(0.0),
+                speed,
+                ..default()
+            },
+        },
+        VolumeSettings {
+            volume: 0.3,
+            bus: VolumeBus::Sfx,
+        },
+    ));
+}
+
+const LABEL_SHAKE_SECONDS: f32 = 0.4;
+const LABEL_SHAKE_AMPLITUDE: f32 = 6.0;
+// Shakes per second.
+const LABEL_SHAKE_RATE: f32 = 10.0;
+
+// Shakes a row or column label sideways, dying down over `LABEL_SHAKE_SECONDS`.
+#[derive(Component)]
+pub struct LabelShake {
+    elapsed: f32,
+    // Where the label rests.
+    base_x: f32,
+}
+
+type LineLabelQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        Option<&'static RowBuildingsRequired>,
+        Option<&'static ColBuildingsRequired>,
+        Option<&'static LabelShake>,
+    ),
+    Or<(With<RowBuildingsRequired>, With<ColBuildingsRequired>)>,
+>;
+
+// Starts shaking the labels of lines that just went over their count. Runs before
+// `play_line_sounds` stores the new statuses as the previous ones.
+fn shake_overflowing_labels(
+    mut commands: Commands,
+    validation: Res<CurrentValidation>,
+    previous: Res<PreviousLineStatus>,
+    reduce_motion: Res<ReduceMotion>,
+    label_query: LineLabelQuery,
+) {
+    if reduce_motion.0 || !validation.is_changed() {
+        return;
+    }
+    let Some((rows, cols)) = &previous.status else {
+        return;
+    };
+    let validation_result = &validation.0;
+    let went_over = |before: &[LineStatus], after: &[LineStatus], index: usize| {
+        before[index] != LineStatus::Overflow && after[index] == LineStatus::Overflow
+    };
+    for (entity, transform, row, col, shake) in label_query.iter() {
+        let overflowed = match (row, col) {
+            (Some(row), _) => went_over(rows, &validation_result.row_status, row.row),
+            (_, Some(col)) => went_over(cols, &validation_result.col_status, col.col),
+            _ => false,
+        };
+        if overflowed {
+            // A label already shaking starts over from where it rests.
+            let base_x = shake.map_or(transform.translation.x, |shake| shake.base_x);
+            commands.entity(entity).insert(LabelShake {
+                elapsed: 0.0,
+                base_x,
+            });
+        }
+    }
+}
+
+fn animate_label_shake(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut label_query: Query<(Entity, &mut LabelShake, &mut Transform)>,
+) {
+    for (entity, mut shake, mut transform) in label_query.iter_mut() {
+        shake.elapsed += time.delta_seconds();
+        let progress = (shake.elapsed / LABEL_SHAKE_SECONDS).min(1.0);
+        let phase = shake.elapsed * LABEL_SHAKE_RATE * std::f32::consts::TAU;
+        transform.translation.x =
+            shake.base_x + LABEL_SHAKE_AMPLITUDE * (1.0 - progress) * phase.sin();
+        if progress >= 1.0 {
+            transform.translation.x = shake.base_x;
+            commands.entity(entity).remove::<LabelShake>();
+        }
+    }
+}
+
+const LABEL_FLASH_SECONDS: f32 = 1.5;
+const LABEL_FLASH_COUNT: f32 = 3.0;
+const LABEL_FLASH_SCALE: f32 = 0.6;
+
+// Sent to draw attention to the label of a row or column.
+#[derive(Event)]
+pub struct FlashLineLabel(pub Line);
+
+// Grows and shrinks a row or column label a few times over `LABEL_FLASH_SECONDS`.
+#[derive(Component)]
+pub struct LabelFlash {
+    elapsed: f32,
+}
+
+fn start_label_flash(
+    mut commands: Commands,
+    mut flash_events: EventReader<FlashLineLabel>,
+    row_label_query: Query<(Entity, &RowBuildingsRequired)>,
+    col_label_query: Query<(Entity, &ColBuildingsRequired)>,
+) {
+    for FlashLineLabel(line) in flash_events.iter() {
+        let label = match *line {
+            Line::Row(row) => row_label_query
+                .iter()
+                .find(|(_, label)| label.row == row)
+                .map(|(entity, _)| entity),
+            Line::Col(col) => col_label_query
+                .iter()
+                .find(|(_, label)| label.col == col)
+                .map(|(entity, _)| entity),
+        };
+        if let Some(entity) = label {
+            commands.entity(entity).insert(LabelFlash { elapsed: 0.0 });
+        }
+    }
+}
+
+fn animate_label_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut label_query: Query<(Entity, &mut LabelFlash, &mut Transform)>,
+) {
+    for (entity, mut flash, mut transform) in label_query.iter_mut() {
+        flash.elapsed += time.delta_seconds();
+        if flash.elapsed >= LABEL_FLASH_SECONDS {
+            transform.scale = Vec3::ONE;
+            commands.entity(entity).remove::<LabelFlash>();
+            continue;
+        }
+        // Without motion the label stays enlarged instead of pulsing.
+        let grow = if reduce_motion.0 {
+            1.0
+        } else {
+            let phase = flash.elapsed / LABEL_FLASH_SECONDS * LABEL_FLASH_COUNT;
+            (phase * std::f32::consts::PI).sin().powi(2)
+        };
+        transform.scale = Vec3::splat(1.0 + LABEL_FLASH_SCALE * grow);
+    }
+}
+
+fn reset_previous_line_status(mut previous: ResMut<PreviousLineStatus>) {
+    previous.status = None;
+}
+
+// Whether row and column labels show "placed/target" instead of just the target.
+#[derive(Resource, Default)]
+pub struct ShowPlacedCounts(pub bool);
+
 pub fn update_buildings_required(
     game_state: Res<GameState>,
+    validation: Res<CurrentValidation>,
+    palette: Res<StatusPalette>,
+    show_placed_counts: Res<ShowPlacedCounts>,
     mut row_buildings_required_text_query: Query<
         (&mut Text, &RowBuildingsRequired),
         Without<ColBuildingsRequired>,
@@ -503,126 +1975,155 @@ pub fn update_buildings_required(
         Without<RowBuildingsRequired>,
     >,
 ) {
-    let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
-    let (rows, cols) = game_state.puzzle.dims();
+    let validation_result = &validation.0;
 
-    let underflow_color = Color::WHITE;
-    let match_color = Color::rgb(0.4, 1.0, 0.3);
-    let overflow_color = Color::rgb(1.0, 0.3, 0.2);
-
-    for r in 0..rows {
-        let color = match validation_result.row_status[r] {
-            LineStatus::Underflow => underflow_color,
-            LineStatus::Match => match_color,
-            LineStatus::Overflow => overflow_color,
-        };
-        if let Some((mut text, _)) = row_buildings_required_text_query
-            .iter_mut()
-            .find(|(_, x)| x.row == r)
-        {
-            text.sections[0].style.color = color;
+    let label = |placed: usize, target: usize| {
+        if show_placed_counts.0 {
+            format!("{placed}/{target}")
+        } else {
+            target.to_string()
         }
+    };
+    let status_color = |status: &LineStatus| match status {
+        LineStatus::Underflow => palette.underflow,
+        LineStatus::Match => palette.matched,
+        LineStatus::Overflow => palette.overflow,
+    };
+
+    for (mut text, x) in row_buildings_required_text_query.iter_mut() {
+        text.sections[0].value = label(
+            validation_result.row_counts[x.row],
+            game_state.puzzle.row_count[x.row],
+        );
+        text.sections[0].style.color = status_color(&validation_result.row_status[x.row]);
     }
 
-    for c in 0..cols {
-        let color = match validation_result.col_status[c] {
-            LineStatus::Underflow => underflow_color,
-            LineStatus::Match => match_color,
-            LineStatus::Overflow => overflow_color,
-        };
-        if let Some((mut text, _)) = col_buildings_required_text_query
-            .iter_mut()
-            .find(|(_, x)| x.col == c)
-        {
-            text.sections[0].style.color = color;
-        }
+    for (mut text, x) in col_buildings_required_text_query.iter_mut() {
+        text.sections[0].value = label(
+            validation_result.col_counts[x.col],
+            game_state.puzzle.col_count[x.col],
+        );
+        text.sections[0].style.color = status_color(&validation_result.col_status[x.col]);
     }
 }
 
 pub fn update_incorrect_placements(
-    game_state: Res<GameState>,
+    validation: Res<CurrentValidation>,
+    palette: Res<StatusPalette>,
     mut incorrect_placements_query: Query<(&mut Visibility, &IncorrectPlacement)>,
+    mut adjacency_links_query: Query<
+        (&mut Visibility, &AdjacencyLink),
+        Without<IncorrectPlacement>,
+    >,
     mut constraint_violations_query: Query<(&mut Text, &ConstraintViolationRender)>,
 ) {
-    let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
-    let (rows, cols) = game_state.puzzle.dims();
-
-    let underflow_color = Color::GRAY;
-    let match_color = Color::rgb(0.2, 0.8, 0.2);
-    let overflow_color = Color::rgb(1.0, 0.3, 0.2);
-
-    for r in 0..rows {
-        for c in 0..cols {
-            let (mut visibility, _) = incorrect_placements_query
-                .iter_mut()
-                .find(|(_, x)| x.row == r && x.col == c)
-                .unwrap();
-            *visibility = Visibility::Hidden;
+    let validation_result = &validation.0;
+
+    for (mut visibility, link) in adjacency_links_query.iter_mut() {
+        let conflicting = validation_result
+            .placement_violations
+            .iter()
+            .any(|x| x.position == link.from && x.neighbors.contains(&link.to));
+        *visibility = if conflicting {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
 
-            let matches_position = |x: &&PlacementViolation| {
-                let placement = &game_state.solution.placements[x.house_index];
-                placement.position == Position { row: r, col: c }
-            };
-            if let Some(_) = validation_result
-                .placement_violations
-                .iter()
-                .find(matches_position)
-            {
-                *visibility = Visibility::Inherited;
-            };
+    for (mut visibility, x) in incorrect_placements_query.iter_mut() {
+        let position = Position {
+            row: x.row,
+            col: x.col,
+        };
+        *visibility = if validation_result.cell_status(position) == CellStatus::Conflict {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
 
-            let (mut text, _) = constraint_violations_query
-                .iter_mut()
-                .find(|(_, x)| x.row == r && x.col == c)
-                .unwrap();
-            let matches_position =
-                |x: &&ConstraintViolation| x.position == Position { row: r, col: c };
-            if let Some(result) = validation_result
-                .constraint_violations
-                .iter()
-                .find(matches_position)
-            {
-                text.sections[0].style.color = match result.violation {
-                    ConstraintViolationType::Underflow => underflow_color,
-                    ConstraintViolationType::Match => match_color,
-                    ConstraintViolationType::Overflow => overflow_color,
-                };
+    for (mut text, x) in constraint_violations_query.iter_mut() {
+        let position = Position {
+            row: x.row,
+            col: x.col,
+        };
+        if let CellStatus::Constraint {
+            status,
+            count,
+            required,
+        } = validation_result.cell_status(position)
+        {
+            text.sections[0].value = format!("{count}/{required}");
+            text.sections[0].style.color = match status {
+                ConstraintViolationType::Underflow => CONSTRAINT_TEXT_COLOR,
+                ConstraintViolationType::Match => palette.matched,
+                ConstraintViolationType::Overflow => palette.overflow,
             };
         }
     }
 }
 
+type ViolationMarker = Or<(With<IncorrectPlacement>, With<AdjacencyLink>)>;
+
+// Fades the crosses and links over conflicting houses in and out so they stand out.
+pub fn pulse_adjacency_violations(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut sprite_query: Query<&mut Sprite, ViolationMarker>,
+) {
+    let phase = time.elapsed_seconds() * ADJACENCY_PULSE_RATE * std::f32::consts::TAU;
+    let alpha = if reduce_motion.0 {
+        1.0
+    } else {
+        0.65 + 0.35 * phase.sin()
+    };
+    for mut sprite in sprite_query.iter_mut() {
+        sprite.color.set_a(alpha);
+    }
+}
+
 pub fn update_cell_hints(
     game_state: Res<GameState>,
-    mut cell_hint_query: Query<(&mut Visibility, &CellHint)>,
+    mut cell_hint_query: Query<(&mut Visibility, &mut Sprite, &CellHint)>,
 ) {
-    let (rows, cols) = game_state.puzzle.dims();
-    for r in 0..rows {
-        for c in 0..cols {
-            let (mut visibility, _) = cell_hint_query
-                .iter_mut()
-                .find(|(_, x)| x.row == r && x.col == c)
-                .unwrap();
-            *visibility = Visibility::Hidden;
-
-            if game_state.hints[r][c] {
+    for (mut visibility, mut sprite, x) in cell_hint_query.iter_mut() {
+        match game_state.hints[x.row][x.col] {
+            0 => *visibility = Visibility::Hidden,
+            hint => {
                 *visibility = Visibility::Inherited;
+                sprite.color = HINT_COLORS[hint as usize - 1];
             }
         }
     }
 }
 
+// Sent once when the current level becomes complete.
+#[derive(Event)]
+pub struct LevelCompleted;
+
+pub const CONFETTI_COUNT: usize = 120;
+pub const CONFETTI_GRAVITY: f32 = -900.0;
+
+#[derive(Component)]
+pub struct Confetti {
+    velocity: Vec2,
+    lifetime: Timer,
+}
+
 fn detect_complete_level(
     mut commands: Commands,
-    game_state: Res<GameState>,
+    validation: Res<CurrentValidation>,
     mut complete_banner: Query<&mut Visibility, With<CompleteBanner>>,
+    mut level_completed: EventWriter<LevelCompleted>,
     server: Res<AssetServer>,
 ) {
-    let validation_result = validate_solution(&game_state.solution, &game_state.puzzle);
+    let validation_result = &validation.0;
     if validation_result.complete {
         let mut visibility = complete_banner.get_single_mut().unwrap();
         if matches!(*visibility, Visibility::Hidden) {
             *visibility = Visibility::Visible;
+            level_completed.send(LevelCompleted);
             commands.spawn((
                 AudioBundle {
                     source: server.load("level_success.wav"),
@@ -632,38 +2133,104 @@ fn detect_complete_level(
                         speed: 1.2,
                         ..default()
                     },
-                    ..default()
                 },
-                VolumeSettings { volume: 0.4 },
+                VolumeSettings {
+                    volume: 0.4,
+                    bus: VolumeBus::Sfx,
+                },
             ));
         }
     }
 }
 
+fn spawn_confetti(
+    mut commands: Commands,
+    mut level_completed: EventReader<LevelCompleted>,
+    game_state: Res<GameState>,
+    game_screen_query: Query<Entity, With<GameScreenRoot>>,
+    reduce_motion: Res<ReduceMotion>,
+) {
+    if level_completed.iter().count() == 0 || reduce_motion.0 {
+        return;
+    }
+    let extent = board_extent(&game_state.puzzle);
+    let colors = [
+        crate::CUSTOM_ORANGE,
+        Color::rgb(0.4, 1.0, 0.3),
+        Color::rgb(0.3, 0.6, 1.0),
+        Color::rgb(1.0, 0.4, 0.7),
+        Color::rgb(1.0, 0.9, 0.2),
+    ];
+    let mut rng = thread_rng();
+    // Confetti lives under the board root, so destroy_game_screen cleans it up with the board.
+    commands
+        .entity(game_screen_query.single())
+        .with_children(|builder| {
+            for _ in 0..CONFETTI_COUNT {
+                let angle = rng.gen_range(0.2..std::f32::consts::PI - 0.2);
+                let speed = rng.gen_range(400.0..900.0);
+                let x = rng.gen_range(0.0..extent.x);
+                builder.spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: *colors.choose(&mut rng).unwrap(),
+                            custom_size: Some(Vec2::new(12.0, 6.0)),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(x, -extent.y * 0.5, AXIS_LAYER + 1.0)
+                            .with_rotation(Quat::from_rotation_z(
+                                rng.gen_range(0.0..std::f32::consts::TAU),
+                            )),
+                        ..Default::default()
+                    },
+                    Confetti {
+                        velocity: Vec2::from_angle(angle) * speed,
+                        lifetime: Timer::from_seconds(rng.gen_range(1.5..2.5), TimerMode::Once),
+                    },
+                ));
+            }
+        });
+}
+
+fn update_confetti(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut confetti_query: Query<(Entity, &mut Transform, &mut Sprite, &mut Confetti)>,
+) {
+    let dt = time.delta_seconds();
+    for (entity, mut transform, mut sprite, mut confetti) in confetti_query.iter_mut() {
+        confetti.lifetime.tick(time.delta());
+        if confetti.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        confetti.velocity.y += CONFETTI_GRAVITY * dt;
+        transform.translation += confetti.velocity.extend(0.0) * dt;
+        transform.rotate_z(4.0 * dt);
+        sprite.color.set_a(confetti.lifetime.percent_left());
+    }
+}
+
 fn handle_mouse_input(
     mouse: Res<Input<MouseButton>>,
     touches_input: Res<Touches>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     game_screen_query: Query<&Transform, With<GameScreenRoot>>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
-    mut game_state: ResMut<GameState>,
-    mut commands: Commands,
-    server: Res<AssetServer>,
+    placement_mode: Res<PlacementMode>,
+    mut cell_input: CellInput,
 ) {
     let game_screen_transform = game_screen_query.single();
     let (camera, camera_global_transform) = camera_query.single();
     let window = window_query.single();
-    let (rows, cols) = game_state.puzzle.dims();
+    let (rows, cols) = cell_input.game_state.puzzle.dims();
 
     let left_just_pressed =
         mouse.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
     let right_just_pressed = mouse.just_pressed(MouseButton::Right);
 
-    let isometric_to_orthographic = |pi: Vec2| {
-        let pi = pi - game_screen_transform.translation.xy();
-        let po = Vec2::new(pi.x + 2.0 * pi.y, pi.x - 2.0 * pi.y);
-        po / CELL_SIZE
-    };
+    let isometric_to_orthographic =
+        |pi: Vec2| world_to_board(pi, game_screen_transform.translation);
 
     if let Some(p) = window
         .cursor_position()
@@ -673,105 +2240,250 @@ fn handle_mouse_input(
     {
         let lower_bound = Vec2::new(0.0, 0.0);
         let upper_bound = Vec2::new(cols as f32, rows as f32);
-        if p.cmpge(lower_bound).all() && p.cmplt(upper_bound).all() {
+        if (left_just_pressed || right_just_pressed)
+            && p.cmpge(lower_bound).all()
+            && p.cmplt(upper_bound).all()
+        {
             let position = Position {
                 row: p.y as usize,
                 col: p.x as usize,
             };
-            let r = position.row;
-            let c = position.col;
-
-            if left_just_pressed
-                && game_state.puzzle.field[r][c] == CellType::Grass
-                && game_state
-                    .solution
-                    .placements
-                    .iter()
-                    .all(|x| !(x.position == position))
-            {
-                game_state.solution.placements.push(Placement { position });
-                game_state.hints[r][c] = false;
-
-                commands.spawn((
-                    AudioBundle {
-                        source: server.load("place.wav"),
-                        settings: PlaybackSettings {
-                            mode: PlaybackMode::Despawn,
-                            volume: Volume::new_absolute(0.0),
-                            speed: 1.2,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    VolumeSettings { volume: 0.6 },
-                ));
-            } else if right_just_pressed || left_just_pressed {
-                // Remove placements at this position.
-                if let Some(index) = game_state
-                    .solution
-                    .placements
-                    .iter()
-                    .position(|x| x.position == position)
-                {
-                    game_state.solution.placements.remove(index);
-                    commands.spawn((
-                        AudioBundle {
-                            source: server.load("remove.wav"),
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Despawn,
-                                volume: Volume::new_absolute(0.0),
-                                speed: 1.2,
-                                ..default()
-                            },
-                            ..default()
-                        },
-                        VolumeSettings { volume: 0.5 },
-                    ));
-                    game_state.hints[r][c] = false;
-                } else if game_state.puzzle.field[r][c] == CellType::Grass {
-                    let source = if game_state.hints[r][c] {
-                        server.load("erase.wav")
-                    } else {
-                        server.load("draw.wav")
-                    };
+            // In mark mode every click only removes houses or toggles markers.
+            let (primary, secondary) = match *placement_mode {
+                PlacementMode::Place => (left_just_pressed, right_just_pressed),
+                PlacementMode::Mark => (false, true),
+            };
+            cell_input.handle(position, primary, secondary);
+        }
+    }
+}
 
-                    commands.spawn((
-                        AudioBundle {
-                            source,
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Despawn,
-                                volume: Volume::new_absolute(0.0),
-                                speed: 0.9,
-                                ..default()
-                            },
-                            ..default()
-                        },
-                        VolumeSettings { volume: 0.12 },
-                    ));
-                    game_state.hints[r][c] ^= true;
-                }
+// What a click on a cell changed on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellEdit {
+    Placed,
+    Removed,
+    Marked,
+    Unmarked,
+    // A house that strict rules refused, leaving the board as it was.
+    Rejected,
+    // A click on a cell without grass, which can't take a house or marker.
+    Blocked,
+}
+
+impl CellEdit {
+    // Whether the input was refused, which the player is told about.
+    pub fn is_denied(self) -> bool {
+        matches!(self, CellEdit::Rejected | CellEdit::Blocked)
+    }
+}
+
+// Whether a house at `position` would touch another one.
+fn placement_conflicts(position: Position, game_state: &GameState) -> bool {
+    let mut trial = game_state.solution.clone();
+    trial.placements.push(Placement {
+        position,
+        kind: game_state.house_kind,
+    });
+    validate_solution(&trial, &game_state.puzzle).cell_status(position) == CellStatus::Conflict
+}
+
+// Applies a click on the cell at `position` to the game state. The primary action
+// places a house on empty grass; either action removes a house or cycles the hint
+// marker color. Kept free of ECS access so it can be driven without a window.
+pub fn apply_cell_input(
+    position: Position,
+    primary: bool,
+    secondary: bool,
+    rule_mode: RuleMode,
+    game_state: &mut GameState,
+) -> Option<CellEdit> {
+    let r = position.row;
+    let c = position.col;
+
+    if primary
+        && game_state.puzzle.field[r][c] == CellType::Grass
+        && game_state
+            .solution
+            .placements
+            .iter()
+            .all(|x| !(x.position == position))
+    {
+        if rule_mode == RuleMode::Strict && placement_conflicts(position, game_state) {
+            return Some(CellEdit::Rejected);
+        }
+        game_state.solution.placements.push(Placement {
+            position,
+            kind: game_state.house_kind,
+        });
+        game_state.hints[r][c] = 0;
+        Some(CellEdit::Placed)
+    } else if primary || secondary {
+        // Remove placements at this position.
+        if let Some(index) = game_state
+            .solution
+            .placements
+            .iter()
+            .position(|x| x.position == position)
+        {
+            game_state.solution.placements.remove(index);
+            game_state.hints[r][c] = 0;
+            Some(CellEdit::Removed)
+        } else if game_state.puzzle.field[r][c] == CellType::Grass {
+            // Cycle through the marker colors and back to no marker.
+            let hint = &mut game_state.hints[r][c];
+            *hint = (*hint + 1) % (HINT_COLORS.len() as u8 + 1);
+            if *hint != 0 {
+                Some(CellEdit::Marked)
+            } else {
+                Some(CellEdit::Unmarked)
             }
+        } else {
+            Some(CellEdit::Blocked)
+        }
+    } else {
+        None
+    }
+}
+
+// Everything a click on a cell touches: the board, the replay being recorded, the
+// sound it plays and the feedback on refused input.
+#[derive(SystemParam)]
+pub struct CellInput<'w, 's> {
+    pub game_state: ResMut<'w, GameState>,
+    rule_mode: Res<'w, RuleMode>,
+    recorder: ResMut<'w, ReplayRecorder>,
+    denied: EventWriter<'w, CellInputDenied>,
+    commands: Commands<'w, 's>,
+    server: Res<'w, AssetServer>,
+}
+
+impl CellInput<'_, '_> {
+    // Applies a click on the cell at `position` under the current rules.
+    pub fn handle(
+        &mut self,
+        position: Position,
+        primary: bool,
+        secondary: bool,
+    ) -> Option<CellEdit> {
+        let rule_mode = *self.rule_mode;
+        self.handle_with_rules(position, primary, secondary, rule_mode)
+    }
+
+    // Applies a click on the cell at `position`, records it for replays and plays the
+    // matching sound. Refused input leaves nothing to replay and is pointed out.
+    pub fn handle_with_rules(
+        &mut self,
+        position: Position,
+        primary: bool,
+        secondary: bool,
+        rule_mode: RuleMode,
+    ) -> Option<CellEdit> {
+        let edit = apply_cell_input(
+            position,
+            primary,
+            secondary,
+            rule_mode,
+            &mut self.game_state,
+        )?;
+        if edit.is_denied() {
+            self.denied.send(CellInputDenied(position));
+        } else {
+            self.recorder.record(ReplayAction::Cell {
+                row: position.row,
+                col: position.col,
+                primary,
+                secondary,
+            });
+        }
+        let (sound, volume, speed) = match edit {
+            CellEdit::Placed => ("place.wav", 0.6, 1.2),
+            CellEdit::Removed => ("remove.wav", 0.5, 1.2),
+            CellEdit::Marked => ("draw.wav", 0.12, 0.9),
+            CellEdit::Unmarked => ("erase.wav", 0.12, 0.9),
+            // Slowed down to a low buzz.
+            CellEdit::Rejected | CellEdit::Blocked => ("remove.wav", 0.6, 0.45),
+        };
+        self.commands.spawn((
+            AudioBundle {
+                source: self.server.load(sound),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new_absolute(0.0),
+                    speed,
+                    ..default()
+                },
+            },
+            VolumeSettings {
+                volume,
+                bus: VolumeBus::Sfx,
+            },
+        ));
+        Some(edit)
+    }
+}
+
+fn update_house_kind_text(
+    game_state: Res<GameState>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<HouseKindText>>,
+) {
+    if game_state.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = lang
+                .t(house_kind_label_key(game_state.house_kind))
+                .to_string();
+        }
+    }
+}
+
+fn update_placement_mode_text(
+    placement_mode: Res<PlacementMode>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<PlacementModeText>>,
+) {
+    if placement_mode.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = lang.t(placement_mode.label_key()).to_string();
         }
     }
 }
 
 // This system handles changing all buttons color based on mouse interaction
+type GameScreenButtonInteractions<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        &'static mut BackgroundColor,
+        &'static GameScreenButtonAction,
+        Has<DisabledButton>,
+    ),
+    (Changed<Interaction>, With<Button>),
+>;
+
+// The requests the buttons hand over to other plugins.
+#[derive(SystemParam)]
+struct ButtonRequests<'w> {
+    confirm_dialog: EventWriter<'w, ShowConfirmDialog>,
+    hint: EventWriter<'w, RequestHint>,
+    check: EventWriter<'w, RequestCheck>,
+    starting_line: EventWriter<'w, RequestStartingLine>,
+}
+
 fn button_system(
-    mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &GameScreenButtonAction,
-            &mut UiImage,
-        ),
-        (Changed<Interaction>, With<Button>),
-    >,
+    mut interaction_query: GameScreenButtonInteractions,
     mut game_state: ResMut<GameState>,
     mut app_state: ResMut<NextState<AppState>>,
     mut global_volume_settings: ResMut<GlobalVolumeSettings>,
-    server: Res<AssetServer>,
+    mut placement_mode: ResMut<PlacementMode>,
+    mut requests: ButtonRequests,
+    save_data: Res<SaveData>,
 ) {
-    for (interaction, mut color, action, mut ui_image) in &mut interaction_query {
+    for (interaction, mut color, action, disabled) in &mut interaction_query {
+        if disabled {
+            continue;
+        }
         *color = match *interaction {
             Interaction::Pressed => PRESSED_BUTTON.into(),
             Interaction::Hovered => HOVERED_BUTTON.into(),
@@ -781,27 +2493,299 @@ fn button_system(
         if *interaction == Interaction::Pressed {
             match *action {
                 GameScreenButtonAction::Back => {
-                    app_state.set(AppState::MainMenuScreen);
+                    if game_state.solution.placements.is_empty() {
+                        app_state.set(AppState::MainMenuScreen);
+                    } else {
+                        requests.confirm_dialog.send(ShowConfirmDialog {
+                            message: "dialog.leave_level",
+                            action: ConfirmAction::LeaveLevel,
+                        });
+                    }
                 }
-                GameScreenButtonAction::ToggleSound => {
-                    if global_volume_settings.volume == 0.0 {
-                        global_volume_settings.volume = 0.5;
-                        ui_image.texture = server.load("UI/button_snd_low.png");
-                    } else if global_volume_settings.volume == 0.5 {
-                        global_volume_settings.volume = 1.0;
-                        ui_image.texture = server.load("UI/button_snd_on.png");
-                    } else if global_volume_settings.volume == 1.0 {
-                        global_volume_settings.volume = 0.0;
-                        ui_image.texture = server.load("UI/button_snd_off.png");
+                GameScreenButtonAction::PreviousLevel => {
+                    if let Some(index) = save_data.previous_level(game_state.current_level) {
+                        game_state.current_level = index;
+                        app_state.set(AppState::SwitchLevel);
                     }
                 }
+                GameScreenButtonAction::NextLevel => {
+                    if let Some(index) = save_data.next_level(game_state.current_level) {
+                        game_state.current_level = index;
+                        app_state.set(AppState::SwitchLevel);
+                    }
+                }
+                GameScreenButtonAction::ToggleSound => {
+                    global_volume_settings.volume = if global_volume_settings.volume == 0.0 {
+                        0.5
+                    } else if global_volume_settings.volume <= 0.5 {
+                        1.0
+                    } else {
+                        0.0
+                    };
+                }
+                GameScreenButtonAction::TogglePlacementMode => {
+                    *placement_mode = match *placement_mode {
+                        PlacementMode::Place => PlacementMode::Mark,
+                        PlacementMode::Mark => PlacementMode::Place,
+                    };
+                }
+                GameScreenButtonAction::ToggleHouseKind => {
+                    game_state.house_kind = game_state.house_kind.next();
+                }
+                GameScreenButtonAction::Hint => {
+                    requests.hint.send(RequestHint);
+                }
+                GameScreenButtonAction::Check => {
+                    requests.check.send(RequestCheck);
+                }
+                GameScreenButtonAction::StartingLine => {
+                    requests.starting_line.send(RequestStartingLine);
+                }
+                GameScreenButtonAction::Solve => {
+                    requests.confirm_dialog.send(ShowConfirmDialog {
+                        message: "dialog.reveal_solution",
+                        action: ConfirmAction::RevealSolution,
+                    });
+                }
                 GameScreenButtonAction::Complete => {
-                    if game_state.current_level + 1 < all_levels().len() {
-                        game_state.current_level += 1;
+                    let level_count = all_levels().len();
+                    if let Some(index) = save_data.next_level(game_state.current_level) {
+                        game_state.current_level = index;
                         app_state.set(AppState::SwitchLevel);
+                    } else if game_state.current_level + 1 < level_count {
+                        // The next pack is still locked, which the worlds show.
+                        app_state.set(AppState::WorldSelectScreen);
+                    } else if game_state.current_level + 1 == level_count {
+                        // The last built-in level ends the game. Custom levels come after
+                        // it and have nothing to go on to.
+                        app_state.set(AppState::GameCompleteScreen);
                     }
                 }
             };
         }
     }
 }
+
+// Keeps the sky covering the whole window as it is resized and the camera pans and zooms.
+fn update_sky_background(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&OrthographicProjection, &Transform), With<Camera>>,
+    mut sky_query: Query<&mut Transform, (With<SkyBackground>, Without<Camera>)>,
+) {
+    let (Ok(window), Ok((projection, camera_transform))) =
+        (window_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    let size = Vec2::new(window.width(), window.height()) * projection.scale;
+    for mut transform in sky_query.iter_mut() {
+        transform.translation.x = camera_transform.translation.x;
+        transform.translation.y = camera_transform.translation.y;
+        transform.scale = size.extend(1.0);
+    }
+}
+
+fn animate_tiles(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut tile_query: Query<(&mut AnimatedTile, &mut Handle<Image>)>,
+) {
+    if reduce_motion.0 {
+        return;
+    }
+    for (mut tile, mut texture) in tile_query.iter_mut() {
+        if tile.timer.tick(time.delta()).just_finished() {
+            tile.frame = (tile.frame + 1) % tile.frames.len();
+            *texture = tile.frames[tile.frame].clone();
+        }
+    }
+}
+
+// Clears the board and starts putting the solver's houses on it.
+fn start_solution_reveal(
+    mut confirmed: EventReader<DialogConfirmed>,
+    mut game_state: ResMut<GameState>,
+    mut solution_reveal: ResMut<SolutionReveal>,
+    mut level_stats: ResMut<LevelStats>,
+) {
+    for DialogConfirmed(action) in confirmed.iter() {
+        if *action == ConfirmAction::RevealSolution && solution_reveal.solution.is_some() {
+            game_state.clear_progress();
+            solution_reveal.elapsed = Some(0.0);
+            solution_reveal.revealed = 0;
+            level_stats.assisted = true;
+        }
+    }
+}
+
+// Places the revealed houses one after another, each appearing with the usual house
+// animation, so the whole solution is on the board after SOLUTION_REVEAL_SECONDS.
+fn reveal_solution(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut game_state: ResMut<GameState>,
+    mut solution_reveal: ResMut<SolutionReveal>,
+) {
+    let solution_reveal = &mut *solution_reveal;
+    let (Some(elapsed), Some(solution)) = (&mut solution_reveal.elapsed, &solution_reveal.solution)
+    else {
+        return;
+    };
+    *elapsed += time.delta_seconds();
+    let count = solution.placements.len();
+    let due = if reduce_motion.0 {
+        count
+    } else {
+        ((*elapsed / SOLUTION_REVEAL_SECONDS * count as f32).ceil() as usize).min(count)
+    };
+    for placement in &solution.placements[solution_reveal.revealed..due] {
+        if !game_state.solution.placements.contains(placement) {
+            // A house of the other kind may stand there already.
+            game_state
+                .solution
+                .placements
+                .retain(|placed| placed.position != placement.position);
+            game_state.solution.placements.push(placement.clone());
+        }
+    }
+    solution_reveal.revealed = due;
+    if due == count {
+        solution_reveal.elapsed = None;
+    }
+}
+
+fn leave_level_on_confirm(
+    mut confirmed: EventReader<DialogConfirmed>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for DialogConfirmed(action) in confirmed.iter() {
+        if *action == ConfirmAction::LeaveLevel {
+            game_state.clear_progress();
+            app_state.set(AppState::MainMenuScreen);
+        }
+    }
+}
+
+// Keeps the sound button icon in line with the volume, whichever input changed it.
+fn update_sound_button(
+    global_volume_settings: Res<GlobalVolumeSettings>,
+    mut button_query: Query<(&mut UiImage, Ref<SoundButton>)>,
+    server: Res<AssetServer>,
+) {
+    let icon = if global_volume_settings.volume == 0.0 {
+        "UI/button_snd_off.png"
+    } else if global_volume_settings.volume <= 0.5 {
+        "UI/button_snd_low.png"
+    } else {
+        "UI/button_snd_on.png"
+    };
+    for (mut ui_image, button) in button_query.iter_mut() {
+        if global_volume_settings.is_changed() || button.is_added() {
+            ui_image.texture = server.load(icon);
+        }
+    }
+}
+
+type EnteringTile = (
+    Entity,
+    &'static mut TileEntrance,
+    &'static mut Transform,
+    Option<&'static mut Sprite>,
+    Option<&'static mut TextureAtlasSprite>,
+);
+
+// Moves entering tiles up to their place while fading them in. The board is usable
+// right away, since input is mapped from the final layout.
+fn animate_tile_entrance(
+    mut commands: Commands,
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut tile_query: Query<EnteringTile>,
+) {
+    for (entity, mut entrance, mut transform, sprite, atlas_sprite) in tile_query.iter_mut() {
+        entrance.elapsed += time.delta_seconds();
+        let progress = if reduce_motion.0 {
+            1.0
+        } else {
+            entrance.progress()
+        };
+        // Ease out, so tiles slow down as they settle.
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        transform.translation.y = entrance.target_y - TILE_ENTRANCE_DROP * (1.0 - eased);
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(progress);
+        }
+        if let Some(mut sprite) = atlas_sprite {
+            sprite.color.set_a(progress);
+        }
+        if progress >= 1.0 {
+            commands.entity(entity).remove::<TileEntrance>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The board cell a point on an unmoved board falls in, as (row, col).
+    fn board_cell(point: Vec2) -> (i32, i32) {
+        let board = world_to_board(point, Vec3::ZERO).floor();
+        (board.y as i32, board.x as i32)
+    }
+
+    #[test]
+    fn labels_of_a_rectangular_board_sit_beside_their_lines() {
+        let (rows, cols) = (4, 6);
+        for row in 0..rows {
+            assert_eq!(
+                board_cell(row_label_position(row, cols)),
+                (row as i32, cols as i32)
+            );
+        }
+        for col in 0..cols {
+            assert_eq!(board_cell(col_label_position(col)), (-1, col as i32));
+        }
+    }
+
+    #[test]
+    fn solving_the_board_completes_the_level() {
+        let level = all_levels()[0].clone();
+        let solution = solve(&level.puzzle).unwrap();
+        let game_state = GameState::new(level, 0);
+        let validation = validate_solution(&game_state.solution, &game_state.puzzle);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_event::<LevelCompleted>()
+            .insert_resource(game_state)
+            .insert_resource(CurrentValidation(validation))
+            .init_resource::<ValidationTime>()
+            .add_systems(Update, (update_validation, detect_complete_level).chain());
+        let banner = app.world.spawn((CompleteBanner, Visibility::Hidden)).id();
+
+        app.update();
+        assert!(!app.world.resource::<CurrentValidation>().0.complete);
+
+        let mut game_state = app.world.resource_mut::<GameState>();
+        for placement in &solution.placements {
+            let edit = apply_cell_input(
+                placement.position,
+                true,
+                false,
+                RuleMode::Strict,
+                &mut game_state,
+            );
+            assert_eq!(edit, Some(CellEdit::Placed));
+        }
+        app.update();
+
+        assert!(app.world.resource::<CurrentValidation>().0.complete);
+        assert_eq!(
+            app.world.get::<Visibility>(banner),
+            Some(&Visibility::Visible)
+        );
+        assert_eq!(app.world.resource::<Events<LevelCompleted>>().len(), 1);
+    }
+}
@@ -0,0 +1,84 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::level::*;
+use crate::solver::count_solutions;
+
+// Chance that a free grass cell gets a house in the hidden solution.
+const HOUSE_PROBABILITY: f64 = 0.45;
+// Chances that a cell without a house becomes each kind of terrain. Lakes and
+// mountains are only placed where the hidden solution satisfies them.
+const TREE_PROBABILITY: f32 = 0.15;
+const LAKE_PROBABILITY: f32 = 0.05;
+const MOUNTAIN_PROBABILITY: f32 = 0.03;
+
+// Generates a puzzle with a unique solution. The same seed always gives the same
+// puzzle.
+pub fn generate_puzzle(rows: usize, cols: usize, seed: u64) -> Puzzle {
+    let mut rng = StdRng::seed_from_u64(seed);
+    loop {
+        let puzzle = random_puzzle(rows, cols, &mut rng);
+        let has_constraints = puzzle
+            .field
+            .iter()
+            .flatten()
+            .any(|cell| cell.required_count().is_some());
+        if has_constraints && count_solutions(&puzzle, 2) == 1 {
+            return puzzle;
+        }
+    }
+}
+
+// Places houses at random and builds the terrain and line counts around them, so
+// the puzzle always has at least that solution.
+fn random_puzzle(rows: usize, cols: usize, rng: &mut StdRng) -> Puzzle {
+    let mut puzzle = Puzzle::new(
+        vec![vec![CellType::Grass; cols]; rows],
+        vec![0; rows],
+        vec![0; cols],
+    )
+    .expect("generated boards are rectangular");
+
+    let mut has_house = vec![vec![false; cols]; rows];
+    let mut cells: Vec<(usize, usize)> = (0..rows)
+        .flat_map(|row| (0..cols).map(move |col| (row, col)))
+        .collect();
+    cells.shuffle(rng);
+    for &(row, col) in &cells {
+        if rng.gen_bool(HOUSE_PROBABILITY)
+            && adjacent_houses(row, col, &has_house, &puzzle).is_empty()
+        {
+            has_house[row][col] = true;
+            puzzle.row_count[row] += 1;
+            puzzle.col_count[col] += 1;
+        }
+    }
+
+    for row in 0..rows {
+        for col in 0..cols {
+            if has_house[row][col] {
+                continue;
+            }
+            let roll: f32 = rng.gen();
+            puzzle.field[row][col] = if roll < TREE_PROBABILITY {
+                CellType::Tree
+            } else if roll < TREE_PROBABILITY + LAKE_PROBABILITY {
+                if count_houses_in_3x3(row, col, &has_house, &puzzle) == DEFAULT_LAKE_COUNT {
+                    CellType::Lake(DEFAULT_LAKE_COUNT)
+                } else {
+                    CellType::Grass
+                }
+            } else if roll < TREE_PROBABILITY + LAKE_PROBABILITY + MOUNTAIN_PROBABILITY {
+                if count_diagnoal_houses(row, col, &has_house, &puzzle) == DEFAULT_MOUNTAIN_COUNT {
+                    CellType::Mountain(DEFAULT_MOUNTAIN_COUNT)
+                } else {
+                    CellType::Grass
+                }
+            } else {
+                CellType::Grass
+            };
+        }
+    }
+    puzzle
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+
+use crate::game_screen::{FlashLineLabel, LevelStats};
+use crate::lang::Lang;
+use crate::level::{most_constrained_line, Position, Solution};
+use crate::pointer::{DismissPointers, PointerTarget, Pointers};
+use crate::replay::{ReplayAction, ReplayRecorder};
+use crate::solver::solve;
+use crate::toast::ShowToast;
+use crate::GameState;
+
+pub struct HintPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for HintPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RequestHint>()
+            .add_event::<RequestStartingLine>()
+            .init_resource::<ActiveHint>()
+            .add_systems(OnEnter(self.0), reset_active_hint)
+            .add_systems(
+                Update,
+                (
+                    give_hint,
+                    dismiss_followed_hint,
+                    update_hint_button_text,
+                    point_out_starting_line,
+                )
+                    .chain()
+                    .run_if(in_state(self.0)),
+            );
+    }
+}
+
+// Hints the player can ask for on each level.
+pub const HINTS_PER_LEVEL: usize = 3;
+
+// Sent by the hint button. Costs one hint if there is anything to point out.
+#[derive(Event)]
+pub struct RequestHint;
+
+// Sent by the start button. Flashes the label of the line to look at first, which
+// reveals no cells and so is free.
+#[derive(Event)]
+pub struct RequestStartingLine;
+
+// Text of the hint button, showing how many hints are left.
+#[derive(Component)]
+pub struct HintButtonText;
+
+pub fn hint_button_label(lang: &Lang, hints_remaining: usize) -> String {
+    lang.t_with("hud.hint", &[("count", hints_remaining.to_string())])
+}
+
+// The board the last hint was given for. Its pointer goes away once the board
+// changes.
+#[derive(Resource, Default)]
+struct ActiveHint {
+    board: Option<Solution>,
+}
+
+fn reset_active_hint(mut active_hint: ResMut<ActiveHint>) {
+    active_hint.board = None;
+}
+
+// A house that doesn't belong in the solution comes first, otherwise a house that
+// is still missing from it.
+fn hint_cell(placed: &Solution, solution: &Solution) -> Option<(Position, &'static str)> {
+    let is_placed = |position: Position| {
+        placed
+            .placements
+            .iter()
+            .any(|placement| placement.position == position)
+    };
+    let in_solution = |position: Position| {
+        solution
+            .placements
+            .iter()
+            .any(|placement| placement.position == position)
+    };
+    placed
+        .placements
+        .iter()
+        .map(|placement| placement.position)
+        .find(|&position| !in_solution(position))
+        .map(|position| (position, "hint.remove_house"))
+        .or_else(|| {
+            solution
+                .placements
+                .iter()
+                .map(|placement| placement.position)
+                .find(|&position| !is_placed(position))
+                .map(|position| (position, "hint.place_house"))
+        })
+}
+
+fn give_hint(
+    mut requests: EventReader<RequestHint>,
+    mut game_state: ResMut<GameState>,
+    mut level_stats: ResMut<LevelStats>,
+    mut active_hint: ResMut<ActiveHint>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut pointers: Pointers,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    if requests.iter().count() == 0 {
+        return;
+    }
+    if game_state.hints_remaining == 0 {
+        toasts.send(ShowToast::new("toast.no_hints_left"));
+        return;
+    }
+    let Some(solution) = solve(&game_state.puzzle) else {
+        toasts.send(ShowToast::new("toast.no_solution"));
+        return;
+    };
+    // A finished board has nothing to point out, so the hint is not spent.
+    let Some((position, message)) = hint_cell(&game_state.solution, &solution) else {
+        return;
+    };
+    game_state.hints_remaining -= 1;
+    level_stats.hints_used += 1;
+    recorder.record(ReplayAction::Hint);
+    active_hint.board = Some(game_state.solution.clone());
+    pointers.dismiss();
+    pointers.show(PointerTarget::Cell(position));
+    toasts.send(ShowToast::new(message));
+}
+
+fn dismiss_followed_hint(
+    game_state: Res<GameState>,
+    mut active_hint: ResMut<ActiveHint>,
+    mut dismiss_pointers: EventWriter<DismissPointers>,
+) {
+    let Some(board) = &active_hint.board else {
+        return;
+    };
+    if *board != game_state.solution {
+        active_hint.board = None;
+        dismiss_pointers.send(DismissPointers);
+    }
+}
+
+fn update_hint_button_text(
+    game_state: Res<GameState>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<HintButtonText>>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+    let label = hint_button_label(&lang, game_state.hints_remaining);
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != label {
+            text.sections[0].value = label.clone();
+        }
+    }
+}
+
+fn point_out_starting_line(
+    mut requests: EventReader<RequestStartingLine>,
+    game_state: Res<GameState>,
+    mut flash_line_label: EventWriter<FlashLineLabel>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    if requests.iter().count() == 0 {
+        return;
+    }
+    match most_constrained_line(&game_state.puzzle, &game_state.solution) {
+        Some(line) => flash_line_label.send(FlashLineLabel(line)),
+        None => toasts.send(ShowToast::new("toast.lines_complete")),
+    }
+}
@@ -1,30 +1,477 @@
-use crate::level::all_levels;
-use crate::{AppState, GameState, GlobalVolumeSettings};
+use crate::clipboard::{copy_to_clipboard, read_clipboard};
+use crate::confirm_dialog::no_confirm_dialog;
+use crate::game_screen::{BoardCursor, CellInput, ShowPlacedCounts};
+use crate::level::{forced_empty_cells, pack_of, GameLevel};
+use crate::pause_menu::{not_paused, Paused};
+use crate::replay::no_replay_playing;
+use crate::save::SaveData;
+use crate::select_level_screen::SelectedPack;
+use crate::share::share_link;
+use crate::toast::ShowToast;
+use crate::{AppState, CustomLevel, GameState, GlobalVolumeSettings};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 pub struct GameInputPlugin;
 
 impl Plugin for GameInputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, keyboard_input);
+        app.init_resource::<FocusedButton>()
+            .add_event::<DroppedFile>()
+            .add_systems(PreUpdate, read_dropped_files)
+            .add_systems(
+                Update,
+                (paste_level, drop_level, toggle_mute)
+                    .run_if(not_paused.and_then(no_confirm_dialog)),
+            )
+            // Other screens, like the editor, give these keys their own meaning.
+            .add_systems(
+                Update,
+                level_keys.run_if(
+                    in_state(AppState::InGame)
+                        .and_then(not_paused)
+                        .and_then(no_confirm_dialog),
+                ),
+            )
+            .add_systems(
+                Update,
+                continue_key.run_if(
+                    in_state(AppState::InGame)
+                        .or_else(in_state(AppState::MainMenuScreen))
+                        .and_then(not_paused)
+                        .and_then(no_confirm_dialog),
+                ),
+            )
+            .add_systems(
+                Update,
+                gamepad_board_input.run_if(
+                    in_state(AppState::InGame)
+                        .and_then(not_paused)
+                        .and_then(no_replay_playing),
+                ),
+            )
+            .add_systems(
+                Update,
+                mark_forced_empty_cells.run_if(
+                    in_state(AppState::InGame)
+                        .and_then(not_paused)
+                        .and_then(no_confirm_dialog),
+                ),
+            )
+            .add_systems(
+                Update,
+                gamepad_pause_input.run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                gamepad_menu_navigation.run_if(
+                    in_state(AppState::MainMenuScreen)
+                        .or_else(in_state(AppState::WorldSelectScreen))
+                        .or_else(in_state(AppState::SelectLevelScreen)),
+                ),
+            );
+        #[cfg(target_arch = "wasm32")]
+        app.add_plugins(crate::web_drop::WebDropPlugin);
     }
 }
 
-fn keyboard_input(
+// Stick deflection needed to count as a directional press.
+const STICK_THRESHOLD: f32 = 0.5;
+const FOCUS_BORDER_COLOR: Color = Color::rgb(0.3, 0.2, 0.1);
+
+// The menu button selected with a gamepad.
+#[derive(Resource, Default)]
+pub struct FocusedButton(pub Option<Entity>);
+
+// Moves to the levels the next and previous level controls lead to, which skip
+// locked packs, see `SaveData::next_level`.
+#[derive(SystemParam)]
+struct LevelSwitch<'w> {
+    save_data: Res<'w, SaveData>,
+    app_state: ResMut<'w, NextState<AppState>>,
+}
+
+impl LevelSwitch<'_> {
+    fn switch_to(&mut self, game_state: &mut GameState, index: Option<usize>) {
+        if let Some(index) = index {
+            game_state.current_level = index;
+            self.app_state.set(AppState::SwitchLevel);
+        }
+    }
+
+    fn next(&mut self, game_state: &mut GameState) {
+        let next = self.save_data.next_level(game_state.current_level);
+        self.switch_to(game_state, next);
+    }
+
+    fn previous(&mut self, game_state: &mut GameState) {
+        let previous = self.save_data.previous_level(game_state.current_level);
+        self.switch_to(game_state, previous);
+    }
+}
+
+fn level_keys(
+    keys: Res<Input<KeyCode>>,
+    mut game_state: ResMut<GameState>,
+    mut level_switch: LevelSwitch,
+    mut show_placed_counts: ResMut<ShowPlacedCounts>,
+    mut selected_pack: ResMut<SelectedPack>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    if keys.just_pressed(KeyCode::Right) {
+        level_switch.next(&mut game_state);
+    }
+    if keys.just_pressed(KeyCode::Left) {
+        level_switch.previous(&mut game_state);
+    }
+    // L lists the levels of the current level's pack.
+    if keys.just_pressed(KeyCode::L) {
+        if let Some((pack, _)) = pack_of(game_state.current_level) {
+            selected_pack.0 = pack;
+        }
+        level_switch.app_state.set(AppState::SelectLevelScreen);
+    }
+    // Tab switches between small and large houses on puzzles that have both.
+    if keys.just_pressed(KeyCode::Tab) && game_state.puzzle.house_kinds {
+        game_state.house_kind = game_state.house_kind.next();
+    }
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if keys.just_pressed(KeyCode::C) {
+        if ctrl && shift {
+            // Share the current level as a link.
+            match share_link(&game_state.puzzle) {
+                Ok(link) => copy_to_clipboard(&link),
+                Err(err) => toasts.send(ShowToast::with(
+                    "toast.share_failed",
+                    vec![("error", err.to_string())],
+                )),
+            }
+        } else if ctrl {
+            // Share the current level as text.
+            copy_to_clipboard(&game_state.puzzle.to_level_string());
+        } else {
+            show_placed_counts.0 ^= true;
+        }
+    }
+}
+
+// N continues with the first level not solved yet.
+fn continue_key(
     keys: Res<Input<KeyCode>>,
     mut game_state: ResMut<GameState>,
     mut app_state: ResMut<NextState<AppState>>,
-    mut global_volume_settings: ResMut<GlobalVolumeSettings>,
+    save_data: Res<SaveData>,
 ) {
-    if keys.just_pressed(KeyCode::Right) && game_state.current_level + 1 < all_levels().len() {
-        game_state.current_level += 1;
+    if keys.just_pressed(KeyCode::N) {
+        game_state.current_level = save_data.continue_level();
         app_state.set(AppState::SwitchLevel);
     }
-    if keys.just_pressed(KeyCode::Left) && game_state.current_level > 0 {
-        game_state.current_level -= 1;
-        app_state.set(AppState::SwitchLevel);
+}
+
+// M mutes the game, and pressing it again brings back the volume it had before.
+fn toggle_mute(
+    keys: Res<Input<KeyCode>>,
+    mut global_volume_settings: ResMut<GlobalVolumeSettings>,
+    mut unmuted_volume: Local<Option<f32>>,
+) {
+    if !keys.just_pressed(KeyCode::M) {
+        return;
     }
-    if keys.just_pressed(KeyCode::L) {
-        app_state.set(AppState::SelectLevelScreen);
+    if global_volume_settings.volume > 0.0 {
+        *unmuted_volume = Some(global_volume_settings.volume);
+        global_volume_settings.volume = 0.0;
+    } else {
+        global_volume_settings.volume = unmuted_volume.take().unwrap_or(0.5);
+    }
+}
+
+// X marks every cell that can't hold a house given the satisfied lines and the houses
+// placed so far. Cells that already have a marker keep its color.
+fn mark_forced_empty_cells(keys: Res<Input<KeyCode>>, mut game_state: ResMut<GameState>) {
+    if !keys.just_pressed(KeyCode::X) {
+        return;
+    }
+    let game_state = &mut *game_state;
+    for position in forced_empty_cells(&game_state.puzzle, &game_state.solution) {
+        let hint = &mut game_state.hints[position.row][position.col];
+        if *hint == 0 {
+            *hint = 1;
+        }
+    }
+}
+
+// Ctrl+V plays a level copied with Ctrl+C.
+fn paste_level(
+    keys: Res<Input<KeyCode>>,
+    mut game_state: ResMut<GameState>,
+    mut custom_level: ResMut<CustomLevel>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if !ctrl || !keys.just_pressed(KeyCode::V) {
+        return;
+    }
+    let level = read_clipboard().and_then(|text| {
+        GameLevel::from_level_string(&text).map_err(|err| format!("not a level, {err}"))
+    });
+    match level {
+        Ok(level) => {
+            custom_level.0 = Some(level);
+            game_state.current_level = CustomLevel::index();
+            app_state.set(AppState::SwitchLevel);
+        }
+        Err(err) => {
+            toasts.send(ShowToast::with("toast.paste_failed", vec![("error", err)]));
+        }
+    }
+}
+
+// A file dropped on the window, with its contents or why they couldn't be read.
+#[derive(Event, Clone)]
+pub struct DroppedFile {
+    pub name: String,
+    pub contents: Result<String, String>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_dropped_files(
+    mut drops: EventReader<FileDragAndDrop>,
+    mut dropped_files: EventWriter<DroppedFile>,
+) {
+    for drop in drops.iter() {
+        if let FileDragAndDrop::DroppedFile { path_buf, .. } = drop {
+            dropped_files.send(DroppedFile {
+                name: path_buf
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into(),
+                contents: std::fs::read_to_string(path_buf).map_err(|err| err.to_string()),
+            });
+        }
+    }
+}
+
+// Browsers don't hand out paths of dropped files, so the page reads them itself.
+#[cfg(target_arch = "wasm32")]
+fn read_dropped_files(
+    web_drops: Res<crate::web_drop::WebDrops>,
+    mut dropped_files: EventWriter<DroppedFile>,
+) {
+    dropped_files.send_batch(web_drops.take());
+}
+
+// Dropping a level file on the window plays it, like pasting it does.
+fn drop_level(
+    mut drops: EventReader<DroppedFile>,
+    mut game_state: ResMut<GameState>,
+    mut custom_level: ResMut<CustomLevel>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    // Only the last of several files dropped at once would stay loaded.
+    let Some(file) = drops.iter().last() else {
+        return;
+    };
+    let level = file.contents.clone().and_then(|text| {
+        GameLevel::from_level_string(&text).map_err(|err| format!("not a level, {err}"))
+    });
+    match level {
+        Ok(level) => {
+            custom_level.0 = Some(level);
+            game_state.current_level = CustomLevel::index();
+            app_state.set(AppState::SwitchLevel);
+        }
+        Err(err) => {
+            toasts.send(ShowToast::with(
+                "toast.load_failed",
+                vec![("file", file.name.clone()), ("error", err)],
+            ));
+        }
+    }
+}
+
+// Returns the direction pressed on the D-pad or the left stick this frame, with y
+// growing downwards. The stick only counts once each time it leaves the center.
+fn gamepad_direction(
+    gamepad: Gamepad,
+    buttons: &Input<GamepadButton>,
+    axes: &Axis<GamepadAxis>,
+    stick_held: &mut bool,
+) -> Option<IVec2> {
+    let pressed = |button_type| buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+    if pressed(GamepadButtonType::DPadUp) {
+        return Some(IVec2::new(0, -1));
+    }
+    if pressed(GamepadButtonType::DPadDown) {
+        return Some(IVec2::new(0, 1));
+    }
+    if pressed(GamepadButtonType::DPadLeft) {
+        return Some(IVec2::new(-1, 0));
+    }
+    if pressed(GamepadButtonType::DPadRight) {
+        return Some(IVec2::new(1, 0));
+    }
+
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+    let stick = Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        -axis(GamepadAxisType::LeftStickY),
+    );
+    if stick.length() < STICK_THRESHOLD {
+        *stick_held = false;
+        return None;
+    }
+    if *stick_held {
+        return None;
+    }
+    *stick_held = true;
+    if stick.x.abs() > stick.y.abs() {
+        Some(IVec2::new(stick.x.signum() as i32, 0))
+    } else {
+        Some(IVec2::new(0, stick.y.signum() as i32))
+    }
+}
+
+// D-pad/stick moves the board cursor, A places, B removes or marks and the shoulder
+// buttons switch levels.
+fn gamepad_board_input(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut stick_held: Local<bool>,
+    mut cursor: ResMut<BoardCursor>,
+    mut cell_input: CellInput,
+    mut level_switch: LevelSwitch,
+) {
+    for gamepad in gamepads.iter() {
+        let pressed = |button_type| buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+
+        if let Some(direction) = gamepad_direction(gamepad, &buttons, &axes, &mut stick_held) {
+            let (rows, cols) = cell_input.game_state.puzzle.dims();
+            let position = cursor.position;
+            let row = (position.row as i32 + direction.y).clamp(0, rows as i32 - 1);
+            let col = (position.col as i32 + direction.x).clamp(0, cols as i32 - 1);
+            cursor.position.row = row as usize;
+            cursor.position.col = col as usize;
+            cursor.visible = true;
+        }
+
+        let primary = pressed(GamepadButtonType::South);
+        let secondary = pressed(GamepadButtonType::East);
+        if primary || secondary {
+            cursor.visible = true;
+            cell_input.handle(cursor.position, primary, secondary);
+        }
+
+        if pressed(GamepadButtonType::RightTrigger) {
+            level_switch.next(&mut cell_input.game_state);
+        }
+        if pressed(GamepadButtonType::LeftTrigger) {
+            level_switch.previous(&mut cell_input.game_state);
+        }
+    }
+}
+
+fn gamepad_pause_input(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut paused: ResMut<Paused>,
+) {
+    for gamepad in gamepads.iter() {
+        if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start)) {
+            paused.0 ^= true;
+        }
+    }
+}
+
+// Moves the menu focus to the nearest button in the pressed direction and presses
+// the focused button with A. Bevy UI interaction is mouse driven, so the press is
+// simulated by setting its Interaction.
+fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut stick_held: Local<bool>,
+    mut focused: ResMut<FocusedButton>,
+    mut button_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Interaction,
+            &mut Style,
+            &mut BorderColor,
+        ),
+        With<Button>,
+    >,
+) {
+    // Drop focus left over from a screen that has been destroyed.
+    if let Some(entity) = focused.0 {
+        if button_query.get(entity).is_err() {
+            focused.0 = None;
+        }
+    }
+
+    for gamepad in gamepads.iter() {
+        if let Some(direction) = gamepad_direction(gamepad, &buttons, &axes, &mut stick_held) {
+            let centers: Vec<(Entity, Vec2)> = button_query
+                .iter()
+                .map(|(entity, transform, ..)| (entity, transform.translation().truncate()))
+                .collect();
+            let next = match focused.0.and_then(|entity| {
+                centers
+                    .iter()
+                    .find(|(other, _)| *other == entity)
+                    .map(|(_, center)| *center)
+            }) {
+                // Nothing focused yet: start from the top-left button.
+                None => centers
+                    .iter()
+                    .min_by(|(_, a), (_, b)| (a.y, a.x).partial_cmp(&(b.y, b.x)).unwrap())
+                    .map(|(entity, _)| *entity),
+                Some(from) => {
+                    let direction = direction.as_vec2();
+                    centers
+                        .iter()
+                        .filter(|(_, center)| (*center - from).dot(direction) > 1.0)
+                        .min_by(|(_, a), (_, b)| {
+                            let score = |center: Vec2| {
+                                let offset = center - from;
+                                // Prefer buttons straight ahead over ones off to the side.
+                                offset.length() + 2.0 * offset.perp_dot(direction).abs()
+                            };
+                            score(*a).partial_cmp(&score(*b)).unwrap()
+                        })
+                        .map(|(entity, _)| *entity)
+                }
+            };
+            if next.is_some() {
+                focused.0 = next;
+            }
+        }
+
+        if buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South)) {
+            if let Some(Ok((_, _, mut interaction, ..))) =
+                focused.0.map(|entity| button_query.get_mut(entity))
+            {
+                *interaction = Interaction::Pressed;
+            }
+        }
+    }
+
+    if focused.is_changed() {
+        for (entity, _, _, mut style, mut border_color) in button_query.iter_mut() {
+            if Some(entity) == focused.0 {
+                style.border = UiRect::all(Val::Px(4.0));
+                *border_color = BorderColor(FOCUS_BORDER_COLOR);
+            } else if style.border != UiRect::default() {
+                style.border = UiRect::default();
+            }
+        }
     }
 }
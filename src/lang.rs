@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+pub struct LangPlugin;
+
+impl Plugin for LangPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_translated_text);
+    }
+}
+
+// Code and string table of every language, in the order the settings cycle through
+// them. The tables are built in, so the web build has them without loading files.
+const LANGUAGES: &[(&str, &str)] = &[
+    ("en", include_str!("../assets/lang/en.ron")),
+    ("nl", include_str!("../assets/lang/nl.ron")),
+];
+
+fn parse_table(code: &str, source: &str) -> HashMap<String, String> {
+    ron::from_str(source).unwrap_or_else(|err| panic!("Invalid lang/{code}.ron: {err}"))
+}
+
+// The language UI text is shown in. Keys missing from its table fall back to
+// English, and keys missing from that are shown as they are.
+#[derive(Resource, Clone)]
+pub struct Lang {
+    pub code: &'static str,
+    strings: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Default for Lang {
+    fn default() -> Self {
+        let (code, source) = LANGUAGES[0];
+        let english = parse_table(code, source);
+        Self {
+            code,
+            strings: english.clone(),
+            english,
+        }
+    }
+}
+
+impl Lang {
+    pub fn by_code(code: &str) -> Option<Self> {
+        let &(code, source) = LANGUAGES.iter().find(|(other, _)| *other == code)?;
+        Some(Self {
+            code,
+            strings: parse_table(code, source),
+            ..default()
+        })
+    }
+
+    pub fn next(&self) -> Self {
+        let index = LANGUAGES
+            .iter()
+            .position(|(code, _)| *code == self.code)
+            .unwrap_or(0);
+        let (code, _) = LANGUAGES[(index + 1) % LANGUAGES.len()];
+        Self::by_code(code).unwrap_or_default()
+    }
+
+    pub fn t<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.english.get(key))
+            .map(String::as_str)
+            .unwrap_or(key)
+    }
+
+    // Translates `key` and fills in its `{name}` placeholders.
+    pub fn t_with(&self, key: &str, args: &[(&str, String)]) -> String {
+        args.iter()
+            .fold(self.t(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+
+    // Level names are keyed as `level.<id>`. Levels without a translation, like
+    // custom and daily ones, keep the name they were given.
+    pub fn level_name(&self, id: &str, name: &str) -> String {
+        let key = format!("level.{id}");
+        match self.t(&key) {
+            text if text == key => name.to_string(),
+            text => text.to_string(),
+        }
+    }
+}
+
+// Text that is translated again when the language changes, for screens that stay up
+// while it does.
+#[derive(Component)]
+pub struct Translated(pub &'static str);
+
+fn update_translated_text(lang: Res<Lang>, mut text_query: Query<(&mut Text, &Translated)>) {
+    if !lang.is_changed() {
+        return;
+    }
+    for (mut text, translated) in text_query.iter_mut() {
+        text.sections[0].value = lang.t(translated.0).to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    // Contents of every string literal in `source`, escapes left as written.
+    fn string_literals(source: &str) -> Vec<String> {
+        // A quote as a char literal doesn't start a string.
+        let source = source.replace("'\"'", "' '");
+        let mut literals = Vec::new();
+        let mut start = None;
+        let mut escaped = false;
+        for (i, c) in source.char_indices() {
+            match (start, c) {
+                (Some(_), _) if escaped => escaped = false,
+                (Some(_), '\\') => escaped = true,
+                (Some(from), '"') => {
+                    literals.push(source[from..i].to_string());
+                    start = None;
+                }
+                (None, '"') => start = Some(i + 1),
+                _ => {}
+            }
+        }
+        literals
+    }
+
+    fn rust_sources(dir: &Path, sources: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                rust_sources(&path, sources);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                sources.push(fs::read_to_string(path).unwrap());
+            }
+        }
+    }
+
+    // Literals shaped like `section.name`, in a section the English table uses.
+    // Asset file names like "hint.wav" share that shape and are left out.
+    fn is_key(literal: &str, sections: &[&str]) -> bool {
+        const FILE_EXTENSIONS: &[&str] = &["json", "mp3", "png", "ron", "ttf", "wav"];
+        let Some((section, rest)) = literal.split_once('.') else {
+            return false;
+        };
+        let extension = literal.rsplit('.').next().unwrap_or_default();
+        sections.contains(&section)
+            && !rest.is_empty()
+            && !FILE_EXTENSIONS.contains(&extension)
+            && literal
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._".contains(c))
+    }
+
+    #[test]
+    fn every_key_used_in_the_code_is_in_english() {
+        let english = Lang::default().english;
+        let mut sections: Vec<&str> = english
+            .keys()
+            .filter_map(|key| key.split_once('.').map(|(section, _)| section))
+            .collect();
+        sections.sort();
+        sections.dedup();
+
+        let mut sources = Vec::new();
+        rust_sources(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut sources,
+        );
+        let mut missing: Vec<String> = sources
+            .iter()
+            .flat_map(|source| string_literals(source))
+            .filter(|literal| is_key(literal, &sections) && !english.contains_key(literal))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        assert!(missing.is_empty(), "missing from lang/en.ron: {missing:?}");
+    }
+
+    // Level names are the exception, English keeps the names the levels were given.
+    #[test]
+    fn other_languages_only_translate_english_keys() {
+        let english = Lang::default().english;
+        for &(code, source) in &LANGUAGES[1..] {
+            let table = parse_table(code, source);
+            for key in table.keys().filter(|key| !key.starts_with("level.")) {
+                assert!(
+                    english.contains_key(key),
+                    "lang/{code}.ron has unknown key {key}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn messages_are_translated() {
+        let dutch = Lang::by_code("nl").unwrap();
+        for key in [
+            "toast.replay_finished",
+            "dialog.reset_progress",
+            "check.lake_short",
+            "status.lake_short",
+            "editor.unique_solution",
+        ] {
+            assert_ne!(dutch.t(key), key);
+            assert_ne!(dutch.t(key), Lang::default().t(key));
+        }
+    }
+}
@@ -1,11 +1,30 @@
 use core::fmt;
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::time::Duration;
 
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::solver::search_effort;
+
+// Number of houses a lake or mountain requires unless the level says otherwise.
+pub const DEFAULT_LAKE_COUNT: usize = 3;
+pub const DEFAULT_MOUNTAIN_COUNT: usize = 2;
+
+/// Contents of one cell of the board.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum CellType {
     Grass,
     Tree,
-    Lake,
-    Mountain,
+    // Number of houses required in the 3x3 square around the lake.
+    Lake(usize),
+    // Number of houses required on the diagonals crossing the mountain.
+    Mountain(usize),
+    Rock,
+    // A house given as part of the puzzle. It counts like a placed house, but the
+    // player can't remove it.
+    House,
 }
 
 impl CellType {
@@ -13,36 +32,105 @@ impl CellType {
         match self {
             CellType::Grass => '.',
             CellType::Tree => 'T',
-            CellType::Lake => 'L',
-            CellType::Mountain => 'M',
+            CellType::Lake(_) => 'L',
+            CellType::Mountain(_) => 'M',
+            CellType::Rock => 'R',
+            CellType::House => 'x',
         }
     }
 
-    pub fn from_char(c: u8) -> CellType {
+    pub fn from_char(c: char) -> Option<CellType> {
         match c {
-            b'.' => CellType::Grass,
-            b'T' => CellType::Tree,
-            b'L' => CellType::Lake,
-            b'M' => CellType::Mountain,
-            _ => panic!("Unknown cell type: {}", c),
+            '.' => Some(CellType::Grass),
+            'T' => Some(CellType::Tree),
+            'L' => Some(CellType::Lake(DEFAULT_LAKE_COUNT)),
+            'M' => Some(CellType::Mountain(DEFAULT_MOUNTAIN_COUNT)),
+            'R' => Some(CellType::Rock),
+            'x' => Some(CellType::House),
+            _ => None,
+        }
+    }
+
+    // The cell as written in level strings: its char, followed by the count of a lake
+    // or mountain that requires other than the default, e.g. "L4".
+    pub fn to_level_str(self) -> String {
+        match self {
+            CellType::Lake(count) if count != DEFAULT_LAKE_COUNT => format!("L{count}"),
+            CellType::Mountain(count) if count != DEFAULT_MOUNTAIN_COUNT => format!("M{count}"),
+            _ => self.to_char().to_string(),
+        }
+    }
+
+    // The same cell type requiring `count` houses. Cells without a count are unchanged.
+    pub fn with_count(self, count: usize) -> CellType {
+        match self {
+            CellType::Lake(_) => CellType::Lake(count),
+            CellType::Mountain(_) => CellType::Mountain(count),
+            _ => self,
+        }
+    }
+
+    pub fn required_count(self) -> Option<usize> {
+        match self {
+            CellType::Lake(count) | CellType::Mountain(count) => Some(count),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug)]
+// Cells around a house that may not hold another house. Houses never share a side;
+// the variants rule out more cells on top of that.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    #[default]
+    Orthogonal,
+    // Houses may not touch diagonally either, like a chess king's moves.
+    King,
+    // Houses may not be a chess knight's move apart either.
+    Knight,
+}
+
+/// A board with the number of houses each row and column needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Puzzle {
     pub row_count: Vec<usize>,
     pub col_count: Vec<usize>,
     pub field: Vec<Vec<CellType>>,
+    // Which cells around a house are ruled out for other houses.
+    pub neighborhood: Neighborhood,
+    // Variant rule where a mountain's diagonals stop at the first tree, lake, mountain
+    // or rock instead of running to the edge of the board.
+    pub blocked_diagonals: bool,
+    // Variant rule where houses come small or large, and large ones count twice
+    // towards the row and column totals.
+    pub house_kinds: bool,
 }
 
 impl Puzzle {
+    // Builds a puzzle without variant rules, checking that the counts fit the field.
+    pub fn new(
+        field: Vec<Vec<CellType>>,
+        row_count: Vec<usize>,
+        col_count: Vec<usize>,
+    ) -> Result<Puzzle, PuzzleError> {
+        let puzzle = Puzzle {
+            row_count,
+            col_count,
+            field,
+            neighborhood: Neighborhood::Orthogonal,
+            blocked_diagonals: false,
+            house_kinds: false,
+        };
+        puzzle.validate_dimensions()?;
+        Ok(puzzle)
+    }
+
     pub fn rows(&self) -> usize {
         self.field.len()
     }
 
     pub fn cols(&self) -> usize {
-        self.field[0].len()
+        self.field.first().map_or(0, |line| line.len())
     }
 
     pub fn dims(&self) -> (usize, usize) {
@@ -52,6 +140,376 @@ impl Puzzle {
     pub fn is_valid(&self, row: i32, col: i32) -> bool {
         row >= 0 && row < self.rows() as i32 && col >= 0 && col < self.cols() as i32
     }
+
+    // Every cell of the board, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        (0..self.rows()).flat_map(move |row| (0..self.cols()).map(move |col| Position { row, col }))
+    }
+
+    pub fn grass_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions()
+            .filter(|position| self.field[position.row][position.col] == CellType::Grass)
+    }
+
+    // Writes the puzzle in the format read by `parse_puzzle`.
+    pub fn to_level_string(&self) -> String {
+        let counts = |counts: &[usize]| {
+            counts
+                .iter()
+                .map(|count| count.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let mut s = format!(
+            "rows: {}\ncols: {}\n",
+            counts(&self.row_count),
+            counts(&self.col_count)
+        );
+        match self.neighborhood {
+            Neighborhood::Orthogonal => {}
+            Neighborhood::King => s.push_str("no_diagonal\n"),
+            Neighborhood::Knight => s.push_str("no_knight_move\n"),
+        }
+        if self.blocked_diagonals {
+            s.push_str("blocked_diagonals\n");
+        }
+        if self.house_kinds {
+            s.push_str("house_kinds\n");
+        }
+        for line in &self.field {
+            s.extend(line.iter().map(|cell| cell.to_level_str()));
+            s.push('\n');
+        }
+        s
+    }
+
+    // Checks that the field is a non-empty rectangle with a count for every line.
+    // The rest of the game assumes this holds.
+    pub fn validate_dimensions(&self) -> Result<(), PuzzleError> {
+        let (rows, cols) = self.dims();
+        if rows == 0 || cols == 0 {
+            return Err(PuzzleError::Empty);
+        }
+        for (row, line) in self.field.iter().enumerate() {
+            if line.len() != cols {
+                return Err(PuzzleError::NotRectangular {
+                    row,
+                    expected: cols,
+                    found: line.len(),
+                });
+            }
+        }
+        if self.row_count.len() != rows {
+            return Err(PuzzleError::RowCountMismatch {
+                expected: rows,
+                found: self.row_count.len(),
+            });
+        }
+        if self.col_count.len() != cols {
+            return Err(PuzzleError::ColCountMismatch {
+                expected: cols,
+                found: self.col_count.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // Changes the board size, cutting off or adding grass at the bottom and right.
+    // Counts of lines that stay are kept, down to what fits in the new size, and
+    // new lines need no houses. A puzzle can't be empty, so sizes below 1 are 1.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        self.field.resize(rows, vec![CellType::Grass; cols]);
+        for line in self.field.iter_mut() {
+            line.resize(cols, CellType::Grass);
+        }
+        self.row_count.resize(rows, 0);
+        self.col_count.resize(cols, 0);
+        // A line can never hold more houses than it has cells.
+        for count in self.row_count.iter_mut() {
+            *count = (*count).min(cols);
+        }
+        for count in self.col_count.iter_mut() {
+            *count = (*count).min(rows);
+        }
+    }
+
+    // Cheap check that the line counts can be met at all: rows and columns must ask
+    // for the same number of houses, and no line may ask for more houses than fit on
+    // its grass without two of them sharing a side. Passing doesn't mean the puzzle
+    // is solvable.
+    pub fn counts_feasible(&self) -> bool {
+        let max_weight = self.max_house_weight();
+        let cols: Vec<Vec<CellType>> = (0..self.cols())
+            .map(|col| self.field.iter().map(|line| line[col]).collect())
+            .collect();
+        self.row_count.iter().sum::<usize>() == self.col_count.iter().sum::<usize>()
+            && self
+                .field
+                .iter()
+                .zip(&self.row_count)
+                .chain(cols.iter().zip(&self.col_count))
+                .all(|(line, &count)| count <= max_houses_in_line(line) * max_weight)
+    }
+
+    // Most a single house can add to a line count.
+    pub fn max_house_weight(&self) -> usize {
+        if self.house_kinds {
+            HouseKind::Large.weight()
+        } else {
+            HouseKind::Small.weight()
+        }
+    }
+}
+
+// Codes of the cell types in `Puzzle::encode`, 3 bits each. Lakes and mountains with
+// the default count take the short codes, others one of the codes after these,
+// followed by their count.
+const ENCODED_CELLS: [CellType; 6] = [
+    CellType::Grass,
+    CellType::Tree,
+    CellType::Lake(DEFAULT_LAKE_COUNT),
+    CellType::Mountain(DEFAULT_MOUNTAIN_COUNT),
+    CellType::Rock,
+    CellType::House,
+];
+const LAKE_WITH_COUNT: usize = 6;
+const MOUNTAIN_WITH_COUNT: usize = 7;
+const CELL_BITS: u32 = 3;
+const CELL_COUNT_BITS: u32 = 4;
+// Bits of the header fields holding the width of the counts and each side.
+const COUNT_WIDTH_BITS: u32 = 4;
+const SIDE_BITS: u32 = 8;
+
+// Appends values of a fixed number of bits to a byte buffer, lowest bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: usize, bits: u32) {
+        for bit in 0..bits {
+            if self.len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if value >> bit & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 1 << (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, bits: u32) -> Result<usize, PuzzleError> {
+        let mut value = 0;
+        for bit in 0..bits {
+            let byte = self
+                .bytes
+                .get(self.pos / 8)
+                .ok_or(PuzzleError::InvalidEncoding("it is cut short"))?;
+            value |= ((byte >> (self.pos % 8) & 1) as usize) << bit;
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+}
+
+impl Puzzle {
+    // Packs the puzzle into a short string that is safe in URLs. A header byte holds
+    // the variant rules and the width of the counts, followed by a byte each for the
+    // rows and columns. Then come the counts, just wide enough for the largest one,
+    // and 3 bits per cell. Lakes and mountains that don't require the default count
+    // add 4 bits for theirs. Puzzles with sides over 255 cells, or lakes and
+    // mountains needing over 15 houses, don't fit and are refused.
+    pub fn encode(&self) -> Result<String, PuzzleError> {
+        let fits = |value: usize, bits: u32| value >> bits == 0;
+        let (rows, cols) = self.dims();
+        if !fits(rows, SIDE_BITS) || !fits(cols, SIDE_BITS) {
+            return Err(PuzzleError::CannotEncode("a side is over 255 cells long"));
+        }
+        let max_count = self.row_count.iter().chain(&self.col_count).max();
+        let count_bits = max_count.map_or(0, |&count| usize::BITS - count.leading_zeros());
+        if !fits(count_bits as usize, COUNT_WIDTH_BITS) {
+            return Err(PuzzleError::CannotEncode("a line count is too large"));
+        }
+        let constraint_fits = |cell: &CellType| match *cell {
+            CellType::Lake(count) | CellType::Mountain(count) => fits(count, CELL_COUNT_BITS),
+            _ => true,
+        };
+        if !self.field.iter().flatten().all(constraint_fits) {
+            return Err(PuzzleError::CannotEncode(
+                "a lake or mountain needs over 15 houses",
+            ));
+        }
+        let neighborhood = match self.neighborhood {
+            Neighborhood::Orthogonal => 0,
+            Neighborhood::King => 1,
+            Neighborhood::Knight => 2,
+        };
+        let mut writer = BitWriter::default();
+        writer.push(neighborhood, 2);
+        writer.push(self.blocked_diagonals as usize, 1);
+        writer.push(self.house_kinds as usize, 1);
+        writer.push(count_bits as usize, COUNT_WIDTH_BITS);
+        writer.push(rows, SIDE_BITS);
+        writer.push(cols, SIDE_BITS);
+        for &count in self.row_count.iter().chain(&self.col_count) {
+            writer.push(count, count_bits);
+        }
+        for &cell in self.field.iter().flatten() {
+            match cell {
+                CellType::Lake(count) if count != DEFAULT_LAKE_COUNT => {
+                    writer.push(LAKE_WITH_COUNT, CELL_BITS);
+                    writer.push(count, CELL_COUNT_BITS);
+                }
+                CellType::Mountain(count) if count != DEFAULT_MOUNTAIN_COUNT => {
+                    writer.push(MOUNTAIN_WITH_COUNT, CELL_BITS);
+                    writer.push(count, CELL_COUNT_BITS);
+                }
+                _ => {
+                    let code = ENCODED_CELLS
+                        .iter()
+                        .position(|&encoded| encoded == cell)
+                        .unwrap_or(0);
+                    writer.push(code, CELL_BITS);
+                }
+            }
+        }
+        Ok(URL_SAFE_NO_PAD.encode(writer.bytes))
+    }
+
+    // Reads a puzzle written by `Puzzle::encode`.
+    pub fn decode(s: &str) -> Result<Puzzle, PuzzleError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s.trim())
+            .map_err(|_| PuzzleError::InvalidEncoding("it is not base64"))?;
+        let mut reader = BitReader {
+            bytes: &bytes,
+            pos: 0,
+        };
+        let neighborhood = match reader.read(2)? {
+            0 => Neighborhood::Orthogonal,
+            1 => Neighborhood::King,
+            2 => Neighborhood::Knight,
+            _ => return Err(PuzzleError::InvalidEncoding("unknown neighborhood")),
+        };
+        let blocked_diagonals = reader.read(1)? == 1;
+        let house_kinds = reader.read(1)? == 1;
+        let count_bits = reader.read(COUNT_WIDTH_BITS)? as u32;
+        let rows = reader.read(SIDE_BITS)?;
+        let cols = reader.read(SIDE_BITS)?;
+        let mut read_counts = |len: usize| -> Result<Vec<usize>, PuzzleError> {
+            (0..len).map(|_| reader.read(count_bits)).collect()
+        };
+        let row_count = read_counts(rows)?;
+        let col_count = read_counts(cols)?;
+        let field = (0..rows)
+            .map(|_| {
+                (0..cols)
+                    .map(|_| match reader.read(CELL_BITS)? {
+                        LAKE_WITH_COUNT => Ok(CellType::Lake(reader.read(CELL_COUNT_BITS)?)),
+                        MOUNTAIN_WITH_COUNT => {
+                            Ok(CellType::Mountain(reader.read(CELL_COUNT_BITS)?))
+                        }
+                        code => ENCODED_CELLS
+                            .get(code)
+                            .copied()
+                            .ok_or(PuzzleError::InvalidEncoding("unknown cell")),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        let puzzle = Puzzle {
+            row_count,
+            col_count,
+            field,
+            neighborhood,
+            blocked_diagonals,
+            house_kinds,
+        };
+        puzzle.validate_dimensions()?;
+        Ok(puzzle)
+    }
+}
+
+// Most houses a line can hold: every other cell of each run of buildable cells.
+fn max_houses_in_line(line: &[CellType]) -> usize {
+    line.split(|&cell| !matches!(cell, CellType::Grass | CellType::House))
+        .map(|run| run.len().div_ceil(2))
+        .sum()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PuzzleError {
+    UnknownCell {
+        row: usize,
+        col: usize,
+        found: char,
+    },
+    Empty,
+    NotRectangular {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    RowCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    ColCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    MissingCountLine(&'static str),
+    InvalidCount(String),
+    InvalidEncoding(&'static str),
+    CannotEncode(&'static str),
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::UnknownCell { row, col, found } => write!(
+                formatter,
+                "unknown cell {found:?} in row {row}, column {col}"
+            ),
+            PuzzleError::Empty => write!(formatter, "the field is empty"),
+            PuzzleError::NotRectangular {
+                row,
+                expected,
+                found,
+            } => write!(
+                formatter,
+                "row {row} has {found} cells, expected {expected} like the first row"
+            ),
+            PuzzleError::RowCountMismatch { expected, found } => {
+                write!(formatter, "{found} row counts given for {expected} rows")
+            }
+            PuzzleError::ColCountMismatch { expected, found } => write!(
+                formatter,
+                "{found} column counts given for {expected} columns"
+            ),
+            PuzzleError::MissingCountLine(prefix) => {
+                write!(formatter, "expected a count line starting with {prefix:?}")
+            }
+            PuzzleError::InvalidCount(count) => write!(formatter, "invalid count {count:?}"),
+            PuzzleError::InvalidEncoding(reason) => {
+                write!(formatter, "invalid level code, {reason}")
+            }
+            PuzzleError::CannotEncode(reason) => {
+                write!(formatter, "no level code possible, {reason}")
+            }
+        }
+    }
 }
 
 impl fmt::Display for Puzzle {
@@ -64,26 +522,100 @@ impl fmt::Display for Puzzle {
         }
         writeln!(formatter, "Row count: {:?}", self.row_count)?;
         writeln!(formatter, "Col count: {:?}", self.col_count)?;
+        match self.neighborhood {
+            Neighborhood::Orthogonal => {}
+            Neighborhood::King => writeln!(formatter, "No diagonal neighbors")?,
+            Neighborhood::Knight => writeln!(formatter, "No neighbors a knight's move apart")?,
+        }
+        if self.blocked_diagonals {
+            writeln!(formatter, "Mountain diagonals stop at obstacles")?;
+        }
+        if self.house_kinds {
+            writeln!(formatter, "Large houses count twice")?;
+        }
         Ok(())
     }
 }
 
-pub fn field_from_size(rows: usize, cols: usize) -> Vec<Vec<CellType>> {
-    vec![vec![CellType::Grass; cols]; rows]
+// Lines of different lengths give a ragged field, see `Puzzle::validate_dimensions`.
+// Digits after a lake or mountain set the number of houses it requires, e.g. "L4" or
+// "L12".
+pub fn parse_field(s: Vec<&str>) -> Result<Vec<Vec<CellType>>, PuzzleError> {
+    s.iter()
+        .enumerate()
+        .map(|(row, line)| {
+            let mut chars = line.chars().peekable();
+            let mut cells = Vec::new();
+            while let Some(c) = chars.next() {
+                let col = cells.len();
+                let mut cell = CellType::from_char(c).ok_or(PuzzleError::UnknownCell {
+                    row,
+                    col,
+                    found: c,
+                })?;
+                if cell.required_count().is_some() {
+                    let mut count = None;
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        let tens = count.unwrap_or(0usize).saturating_mul(10);
+                        count = Some(tens.saturating_add(digit as usize));
+                        chars.next();
+                    }
+                    if let Some(count) = count {
+                        cell = cell.with_count(count);
+                    }
+                }
+                cells.push(cell);
+            }
+            Ok(cells)
+        })
+        .collect()
 }
 
-pub fn parse_field(s: Vec<&str>) -> Vec<Vec<CellType>> {
-    let mut field = field_from_size(s.len(), s[0].len());
-    for (row, line) in s.iter().enumerate() {
-        for (col, c) in line.as_bytes().iter().enumerate() {
-            // Skip cells with house objects.
-            if [b'x'].contains(c) {
-                continue;
-            }
-            field[row][col] = CellType::from_char(*c);
+// Parses a puzzle written as two count lines followed by the field, e.g.:
+//
+//   rows: 1 1
+//   cols: 1 1
+//   ..
+//   ..
+//
+// "no_diagonal", "no_knight_move", "blocked_diagonals" and "house_kinds" lines before
+// the field enable the matching variant rules. See `parse_field` for the cells.
+pub fn parse_puzzle(s: &str) -> Result<Puzzle, PuzzleError> {
+    let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut parse_counts = |prefix: &'static str| -> Result<Vec<usize>, PuzzleError> {
+        lines
+            .next()
+            .and_then(|line| line.strip_prefix(prefix))
+            .ok_or(PuzzleError::MissingCountLine(prefix))?
+            .split_whitespace()
+            .map(|count| {
+                count
+                    .parse()
+                    .map_err(|_| PuzzleError::InvalidCount(count.to_string()))
+            })
+            .collect()
+    };
+    let row_count = parse_counts("rows:")?;
+    let col_count = parse_counts("cols:")?;
+    let mut lines = lines.peekable();
+    let (mut neighborhood, mut blocked_diagonals, mut house_kinds) =
+        (Neighborhood::Orthogonal, false, false);
+    loop {
+        match lines.peek() {
+            Some(&"no_diagonal") => neighborhood = Neighborhood::King,
+            Some(&"no_knight_move") => neighborhood = Neighborhood::Knight,
+            Some(&"blocked_diagonals") => blocked_diagonals = true,
+            Some(&"house_kinds") => house_kinds = true,
+            _ => break,
         }
+        lines.next();
     }
-    field
+    Ok(Puzzle {
+        neighborhood,
+        blocked_diagonals,
+        house_kinds,
+        ..Puzzle::new(parse_field(lines.collect())?, row_count, col_count)?
+    })
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -92,57 +624,301 @@ pub struct Position {
     pub col: usize,
 }
 
-#[derive(Debug)]
+impl Position {
+    // The cell `drow` rows and `dcol` columns away, if it is on the board.
+    pub fn offset(self, drow: i32, dcol: i32, puzzle: &Puzzle) -> Option<Position> {
+        let row = self.row as i32 + drow;
+        let col = self.col as i32 + dcol;
+        puzzle.is_valid(row, col).then_some(Position {
+            row: row as usize,
+            col: col as usize,
+        })
+    }
+
+    // Neighbors on the board sharing a side with this cell.
+    pub fn neighbors4(self, puzzle: &Puzzle) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_in(ORTHOGONAL_DIRECTIONS, puzzle)
+    }
+
+    // Neighbors on the board touching this cell at a corner only.
+    pub fn diagonal_neighbors(self, puzzle: &Puzzle) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_in(DIAGONAL_DIRECTIONS, puzzle)
+    }
+
+    // All neighbors on the board, sides first.
+    pub fn neighbors8(self, puzzle: &Puzzle) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_in(ORTHOGONAL_DIRECTIONS.start..DIAGONAL_DIRECTIONS.end, puzzle)
+    }
+
+    // Cells on the board a chess knight's move away.
+    pub fn knight_neighbors(self, puzzle: &Puzzle) -> impl Iterator<Item = Position> + '_ {
+        self.neighbors_in(KNIGHT_DIRECTIONS, puzzle)
+    }
+
+    fn neighbors_in(
+        self,
+        directions: Range<usize>,
+        puzzle: &Puzzle,
+    ) -> impl Iterator<Item = Position> + '_ {
+        directions.filter_map(move |d| self.offset(DROW[d], DCOL[d], puzzle))
+    }
+}
+
+// Houses are small unless the puzzle has the house kinds rule.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HouseKind {
+    #[default]
+    Small,
+    Large,
+}
+
+impl HouseKind {
+    // How much the house adds to its row and column counts.
+    pub fn weight(self) -> usize {
+        match self {
+            HouseKind::Small => 1,
+            HouseKind::Large => 2,
+        }
+    }
+
+    pub fn next(self) -> HouseKind {
+        match self {
+            HouseKind::Small => HouseKind::Large,
+            HouseKind::Large => HouseKind::Small,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Placement {
     pub position: Position,
+    pub kind: HouseKind,
 }
 
-#[derive(Debug, Default)]
+/// Houses placed by the player, complete or not.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Solution {
     pub placements: Vec<Placement>,
 }
 
-const DROW: [i32; 4] = [1, 0, -1, 0];
-const DCOL: [i32; 4] = [0, 1, 0, -1];
+// The first four directions are orthogonal, the next four diagonal and the last eight
+// knight's moves.
+const DROW: [i32; 16] = [1, 0, -1, 0, 1, 1, -1, -1, 2, 2, -2, -2, 1, 1, -1, -1];
+const DCOL: [i32; 16] = [0, 1, 0, -1, 1, -1, 1, -1, 1, -1, 1, -1, 2, -2, 2, -2];
+const ORTHOGONAL_DIRECTIONS: Range<usize> = 0..4;
+const DIAGONAL_DIRECTIONS: Range<usize> = 4..8;
+const KNIGHT_DIRECTIONS: Range<usize> = 8..16;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViolationType {
     AdjacentHouse,
+    // Only checked for puzzles with the king neighborhood.
+    DiagonalHouse,
+    // Only checked for puzzles with the knight neighborhood.
+    KnightHouse,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct PlacementViolation {
     pub house_index: usize,
+    // Where the house stands.
+    pub position: Position,
     pub violation: ViolationType,
+    // The houses this one conflicts with.
+    pub neighbors: Vec<Position>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConstraintViolationType {
     Underflow,
     Match,
     Overflow,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub struct ConstraintViolation {
     pub position: Position,
     pub violation: ConstraintViolationType,
+    // Houses currently counted by the lake or mountain and the number it requires.
+    pub count: usize,
+    pub required: usize,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LineStatus {
     Underflow,
     Match,
     Overflow,
 }
 
-#[derive(Debug)]
+/// What the validation says about a single cell, see [`ValidationResult::cell_status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellStatus {
+    /// A cell without a rule of its own, or a house that breaks none.
+    Fine,
+    /// A placed house that touches another one.
+    Conflict,
+    /// A lake or mountain with the houses it counts so far.
+    Constraint {
+        status: ConstraintViolationType,
+        count: usize,
+        required: usize,
+    },
+}
+
+/// Which rules a solution meets and breaks, see [`validate_solution`].
+#[derive(Debug, PartialEq, Eq)]
 pub struct ValidationResult {
     pub row_status: Vec<LineStatus>,
     pub col_status: Vec<LineStatus>,
     pub placement_violations: Vec<PlacementViolation>,
     pub constraint_violations: Vec<ConstraintViolation>,
+    // Whether the puzzle is solved, see `ValidationResult::all_rules_met`.
     pub complete: bool,
+    // Number of houses placed in each row and column.
+    pub row_counts: Vec<usize>,
+    pub col_counts: Vec<usize>,
+}
+
+impl ValidationResult {
+    /// Every row and column holds exactly its count of houses.
+    pub fn lines_match(&self) -> bool {
+        self.row_status
+            .iter()
+            .chain(&self.col_status)
+            .all(|&status| status == LineStatus::Match)
+    }
+
+    /// Every lake and mountain sees exactly its count of houses.
+    pub fn constraints_match(&self) -> bool {
+        self.constraint_violations
+            .iter()
+            .all(|v| v.violation == ConstraintViolationType::Match)
+    }
+
+    /// Looks up the rules that concern the cell at `position`.
+    pub fn cell_status(&self, position: Position) -> CellStatus {
+        if let Some(constraint) = self
+            .constraint_violations
+            .iter()
+            .find(|v| v.position == position)
+        {
+            return CellStatus::Constraint {
+                status: constraint.violation,
+                count: constraint.count,
+                required: constraint.required,
+            };
+        }
+        if self
+            .placement_violations
+            .iter()
+            .any(|v| v.position == position)
+        {
+            return CellStatus::Conflict;
+        }
+        CellStatus::Fine
+    }
+
+    /// The puzzle is solved only when the line counts match, no houses touch and
+    /// every lake and mountain matches. Any one of them alone is not enough, e.g.
+    /// matching line counts can still leave a lake short.
+    pub fn all_rules_met(&self) -> bool {
+        self.lines_match() && self.placement_violations.is_empty() && self.constraints_match()
+    }
+
+    /// Lists every broken rule, one line each, see [`ReportLine`].
+    pub fn report(&self, puzzle: &Puzzle) -> Vec<ReportLine> {
+        if self.all_rules_met() {
+            return vec![ReportLine::new("check.no_problems", vec![])];
+        }
+        let mut lines = Vec::new();
+        let rows = self.row_counts.iter().zip(&puzzle.row_count);
+        let cols = self.col_counts.iter().zip(&puzzle.col_count);
+        for ((short, over), counts) in [
+            (("check.row_short", "check.row_over"), rows),
+            (("check.column_short", "check.column_over"), cols),
+        ] {
+            for (index, (&count, &required)) in counts.enumerate() {
+                let line = ("line", (index + 1).to_string());
+                match count.cmp(&required) {
+                    std::cmp::Ordering::Less => lines.push(ReportLine::new(
+                        short,
+                        vec![line, ("count", (required - count).to_string())],
+                    )),
+                    std::cmp::Ordering::Equal => {}
+                    std::cmp::Ordering::Greater => lines.push(ReportLine::new(
+                        over,
+                        vec![line, ("count", (count - required).to_string())],
+                    )),
+                }
+            }
+        }
+        for violation in &self.placement_violations {
+            let key = match violation.violation {
+                ViolationType::AdjacentHouse => "check.houses_share_side",
+                ViolationType::DiagonalHouse => "check.houses_touch_diagonally",
+                ViolationType::KnightHouse => "check.houses_knight_apart",
+            };
+            // Both houses of a pair list each other, so only the first one reports it.
+            let position = violation.position;
+            for &neighbor in &violation.neighbors {
+                if (neighbor.row, neighbor.col) > (position.row, position.col) {
+                    let mut args = cell_args(position);
+                    args.push(("other_row", (neighbor.row + 1).to_string()));
+                    args.push(("other_col", (neighbor.col + 1).to_string()));
+                    lines.push(ReportLine::new(key, args));
+                }
+            }
+        }
+        for violation in &self.constraint_violations {
+            let position = violation.position;
+            let mountain = matches!(
+                puzzle.field[position.row][position.col],
+                CellType::Mountain(_)
+            );
+            let (key, count) = match (violation.violation, mountain) {
+                (ConstraintViolationType::Match, _) => continue,
+                (ConstraintViolationType::Underflow, false) => {
+                    ("check.lake_short", violation.required - violation.count)
+                }
+                (ConstraintViolationType::Overflow, false) => {
+                    ("check.lake_over", violation.count - violation.required)
+                }
+                (ConstraintViolationType::Underflow, true) => {
+                    ("check.mountain_short", violation.required - violation.count)
+                }
+                (ConstraintViolationType::Overflow, true) => {
+                    ("check.mountain_over", violation.count - violation.required)
+                }
+            };
+            let mut args = cell_args(position);
+            args.push(("count", count.to_string()));
+            lines.push(ReportLine::new(key, args));
+        }
+        lines
+    }
+}
+
+/// One line of the player facing description of a [`ValidationResult`], as a key
+/// into the game's string tables and the values of its `{name}` placeholders. Rows
+/// and columns are numbered from 1.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReportLine {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl ReportLine {
+    fn new(key: &'static str, args: Vec<(&'static str, String)>) -> Self {
+        Self { key, args }
+    }
+}
+
+fn cell_args(position: Position) -> Vec<(&'static str, String)> {
+    vec![
+        ("row", (position.row + 1).to_string()),
+        ("col", (position.col + 1).to_string()),
+    ]
 }
 
 impl fmt::Display for ValidationResult {
@@ -152,131 +928,259 @@ impl fmt::Display for ValidationResult {
         for violation in &self.placement_violations {
             writeln!(
                 formatter,
-                "{}: {:?}",
-                violation.house_index, violation.violation
+                "{}: {:?} with {:?}",
+                violation.house_index, violation.violation, violation.neighbors
             )?
         }
         Ok(())
     }
 }
 
-pub fn validate_solution(solution: &Solution, puzzle: &Puzzle) -> ValidationResult {
-    let mut placement_violations = Vec::new();
-
-    let mut has_house = vec![vec![false; puzzle.cols()]; puzzle.rows()];
+// Cells holding a house, either given by the puzzle or placed in the solution.
+pub fn house_grid(solution: &Solution, puzzle: &Puzzle) -> Vec<Vec<bool>> {
+    let mut has_house: Vec<Vec<bool>> = puzzle
+        .field
+        .iter()
+        .map(|line| line.iter().map(|&cell| cell == CellType::House).collect())
+        .collect();
     for placement in &solution.placements {
         has_house[placement.position.row][placement.position.col] = true;
     }
+    has_house
+}
 
-    // Check that each row and column is satisfied.
-    let mut row_status = vec![LineStatus::Underflow; puzzle.rows()];
-    for row in 0..puzzle.rows() {
-        let house_count = has_house[row].iter().filter(|&b| *b).count();
-        row_status[row] = match house_count.cmp(&puzzle.row_count[row]) {
-            std::cmp::Ordering::Less => LineStatus::Underflow,
-            std::cmp::Ordering::Equal => LineStatus::Match,
-            std::cmp::Ordering::Greater => LineStatus::Overflow,
-        };
-    }
-    let mut col_status = vec![LineStatus::Underflow; puzzle.cols()];
-    for col in 0..puzzle.cols() {
-        let mut house_count = 0;
-        for row in 0..puzzle.rows() {
-            house_count += has_house[row][col] as usize;
-        }
-        col_status[col] = match house_count.cmp(&puzzle.col_count[col]) {
-            std::cmp::Ordering::Less => LineStatus::Underflow,
-            std::cmp::Ordering::Equal => LineStatus::Match,
-            std::cmp::Ordering::Greater => LineStatus::Overflow,
-        };
+// Houses counted towards each row and each column, given houses included. Large
+// houses count twice.
+pub fn line_counts(solution: &Solution, puzzle: &Puzzle) -> (Vec<usize>, Vec<usize>) {
+    let has_house = house_grid(solution, puzzle);
+    let mut row_counts: Vec<usize> = has_house
+        .iter()
+        .map(|line| line.iter().filter(|&b| *b).count())
+        .collect();
+    let mut col_counts: Vec<usize> = (0..puzzle.cols())
+        .map(|col| has_house.iter().filter(|line| line[col]).count())
+        .collect();
+    for placement in &solution.placements {
+        let extra = placement.kind.weight() - 1;
+        row_counts[placement.position.row] += extra;
+        col_counts[placement.position.col] += extra;
     }
+    (row_counts, col_counts)
+}
+
+/// Checks a possibly partial solution against every rule of the puzzle.
+pub fn validate_solution(solution: &Solution, puzzle: &Puzzle) -> ValidationResult {
+    let mut placement_violations = Vec::new();
+
+    let has_house = house_grid(solution, puzzle);
+
+    // Check that each row and column is satisfied.
+    let (row_counts, col_counts) = line_counts(solution, puzzle);
+    let line_status = |house_count: usize, required: usize| match house_count.cmp(&required) {
+        std::cmp::Ordering::Less => LineStatus::Underflow,
+        std::cmp::Ordering::Equal => LineStatus::Match,
+        std::cmp::Ordering::Greater => LineStatus::Overflow,
+    };
+    let row_status: Vec<LineStatus> = row_counts
+        .iter()
+        .zip(&puzzle.row_count)
+        .map(|(&count, &required)| line_status(count, required))
+        .collect();
+    let col_status: Vec<LineStatus> = col_counts
+        .iter()
+        .zip(&puzzle.col_count)
+        .map(|(&count, &required)| line_status(count, required))
+        .collect();
 
     // Check that houses don't have other houses nearby.
     for (index, placement) in solution.placements.iter().enumerate() {
         let position = placement.position;
-        if count_adjacent_houses(position.row, position.col, &has_house, puzzle) > 0 {
+        let neighbors = adjacent_houses(position.row, position.col, &has_house, puzzle);
+        if !neighbors.is_empty() {
             placement_violations.push(PlacementViolation {
                 house_index: index,
+                position,
                 violation: ViolationType::AdjacentHouse,
+                neighbors,
+            })
+        }
+        let violation = match puzzle.neighborhood {
+            Neighborhood::Orthogonal => continue,
+            Neighborhood::King => ViolationType::DiagonalHouse,
+            Neighborhood::Knight => ViolationType::KnightHouse,
+        };
+        let neighbors = variant_neighbor_houses(position.row, position.col, &has_house, puzzle);
+        if !neighbors.is_empty() {
+            placement_violations.push(PlacementViolation {
+                house_index: index,
+                position,
+                violation,
+                neighbors,
             })
         }
     }
 
+    let constraint_violation = |row, col, count: usize, required| ConstraintViolation {
+        position: Position { row, col },
+        violation: match count.cmp(&required) {
+            std::cmp::Ordering::Less => ConstraintViolationType::Underflow,
+            std::cmp::Ordering::Equal => ConstraintViolationType::Match,
+            std::cmp::Ordering::Greater => ConstraintViolationType::Overflow,
+        },
+        count,
+        required,
+    };
     let mut constraint_violations = Vec::new();
-    for row in 0..puzzle.rows() {
-        for col in 0..puzzle.cols() {
-            match puzzle.field[row][col] {
-                CellType::Grass => {}
-                CellType::Tree => {}
-                CellType::Lake => {
-                    let count = count_houses_in_3x3(row, col, &has_house, puzzle);
-                    let t = match count.cmp(&3) {
-                        std::cmp::Ordering::Less => ConstraintViolationType::Underflow,
-                        std::cmp::Ordering::Equal => ConstraintViolationType::Match,
-                        std::cmp::Ordering::Greater => ConstraintViolationType::Overflow,
-                    };
-                    constraint_violations.push(ConstraintViolation {
-                        position: Position { row, col },
-                        violation: t,
-                    });
-                }
-                CellType::Mountain => {
-                    let count = count_diagnoal_houses(row, col, &has_house, puzzle);
-                    let t = match count.cmp(&2) {
-                        std::cmp::Ordering::Less => ConstraintViolationType::Underflow,
-                        std::cmp::Ordering::Equal => ConstraintViolationType::Match,
-                        std::cmp::Ordering::Greater => ConstraintViolationType::Overflow,
-                    };
-                    constraint_violations.push(ConstraintViolation {
-                        position: Position { row, col },
-                        violation: t,
-                    });
-                }
-            };
-        }
+    for Position { row, col } in puzzle.positions() {
+        match puzzle.field[row][col] {
+            CellType::Grass => {}
+            CellType::Tree => {}
+            CellType::Rock => {}
+            CellType::House => {}
+            CellType::Lake(required) => {
+                let count = count_houses_in_3x3(row, col, &has_house, puzzle);
+                constraint_violations.push(constraint_violation(row, col, count, required));
+            }
+            CellType::Mountain(required) => {
+                let count = count_diagnoal_houses(row, col, &has_house, puzzle);
+                constraint_violations.push(constraint_violation(row, col, count, required));
+            }
+        };
     }
 
-    let complete = row_status
-        .iter()
-        .all(|status| matches!(status, LineStatus::Match))
-        && col_status
-            .iter()
-            .all(|status| matches!(status, LineStatus::Match))
-        && placement_violations.is_empty()
-        && constraint_violations
-            .iter()
-            .all(|v| matches!(v.violation, ConstraintViolationType::Match));
-
-    ValidationResult {
+    let mut result = ValidationResult {
         row_status,
         col_status,
         placement_violations,
         constraint_violations,
-        complete,
+        complete: false,
+        row_counts,
+        col_counts,
+    };
+    result.complete = result.all_rules_met();
+    result
+}
+
+// Seconds per board cell allowed for three stars. Two stars allow three times as long.
+pub const STAR_SECONDS_PER_CELL: f32 = 3.0;
+
+// Rates a solve from 1 to 3 stars by the house placements and removals it took and
+// how long it took. The limits grow with the number of houses and cells, so bigger
+// boards aren't rated more harshly.
+pub fn star_rating(moves: usize, elapsed: Duration, puzzle: &Puzzle) -> u8 {
+    let given = puzzle
+        .field
+        .iter()
+        .flatten()
+        .filter(|&&cell| cell == CellType::House)
+        .count();
+    let houses = puzzle.row_count.iter().sum::<usize>().saturating_sub(given);
+    let (rows, cols) = puzzle.dims();
+    let seconds = elapsed.as_secs_f32();
+    let par_seconds = STAR_SECONDS_PER_CELL * (rows * cols) as f32;
+    if moves <= houses + houses / 2 && seconds <= par_seconds {
+        3
+    } else if moves <= houses * 3 && seconds <= par_seconds * 3.0 {
+        2
+    } else {
+        1
     }
 }
 
+// Empty grass cells that can't hold a house with the houses placed so far: their row
+// or column already has all its houses, or a house next to them rules them out.
+// Only follows from the line counts and adjacency, so it never solves the puzzle.
+pub fn forced_empty_cells(puzzle: &Puzzle, solution: &Solution) -> Vec<Position> {
+    let has_house = house_grid(solution, puzzle);
+    let (row_counts, col_counts) = line_counts(solution, puzzle);
+    puzzle
+        .grass_positions()
+        .filter(|&Position { row, col }| {
+            !has_house[row][col]
+                && (row_counts[row] >= puzzle.row_count[row]
+                    || col_counts[col] >= puzzle.col_count[col]
+                    || !adjacent_houses(row, col, &has_house, puzzle).is_empty()
+                    || !variant_neighbor_houses(row, col, &has_house, puzzle).is_empty())
+        })
+        .collect()
+}
+
+/// A single row or column of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Row(usize),
+    Col(usize),
+}
+
+// The unsatisfied row or column that needs the most of its remaining free cells,
+// which is where deductions are easiest to start. Free cells are empty grass cells
+// that `forced_empty_cells` doesn't rule out. None once every line that can still
+// take houses has all of them.
+pub fn most_constrained_line(puzzle: &Puzzle, solution: &Solution) -> Option<Line> {
+    let has_house = house_grid(solution, puzzle);
+    let forced_empty = forced_empty_cells(puzzle, solution);
+    let (rows, cols) = puzzle.dims();
+    let row_missing: Vec<usize> = (0..rows)
+        .map(|row| {
+            let placed = has_house[row].iter().filter(|&&b| b).count();
+            puzzle.row_count[row].saturating_sub(placed)
+        })
+        .collect();
+    let col_missing: Vec<usize> = (0..cols)
+        .map(|col| {
+            let placed = has_house.iter().filter(|line| line[col]).count();
+            puzzle.col_count[col].saturating_sub(placed)
+        })
+        .collect();
+    let mut row_free = vec![0; rows];
+    let mut col_free = vec![0; cols];
+    for position in puzzle.grass_positions() {
+        if !has_house[position.row][position.col] && !forced_empty.contains(&position) {
+            row_free[position.row] += 1;
+            col_free[position.col] += 1;
+        }
+    }
+    let lines = (0..rows)
+        .map(|row| (Line::Row(row), row_missing[row], row_free[row]))
+        .chain((0..cols).map(|col| (Line::Col(col), col_missing[col], col_free[col])));
+    // Lines without free cells left can't be helped by looking at them.
+    lines
+        .filter(|&(_, missing, free)| missing > 0 && free > 0)
+        // Compares missing / free without rounding, keeping the first of equal lines.
+        .reduce(|best, line| {
+            if line.1 * best.2 > best.1 * line.2 {
+                line
+            } else {
+                best
+            }
+        })
+        .map(|(line, _, _)| line)
+}
+
 pub fn count_diagnoal_houses(
     row: usize,
     col: usize,
-    has_house: &Vec<Vec<bool>>,
+    has_house: &[Vec<bool>],
     puzzle: &Puzzle,
 ) -> usize {
+    let start = Position { row, col };
     let mut count = 0;
     for drow in [-1, 1] {
         for dcol in [-1, 1] {
-            for d in 1..puzzle.rows().max(puzzle.cols()) {
-                let nrow = row as i32 + drow * d as i32;
-                let ncol = col as i32 + dcol * d as i32;
-                if !puzzle.is_valid(nrow, ncol) {
-                    break;
-                }
-                let nrow = nrow as usize;
-                let ncol = ncol as usize;
-                if has_house[nrow][ncol] {
-                    count += 1;
-                }
-            }
+            // Cells along the diagonal, out to the edge of the board.
+            let diagonal = std::iter::successors(start.offset(drow, dcol, puzzle), |cell| {
+                cell.offset(drow, dcol, puzzle)
+            });
+            count += diagonal
+                .take_while(|cell| {
+                    !puzzle.blocked_diagonals
+                        || matches!(
+                            puzzle.field[cell.row][cell.col],
+                            CellType::Grass | CellType::House
+                        )
+                })
+                .filter(|cell| has_house[cell.row][cell.col])
+                .count();
         }
     }
     count
@@ -285,341 +1189,1022 @@ pub fn count_diagnoal_houses(
 pub fn count_houses_in_3x3(
     row: usize,
     col: usize,
-    has_house: &Vec<Vec<bool>>,
+    has_house: &[Vec<bool>],
     puzzle: &Puzzle,
 ) -> usize {
-    let mut count = 0;
-    for drow in -1..=1 {
-        for dcol in -1..=1 {
-            let nrow = row as i32 + drow;
-            let ncol = col as i32 + dcol;
-            if !puzzle.is_valid(nrow, ncol) {
-                continue;
-            }
-            let nrow = nrow as usize;
-            let ncol = ncol as usize;
-            if has_house[nrow][ncol] {
-                count += 1;
-            }
-        }
-    }
-    count
+    let center = Position { row, col };
+    std::iter::once(center)
+        .chain(center.neighbors8(puzzle))
+        .filter(|cell| has_house[cell.row][cell.col])
+        .count()
 }
 
-pub fn count_adjacent_houses(
+pub fn adjacent_houses(
     row: usize,
     col: usize,
-    has_house: &Vec<Vec<bool>>,
+    has_house: &[Vec<bool>],
     puzzle: &Puzzle,
-) -> usize {
-    let mut count = 0;
-    for d in 0..4 {
-        let nrow = row as i32 + DROW[d];
-        let ncol = col as i32 + DCOL[d];
-        if !puzzle.is_valid(nrow, ncol) {
-            continue;
-        }
+) -> Vec<Position> {
+    houses_among(Position { row, col }.neighbors4(puzzle), has_house)
+}
 
-        let nrow = nrow as usize;
-        let ncol = ncol as usize;
-        if has_house[nrow][ncol] {
-            count += 1;
-            break;
-        }
+pub fn diagonal_houses(
+    row: usize,
+    col: usize,
+    has_house: &[Vec<bool>],
+    puzzle: &Puzzle,
+) -> Vec<Position> {
+    houses_among(Position { row, col }.diagonal_neighbors(puzzle), has_house)
+}
+
+pub fn knight_houses(
+    row: usize,
+    col: usize,
+    has_house: &[Vec<bool>],
+    puzzle: &Puzzle,
+) -> Vec<Position> {
+    houses_among(Position { row, col }.knight_neighbors(puzzle), has_house)
+}
+
+// Houses ruled out by the puzzle's neighborhood on top of the ones sharing a side.
+pub fn variant_neighbor_houses(
+    row: usize,
+    col: usize,
+    has_house: &[Vec<bool>],
+    puzzle: &Puzzle,
+) -> Vec<Position> {
+    match puzzle.neighborhood {
+        Neighborhood::Orthogonal => Vec::new(),
+        Neighborhood::King => diagonal_houses(row, col, has_house, puzzle),
+        Neighborhood::Knight => knight_houses(row, col, has_house, puzzle),
     }
-    count
 }
 
+fn houses_among(cells: impl Iterator<Item = Position>, has_house: &[Vec<bool>]) -> Vec<Position> {
+    cells.filter(|cell| has_house[cell.row][cell.col]).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GameLevel {
+    // Identifies the level in saves, replays and translations, so it must not change
+    // once released. The position in `all_levels` only decides the order.
+    pub id: String,
     pub name: String,
     pub puzzle: Puzzle,
+    // Intended number of house placements and removals to solve the level, if it has one.
+    pub par: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl GameLevel {
+    // Reads a level written by `Puzzle::to_level_string`.
+    pub fn from_level_string(s: &str) -> Result<GameLevel, PuzzleError> {
+        Ok(GameLevel::imported(parse_puzzle(s)?))
+    }
+
+    // A level brought in from outside the game, like a pasted or linked one.
+    pub fn imported(puzzle: Puzzle) -> GameLevel {
+        GameLevel {
+            id: "imported".into(),
+            name: "Imported".into(),
+            puzzle,
+            par: None,
+        }
+    }
+
+    // Rough difficulty estimate combining the board size, how much of the board is
+    // covered by terrain and how much backtracking the solver needs.
+    pub fn difficulty(&self) -> Difficulty {
+        let (rows, cols) = self.puzzle.dims();
+        let cells = (rows * cols) as f32;
+        let terrain = self
+            .puzzle
+            .field
+            .iter()
+            .flatten()
+            .filter(|&&cell| cell != CellType::Grass)
+            .count() as f32;
+        let effort = search_effort(&self.puzzle) as f32;
+        let score = effort.log2() + 2.0 * terrain / cells + cells / 16.0;
+        if score < 5.5 {
+            Difficulty::Easy
+        } else if score < 8.5 {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
 }
 
 #[rustfmt::skip]
 pub fn twin_lakes() -> GameLevel {
     GameLevel {
+        id: "twin-lakes".into(),
         name: "Twin Lakes".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "....",
                "L.L.",
                "....",
                "..T.",
-            ]),
-            row_count: vec![2, 1, 2, 1],
-            col_count: vec![2, 1, 1, 2],
-        },
+            ]).unwrap(),
+            vec![2, 1, 2, 1],
+            vec![2, 1, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn forest() -> GameLevel {
     GameLevel {
+        id: "forest".into(),
         name: "Forest".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "..T.",
                ".T..",
                "..T.",
                ".T..",
-            ]),
-            row_count: vec![2, 1, 2, 1],
-            col_count: vec![2, 1, 1, 2],
-        },
+            ]).unwrap(),
+            vec![2, 1, 2, 1],
+            vec![2, 1, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn green_mountain() -> GameLevel {
     GameLevel {
+        id: "green-mountain".into(),
         name: "Green Mountain".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "..T.",
                ".M..",
                ".TT.",
                "T...",
-            ]),
-            row_count: vec![1, 1, 1, 2],
-            col_count: vec![1, 2, 1, 1],
-        },
+            ]).unwrap(),
+            vec![1, 1, 1, 2],
+            vec![1, 2, 1, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn green_lake() -> GameLevel {
     GameLevel {
+        id: "green-lake".into(),
         name: "Green Lake".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "....",
                ".TL.",
                "..T.",
                "....",
-            ]),
-            row_count: vec![1, 1, 1, 2],
-            col_count: vec![1, 2, 1, 1],
-        },
+            ]).unwrap(),
+            vec![1, 1, 1, 2],
+            vec![1, 2, 1, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn mountain_lakes() -> GameLevel {
     GameLevel {
+        id: "mountain-lakes".into(),
         name: "Mountain lakes".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                ".....",
                ".T.LT",
                "..M..",
                "..TL.",
                ".....",
-            ]),
-            row_count: vec![2, 1, 1, 2, 1],
-            col_count: vec![1, 1, 2, 1, 2],
-        },
+            ]).unwrap(),
+            vec![2, 1, 1, 2, 1],
+            vec![1, 1, 2, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn twin_mountains() -> GameLevel {
     GameLevel {
+        id: "twin-mountains".into(),
         name: "Twin Mountains".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                ".....",
                ".....",
                ".M.M.",
                ".....",
                ".....",
-            ]),
-            row_count: vec![2, 1, 0, 1, 2],
-            col_count: vec![2, 0, 2, 0, 2],
-        },
+            ]).unwrap(),
+            vec![2, 1, 0, 1, 2],
+            vec![2, 0, 2, 0, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn lonely_mountain() -> GameLevel {
     GameLevel {
+        id: "lonely-mountain".into(),
         name: "Lonely Mountain".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                ".....",
                ".L...",
                "...L.",
                ".M...",
                ".....",
-            ]),
-            row_count: vec![1, 2, 2, 2, 2],
-            col_count: vec![2, 1, 2, 2, 2],
-        },
+            ]).unwrap(),
+            vec![1, 2, 2, 2, 2],
+            vec![2, 1, 2, 2, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn lake_valley() -> GameLevel {
     GameLevel {
+        id: "lake-valley".into(),
         name: "Lake Valley".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                ".....",
                ".L.L.",
                ".....",
                ".L.L.",
                ".....",
-            ]),
-            row_count: vec![1, 2, 2, 1, 2],
-            col_count: vec![2, 1, 2, 1, 2],
-        },
+            ]).unwrap(),
+            vec![1, 2, 1, 2, 2],
+            vec![2, 1, 2, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn first_level() -> GameLevel {
     GameLevel {
+        id: "first".into(),
         name: "First".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                ".",
-            ]),
-            row_count: vec![1],
-            col_count: vec![1],
-        },
+            ]).unwrap(),
+            vec![1],
+            vec![1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn neighbors() -> GameLevel {
     GameLevel {
+        id: "neighbors".into(),
         name: "Neighbors".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "..",
                "..",
-            ]),
-            row_count: vec![1, 1],
-            col_count: vec![1, 1],
-        },
+               "..",
+            ]).unwrap(),
+            vec![1, 1, 1],
+            vec![2, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn meadow() -> GameLevel {
     GameLevel {
+        id: "meadow".into(),
         name: "Meadow".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "...",
                "...",
-            ]),
-            row_count: vec![2, 1],
-            col_count: vec![1, 1, 1],
-        },
+            ]).unwrap(),
+            vec![2, 1],
+            vec![1, 1, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn trees_level() -> GameLevel {
     GameLevel {
+        id: "trees".into(),
         name: "Trees".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "..T.",
                ".T..",
-            ]),
-            row_count: vec![2, 2],
-            col_count: vec![1, 1, 1, 1],
-        },
+            ]).unwrap(),
+            vec![2, 2],
+            vec![1, 1, 1, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn single_lake() -> GameLevel {
     GameLevel {
+        id: "lake".into(),
         name: "Lake".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "..T",
                ".LT",
                "..T",
-            ]),
-            row_count: vec![1, 1, 1],
-            col_count: vec![1, 2, 0],
-        },
+            ]).unwrap(),
+            vec![1, 1, 1],
+            vec![1, 2, 0],
+        ).unwrap(),
+        par: None,
+    }
+}
+
+#[rustfmt::skip]
+pub fn long_lake() -> GameLevel {
+    GameLevel {
+        id: "long-lake".into(),
+        name: "Long Lake".into(),
+        puzzle: Puzzle::new(
+            parse_field(vec![
+               ".....T",
+               "T.....",
+               "...L..",
+               "...T..",
+            ]).unwrap(),
+            vec![1, 3, 1, 2],
+            vec![1, 1, 1, 1, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn mountain() -> GameLevel {
     GameLevel {
+        id: "mountain".into(),
         name: "Mountain".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "....",
                ".M..",
                "....",
                "....",
-            ]),
-            row_count: vec![2, 0, 1, 1],
-            col_count: vec![1, 1, 2, 0],
-        },
+            ]).unwrap(),
+            vec![2, 0, 1, 1],
+            vec![1, 1, 2, 0],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn large_lake() -> GameLevel {
     GameLevel {
+        id: "large-lake".into(),
         name: "Large Lake".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "....",
                "..L.",
                "....",
                "....",
-            ]),
-            row_count: vec![1, 1, 2, 2],
-            col_count: vec![2, 2, 1, 1],
-        },
+            ]).unwrap(),
+            vec![1, 1, 2, 2],
+            vec![2, 2, 1, 1],
+        ).unwrap(),
+        par: None,
     }
 }
 
 #[rustfmt::skip]
 pub fn village() -> GameLevel {
     GameLevel {
+        id: "village".into(),
         name: "Village".into(),
-        puzzle: Puzzle {
-            field: parse_field(vec![
+        puzzle: Puzzle::new(
+            parse_field(vec![
                "......",
                ".M.M..",
                "......",
                ".L.L..",
                "......",
                "TTTTTT",
-            ]),
-            row_count: vec![2, 1, 3, 1, 3, 0],
-            col_count: vec![2, 1, 2, 2, 1, 2],
-        },
+            ]).unwrap(),
+            vec![2, 1, 3, 1, 3, 0],
+            vec![2, 1, 2, 2, 1, 2],
+        ).unwrap(),
+        par: None,
     }
 }
 
-pub fn all_levels() -> Vec<GameLevel> {
-    vec![
-        first_level(),
-        neighbors(),
-        meadow(),
-        trees_level(),
-        single_lake(),
-        mountain(),
-        large_lake(),
-        green_lake(),
-        forest(),
-        twin_lakes(),
-        green_mountain(),
-        mountain_lakes(),
-        twin_mountains(),
-        lake_valley(),
-        lonely_mountain(),
-        village(),
-    ]
+// The lake asks for four houses instead of the usual three.
+#[rustfmt::skip]
+pub fn busy_lake() -> GameLevel {
+    GameLevel {
+        id: "busy-lake".into(),
+        name: "Busy Lake".into(),
+        puzzle: Puzzle::new(
+            parse_field(vec![
+               "....T",
+               ".L4...",
+               ".....",
+               "...T.",
+               ".....",
+            ]).unwrap(),
+            vec![2, 2, 2, 0, 1],
+            vec![1, 2, 2, 2, 0],
+        ).unwrap(),
+        par: None,
+    }
+}
+
+// A named world of levels. Each pack unlocks once most of the previous one is
+// complete, see `PACK_UNLOCK_SHARE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelPack {
+    pub name: String,
+    pub levels: Vec<GameLevel>,
+}
+
+// Share of a pack's levels to complete before the next pack unlocks.
+pub const PACK_UNLOCK_SHARE: f32 = 0.75;
+
+// The built-in packs, built on first use and shared afterwards.
+pub fn all_packs() -> &'static [LevelPack] {
+    static PACKS: OnceLock<Vec<LevelPack>> = OnceLock::new();
+    PACKS.get_or_init(|| {
+        vec![
+            // Every rule, one level at a time.
+            LevelPack {
+                name: "Skyland".into(),
+                levels: vec![
+                    first_level(),
+                    neighbors(),
+                    meadow(),
+                    trees_level(),
+                    single_lake(),
+                    long_lake(),
+                    mountain(),
+                    large_lake(),
+                    green_lake(),
+                ],
+            },
+            // Larger boards mixing lakes, mountains and forests.
+            LevelPack {
+                name: "Highlands".into(),
+                levels: vec![
+                    forest(),
+                    twin_lakes(),
+                    green_mountain(),
+                    mountain_lakes(),
+                    twin_mountains(),
+                    lake_valley(),
+                    lonely_mountain(),
+                    village(),
+                    busy_lake(),
+                ],
+            },
+        ]
+    })
+}
+
+// The levels of every pack, one after the other. Levels are played by their index
+// in here (see `GameState::current_level`) and saved by their id.
+pub fn all_levels() -> &'static [GameLevel] {
+    static LEVELS: OnceLock<Vec<GameLevel>> = OnceLock::new();
+    LEVELS.get_or_init(|| {
+        all_packs()
+            .iter()
+            .flat_map(|pack| pack.levels.iter().cloned())
+            .collect()
+    })
+}
+
+// The difficulty of every level in `all_levels()`, at the same index. Each one runs
+// the solver, so they are worked out once.
+pub fn level_difficulties() -> &'static [Difficulty] {
+    static DIFFICULTIES: OnceLock<Vec<Difficulty>> = OnceLock::new();
+    DIFFICULTIES.get_or_init(|| all_levels().iter().map(GameLevel::difficulty).collect())
+}
+
+// Indices of the pack's levels in `all_levels()`.
+pub fn pack_range(pack: usize) -> Range<usize> {
+    let packs = all_packs();
+    let start = packs[..pack].iter().map(|pack| pack.levels.len()).sum();
+    start..start + packs[pack].levels.len()
+}
+
+// The pack holding level `index` of `all_levels()` and the level's place in it, or
+// None for levels outside `all_levels()`. The game keeps the flat index and works out
+// the pack with this where it needs it, like for locked packs.
+pub fn pack_of(index: usize) -> Option<(usize, usize)> {
+    (0..all_packs().len()).find_map(|pack| {
+        let range = pack_range(pack);
+        range.contains(&index).then(|| (pack, index - range.start))
+    })
+}
+
+// Index into `all_levels()` of the level at `index` in `pack`, the reverse of `pack_of`.
+pub fn level_index(pack: usize, index: usize) -> usize {
+    pack_range(pack).start + index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve;
+
+    fn solution(positions: &[(usize, usize)]) -> Solution {
+        Solution {
+            placements: positions
+                .iter()
+                .map(|&(row, col)| Placement {
+                    position: Position { row, col },
+                    kind: HouseKind::Small,
+                })
+                .collect(),
+        }
+    }
+
+    fn puzzle(field: Vec<&str>, row_count: Vec<usize>, col_count: Vec<usize>) -> Puzzle {
+        Puzzle::new(parse_field(field).unwrap(), row_count, col_count).unwrap()
+    }
+
+    #[test]
+    fn complete_only_when_every_rule_is_met() {
+        let puzzle = single_lake().puzzle;
+        let correct = solve(&puzzle).unwrap();
+        assert!(validate_solution(&correct, &puzzle).complete);
+        // One house more overflows its row and column.
+        for position in puzzle.grass_positions() {
+            if correct.placements.iter().any(|p| p.position == position) {
+                continue;
+            }
+            let mut overflowing = correct.clone();
+            overflowing.placements.push(Placement {
+                position,
+                kind: HouseKind::Small,
+            });
+            assert!(!validate_solution(&overflowing, &puzzle).complete);
+        }
+    }
+
+    #[test]
+    fn lake_and_mountain_counts_round_trip() {
+        let field = parse_field(vec!["L4.LL12", "M1.MM"]).unwrap();
+        assert_eq!(
+            field[0],
+            [
+                CellType::Lake(4),
+                CellType::Grass,
+                CellType::Lake(DEFAULT_LAKE_COUNT),
+                CellType::Lake(12)
+            ]
+        );
+        assert_eq!(
+            field[1],
+            [
+                CellType::Mountain(1),
+                CellType::Grass,
+                CellType::Mountain(DEFAULT_MOUNTAIN_COUNT),
+                CellType::Mountain(DEFAULT_MOUNTAIN_COUNT)
+            ]
+        );
+        let puzzle = Puzzle::new(field, vec![0, 0], vec![0, 0, 0, 0]).unwrap();
+        assert_eq!(parse_puzzle(&puzzle.to_level_string()), Ok(puzzle.clone()));
+        assert_eq!(Puzzle::decode(&puzzle.encode().unwrap()), Ok(puzzle));
+    }
+
+    #[test]
+    fn line_counts_match_the_houses_placed() {
+        let puzzle = puzzle(vec!["....", "....", "...."], vec![0; 3], vec![0; 4]);
+        // x.x.
+        // ...x
+        // x...
+        let result = validate_solution(&solution(&[(0, 0), (0, 2), (1, 3), (2, 0)]), &puzzle);
+        assert_eq!(result.row_counts, [2, 1, 1]);
+        assert_eq!(result.col_counts, [2, 0, 1, 1]);
+    }
+
+    #[test]
+    fn diagonal_houses_conflict_only_in_the_king_neighborhood() {
+        let mut puzzle = puzzle(vec!["...", "...", "..."], vec![1, 1, 0], vec![1, 1, 0]);
+        // x..
+        // .x.
+        let diagonal = solution(&[(0, 0), (1, 1)]);
+
+        let result = validate_solution(&diagonal, &puzzle);
+        assert!(result.placement_violations.is_empty());
+        assert!(result.complete);
+
+        puzzle.neighborhood = Neighborhood::King;
+        let result = validate_solution(&diagonal, &puzzle);
+        let violations: Vec<_> = result
+            .placement_violations
+            .iter()
+            .map(|violation| {
+                (
+                    violation.position,
+                    violation.violation,
+                    violation.neighbors.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            violations,
+            [
+                (
+                    Position { row: 0, col: 0 },
+                    ViolationType::DiagonalHouse,
+                    vec![Position { row: 1, col: 1 }]
+                ),
+                (
+                    Position { row: 1, col: 1 },
+                    ViolationType::DiagonalHouse,
+                    vec![Position { row: 0, col: 0 }]
+                ),
+            ]
+        );
+        assert!(!result.complete);
+    }
+
+    #[test]
+    fn level_strings_parse_back_to_the_same_puzzle() {
+        for level in all_levels() {
+            let puzzle = &level.puzzle;
+            assert_eq!(parse_puzzle(&puzzle.to_level_string()).as_ref(), Ok(puzzle));
+        }
+        let mut variants = single_lake().puzzle;
+        variants.neighborhood = Neighborhood::Knight;
+        variants.blocked_diagonals = true;
+        variants.house_kinds = true;
+        assert_eq!(parse_puzzle(&variants.to_level_string()), Ok(variants));
+    }
+
+    #[test]
+    fn unknown_cells_are_reported_where_they_are() {
+        assert_eq!(
+            parse_field(vec!["..", ".?"]),
+            Err(PuzzleError::UnknownCell {
+                row: 1,
+                col: 1,
+                found: '?'
+            })
+        );
+        // Columns count cells, so a lake's count doesn't shift them.
+        assert_eq!(
+            parse_field(vec!["L4#"]),
+            Err(PuzzleError::UnknownCell {
+                row: 0,
+                col: 1,
+                found: '#'
+            })
+        );
+    }
+
+    #[test]
+    fn blocked_diagonals_stop_at_obstacles() {
+        // The tree stands between the mountain and the house at the far corner.
+        let mut puzzle = puzzle(vec!["M..", ".T.", "..."], vec![0; 3], vec![0; 3]);
+        let has_house = house_grid(&solution(&[(2, 2)]), &puzzle);
+        assert_eq!(count_diagnoal_houses(0, 0, &has_house, &puzzle), 1);
+        puzzle.blocked_diagonals = true;
+        assert_eq!(count_diagnoal_houses(0, 0, &has_house, &puzzle), 0);
+
+        // Grass and houses don't block the view.
+        let has_house = house_grid(&solution(&[(1, 1), (2, 2)]), &puzzle);
+        puzzle.field[1][1] = CellType::Grass;
+        assert_eq!(count_diagnoal_houses(0, 0, &has_house, &puzzle), 2);
+    }
+
+    #[test]
+    fn cloned_levels_validate_like_the_original() {
+        for level in all_levels() {
+            let solved = solve(&level.puzzle).unwrap();
+            // Houses on the first grass cells, next to each other.
+            let crowded = Solution {
+                placements: level
+                    .puzzle
+                    .grass_positions()
+                    .take(3)
+                    .map(|position| Placement {
+                        position,
+                        kind: HouseKind::Small,
+                    })
+                    .collect(),
+            };
+            let copy = level.clone();
+            assert_eq!(&copy, level);
+            for solution in [solved, crowded, Solution::default()] {
+                assert_eq!(
+                    validate_solution(&solution.clone(), &copy.puzzle),
+                    validate_solution(&solution, &level.puzzle)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn level_counts_are_feasible() {
+        for level in all_levels() {
+            assert!(level.puzzle.counts_feasible(), "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn counts_beyond_the_room_in_a_line_are_infeasible() {
+        // Three houses can't share a row of three cells without touching.
+        let mut puzzle = puzzle(vec!["..."], vec![3], vec![1, 1, 1]);
+        assert!(!puzzle.counts_feasible());
+        // Large houses count twice, so two of them make room for it.
+        puzzle.house_kinds = true;
+        assert!(puzzle.counts_feasible());
+    }
+
+    #[test]
+    fn neighbor_helpers_match_the_inline_loops() {
+        let puzzle = puzzle(vec!["....", "....", "...."], vec![0; 3], vec![0; 4]);
+        let sorted = |mut positions: Vec<Position>| {
+            positions.sort_by_key(|position| (position.row, position.col));
+            positions
+        };
+        for position in puzzle.positions() {
+            let (mut sides, mut corners) = (Vec::new(), Vec::new());
+            for drow in -1..=1 {
+                for dcol in -1..=1 {
+                    let (row, col) = (position.row as i32 + drow, position.col as i32 + dcol);
+                    if (drow, dcol) == (0, 0) || !puzzle.is_valid(row, col) {
+                        continue;
+                    }
+                    let neighbor = Position {
+                        row: row as usize,
+                        col: col as usize,
+                    };
+                    if drow == 0 || dcol == 0 {
+                        sides.push(neighbor);
+                    } else {
+                        corners.push(neighbor);
+                    }
+                }
+            }
+            let all = sorted([sides.clone(), corners.clone()].concat());
+            assert_eq!(
+                sorted(position.neighbors4(&puzzle).collect()),
+                sorted(sides)
+            );
+            assert_eq!(
+                sorted(position.diagonal_neighbors(&puzzle).collect()),
+                sorted(corners)
+            );
+            assert_eq!(sorted(position.neighbors8(&puzzle).collect()), all);
+        }
+
+        // A corner, an edge and an inner cell.
+        let counts = |row, col| {
+            let position = Position { row, col };
+            (
+                position.neighbors4(&puzzle).count(),
+                position.diagonal_neighbors(&puzzle).count(),
+                position.neighbors8(&puzzle).count(),
+            )
+        };
+        assert_eq!(counts(0, 0), (2, 1, 3));
+        assert_eq!(counts(2, 3), (2, 1, 3));
+        assert_eq!(counts(0, 1), (3, 2, 5));
+        assert_eq!(counts(1, 1), (4, 4, 8));
+    }
+
+    #[test]
+    fn every_failure_category_keeps_the_level_incomplete() {
+        // A line with too many houses.
+        let overflow = puzzle(vec!["..."], vec![1], vec![1, 0, 1]);
+        let result = validate_solution(&solution(&[(0, 0), (0, 2)]), &overflow);
+        assert_eq!(result.row_status, [LineStatus::Overflow]);
+        assert!(!result.complete);
+
+        // Houses sharing a side, with every line count met.
+        let adjacent = puzzle(vec![".."], vec![2], vec![1, 1]);
+        let result = validate_solution(&solution(&[(0, 0), (0, 1)]), &adjacent);
+        assert!(result
+            .row_status
+            .iter()
+            .all(|&status| status == LineStatus::Match));
+        assert_eq!(result.placement_violations.len(), 2);
+        assert!(!result.complete);
+
+        // A lake with fewer houses around it than it needs.
+        let lake = puzzle(vec!["L..", "..."], vec![0, 1], vec![0, 0, 1]);
+        let result = validate_solution(&solution(&[(1, 2)]), &lake);
+        assert_eq!(
+            result.constraint_violations[0].violation,
+            ConstraintViolationType::Underflow
+        );
+        assert!(!result.complete);
+
+        // A mountain seeing more houses than it needs.
+        let mountain = puzzle(vec!["M1..", "...", "..."], vec![0, 1, 1], vec![0, 1, 1]);
+        let result = validate_solution(&solution(&[(1, 1), (2, 2)]), &mountain);
+        assert_eq!(
+            result.constraint_violations[0].violation,
+            ConstraintViolationType::Overflow
+        );
+        assert!(!result.complete);
+
+        // The mountain board with every rule met.
+        let mountain = puzzle(vec!["M1..", "...", "..."], vec![0, 0, 1], vec![0, 0, 1]);
+        let result = validate_solution(&solution(&[(2, 2)]), &mountain);
+        assert!(result.placement_violations.is_empty());
+        assert_eq!(
+            result.constraint_violations[0].violation,
+            ConstraintViolationType::Match
+        );
+        assert!(result.complete);
+    }
+
+    #[test]
+    fn cell_status_reports_conflicts_and_constraints() {
+        let row = puzzle(vec!["..."], vec![2], vec![1, 1, 0]);
+        let result = validate_solution(&solution(&[(0, 0), (0, 1)]), &row);
+        assert_eq!(
+            result.cell_status(Position { row: 0, col: 0 }),
+            CellStatus::Conflict
+        );
+        assert_eq!(
+            result.cell_status(Position { row: 0, col: 1 }),
+            CellStatus::Conflict
+        );
+        assert_eq!(
+            result.cell_status(Position { row: 0, col: 2 }),
+            CellStatus::Fine
+        );
+
+        let lake = puzzle(vec!["L1..", "..."], vec![0, 1], vec![0, 1, 0]);
+        let result = validate_solution(&solution(&[(1, 1)]), &lake);
+        assert_eq!(
+            result.cell_status(Position { row: 0, col: 0 }),
+            CellStatus::Constraint {
+                status: ConstraintViolationType::Match,
+                count: 1,
+                required: 1,
+            }
+        );
+        assert_eq!(
+            result.cell_status(Position { row: 1, col: 1 }),
+            CellStatus::Fine
+        );
+    }
+
+    #[test]
+    fn report_names_each_broken_rule() {
+        let lake = puzzle(vec!["L1..", "..."], vec![0, 2], vec![1, 0, 1]);
+        let report = validate_solution(&solution(&[(1, 0), (1, 1)]), &lake).report(&lake);
+        let keys: Vec<_> = report.iter().map(|line| line.key).collect();
+        assert_eq!(
+            keys,
+            [
+                "check.column_over",
+                "check.column_short",
+                "check.houses_share_side",
+                "check.lake_over",
+            ]
+        );
+        assert_eq!(report[2].args[2], ("other_row", "2".to_string()));
+        let single = puzzle(vec!["."], vec![1], vec![1]);
+        let solved = validate_solution(&solution(&[(0, 0)]), &single);
+        assert_eq!(
+            solved.report(&single),
+            [ReportLine::new("check.no_problems", vec![])]
+        );
+    }
+
+    #[test]
+    fn level_ids_are_unique() {
+        let mut ids: Vec<_> = all_levels().iter().map(|level| level.id.as_str()).collect();
+        ids.sort();
+        let count = ids.len();
+        ids.dedup();
+        assert_eq!(ids.len(), count, "two levels share an id");
+    }
+
+    #[test]
+    fn every_level_belongs_to_one_pack() {
+        assert!(all_packs().len() >= 2);
+        for (pack, level_pack) in all_packs().iter().enumerate() {
+            for index in 0..level_pack.levels.len() {
+                assert_eq!(pack_of(level_index(pack, index)), Some((pack, index)));
+            }
+        }
+        assert_eq!(pack_of(all_levels().len()), None);
+    }
+
+    #[test]
+    fn each_neighborhood_rules_out_its_own_pairs() {
+        let mut puzzle = puzzle(vec!["...", "...", "..."], vec![1, 0, 1], vec![1, 1, 0]);
+        // x..
+        // ...
+        // .x.
+        let knight = solution(&[(0, 0), (2, 1)]);
+        // x..
+        // .x.
+        let diagonal = solution(&[(0, 0), (1, 1)]);
+        for (neighborhood, diagonal_legal, knight_legal) in [
+            (Neighborhood::Orthogonal, true, true),
+            (Neighborhood::King, false, true),
+            (Neighborhood::Knight, true, false),
+        ] {
+            puzzle.neighborhood = neighborhood;
+            let result = validate_solution(&diagonal, &puzzle);
+            assert_eq!(result.placement_violations.is_empty(), diagonal_legal);
+            let result = validate_solution(&knight, &puzzle);
+            assert_eq!(result.placement_violations.is_empty(), knight_legal);
+            if !knight_legal {
+                assert_eq!(
+                    result.placement_violations[0].violation,
+                    ViolationType::KnightHouse
+                );
+            }
+            assert_eq!(result.complete, knight_legal, "{neighborhood:?}");
+        }
+    }
+
+    #[test]
+    fn level_codes_round_trip() {
+        for level in all_levels() {
+            let code = level.puzzle.encode().unwrap();
+            assert_eq!(
+                Puzzle::decode(&code),
+                Ok(level.puzzle.clone()),
+                "{}",
+                level.id
+            );
+        }
+        let mut puzzle = puzzle(vec!["L..", ".M1T"], vec![1, 0], vec![0, 0, 1]);
+        for neighborhood in [Neighborhood::King, Neighborhood::Knight] {
+            puzzle.neighborhood = neighborhood;
+            puzzle.blocked_diagonals = neighborhood == Neighborhood::King;
+            puzzle.house_kinds = neighborhood == Neighborhood::Knight;
+            assert_eq!(
+                Puzzle::decode(&puzzle.encode().unwrap()),
+                Ok(puzzle.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn broken_level_codes_are_rejected() {
+        let code = busy_lake().puzzle.encode().unwrap();
+        let cut_short = Err(PuzzleError::InvalidEncoding("it is cut short"));
+        assert_eq!(Puzzle::decode(&code[..code.len() / 2]), cut_short);
+        assert_eq!(Puzzle::decode(""), cut_short);
+        assert_eq!(
+            Puzzle::decode("not a code!"),
+            Err(PuzzleError::InvalidEncoding("it is not base64"))
+        );
+    }
+
+    #[test]
+    fn a_5x5_level_code_is_short() {
+        let level = all_levels()
+            .iter()
+            .find(|level| level.puzzle.dims() == (5, 5))
+            .unwrap();
+        // 3 header bytes, 3 bits per count and 3 per cell: 129 bits in 23 characters.
+        assert!(level.puzzle.encode().unwrap().len() <= 24);
+    }
+
+    #[test]
+    fn puzzles_beyond_the_code_limits_are_refused() {
+        let wide = Puzzle::new(vec![vec![CellType::Grass; 256]], vec![0], vec![0; 256]).unwrap();
+        assert!(matches!(wide.encode(), Err(PuzzleError::CannotEncode(_))));
+        let mut steep = puzzle(vec!["M.."], vec![0], vec![0; 3]);
+        steep.field[0][0] = CellType::Mountain(16);
+        assert!(matches!(steep.encode(), Err(PuzzleError::CannotEncode(_))));
+        steep.field[0][0] = CellType::Mountain(15);
+        assert!(steep.encode().is_ok());
+    }
 }
@@ -0,0 +1,10 @@
+//! Puzzle model, rule checking and solver of the game, without any Bevy dependency.
+//!
+//! - [`level`] has the board types ([`level::Puzzle`], [`level::Solution`],
+//!   [`level::CellType`]), the built-in levels and [`level::validate_solution`].
+//! - [`solver`] finds and counts solutions of a puzzle.
+//! - [`generator`] builds random puzzles with a unique solution.
+
+pub mod generator;
+pub mod level;
+pub mod solver;
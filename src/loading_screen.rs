@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::lang::Lang;
+use crate::tile_atlas::TileAtlas;
+use crate::AppState;
+
+pub struct LoadingScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for LoadingScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinLoadingTime>()
+            .add_systems(OnEnter(self.0), create_loading_screen)
+            .add_systems(Update, finish_loading.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_loading_screen);
+    }
+}
+
+pub const LOADING_BACKGROUND: Color = Color::rgb(0.1, 0.12, 0.2);
+
+// `Lang` keys of the tips.
+const TIPS: &[&str] = &[
+    "loading.tip.corners",
+    "loading.tip.lake",
+    "loading.tip.mountain",
+    "loading.tip.full_row",
+    "loading.tip.markers",
+    "loading.tip.grass",
+    "loading.tip.no_choice",
+];
+
+// Shortest time the loading screen stays up, so its tip can be read.
+#[derive(Resource)]
+pub struct MinLoadingTime(pub Duration);
+
+impl Default for MinLoadingTime {
+    fn default() -> Self {
+        Self(Duration::from_secs_f32(1.5))
+    }
+}
+
+#[derive(Component)]
+struct OnLoadingScreen;
+
+#[derive(Resource)]
+struct LoadingTimer(Timer);
+
+fn create_loading_screen(
+    mut commands: Commands,
+    min_loading_time: Res<MinLoadingTime>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    commands.insert_resource(LoadingTimer(Timer::new(
+        min_loading_time.0,
+        TimerMode::Once,
+    )));
+    // A new tip on every switch.
+    let tip = TIPS.choose(&mut thread_rng()).copied().unwrap_or_default();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: LOADING_BACKGROUND.into(),
+                ..default()
+            },
+            OnLoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                lang.t("loading.tip"),
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 80.0,
+                    color: crate::CUSTOM_ORANGE,
+                },
+            ));
+            parent.spawn(
+                TextBundle::from_section(
+                    lang.t(tip),
+                    TextStyle {
+                        font: server.load("NotoSerif-SemiBold.ttf"),
+                        font_size: 28.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center)
+                .with_style(Style {
+                    max_width: Val::Px(700.0),
+                    ..default()
+                }),
+            );
+        });
+}
+
+// Also waits for the tile atlas when it is on, so the board is drawn from it right away.
+fn finish_loading(
+    time: Res<Time>,
+    mut timer: ResMut<LoadingTimer>,
+    tile_atlas: Res<TileAtlas>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if timer.0.tick(time.delta()).finished() && (!tile_atlas.enabled || tile_atlas.is_ready()) {
+        app_state.set(AppState::InGame);
+    }
+}
+
+fn destroy_loading_screen(mut commands: Commands, query: Query<Entity, With<OnLoadingScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<LoadingTimer>();
+}
@@ -1,30 +1,123 @@
-use bevy::audio::PlaybackMode;
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 use bevy::window::{close_on_esc, WindowMode};
+use ludum_dare_54::{generator, level, solver};
 
-use self::game_screen::GameScreenPlugin;
+use self::achievements::{Achievements, AchievementsPlugin};
+use self::achievements_screen::AchievementsScreenPlugin;
+use self::camera::{GameCamera, GameCameraPlugin};
+use self::check_report::CheckReportPlugin;
+use self::confirm_dialog::ConfirmDialogPlugin;
+use self::daily::DailyChallengePlugin;
+use self::debug_overlay::DebugOverlayPlugin;
+use self::editor_screen::EditorScreenPlugin;
+use self::fade::FadePlugin;
+use self::game_complete_screen::GameCompleteScreenPlugin;
+use self::game_screen::{GameScreenPlugin, RuleMode};
+use self::hint::{HintPlugin, HINTS_PER_LEVEL};
 use self::input::GameInputPlugin;
-use self::level::Solution;
+use self::lang::{Lang, LangPlugin};
+use self::level::{validate_solution, Solution};
+use self::loading_screen::LoadingScreenPlugin;
 use self::main_menu_screen::MainMenuScreenPlugin;
+use self::music::{MusicDuck, MusicPlugin};
+use self::pause_menu::PauseMenuPlugin;
+use self::pointer::PointerPlugin;
+use self::replay::ReplayPlugin;
+use self::save::{SaveData, SavePlugin};
 use self::select_level_screen::SelectLevelScreenPlugin;
+use self::settings_screen::SettingsScreenPlugin;
+use self::share::SharePlugin;
+use self::snapshot::SnapshotPlugin;
+use self::stats::StatsPlugin;
+use self::stats_screen::StatsScreenPlugin;
+use self::theme::{Theme, ThemePlugin, TileFiltering};
+use self::tile_atlas::TileAtlasPlugin;
+use self::toast::ToastPlugin;
+use self::tooltip::TerrainTooltipPlugin;
+use self::tutorial::TutorialPlugin;
+use self::world_select_screen::WorldSelectScreenPlugin;
 
+mod achievements;
+mod achievements_screen;
+mod camera;
+mod check_report;
+mod clipboard;
+mod confirm_dialog;
+mod daily;
+mod debug_overlay;
+mod editor_screen;
+mod fade;
+mod game_complete_screen;
 mod game_screen;
+mod hint;
 mod input;
-mod level;
+mod lang;
+mod loading_screen;
 mod main_menu_screen;
+mod music;
+mod pause_menu;
+mod pointer;
+mod replay;
+mod save;
 mod select_level_screen;
+mod settings_screen;
+mod share;
+mod snapshot;
+mod stats;
+mod stats_screen;
+mod theme;
+mod tile_atlas;
+mod toast;
+mod tooltip;
+mod tutorial;
+#[cfg(target_arch = "wasm32")]
+mod web_drop;
+mod world_select_screen;
 
 pub const TEXT_FONT_NAME: &str = "NanumBrushScript-Regular.ttf";
 pub const CUSTOM_ORANGE: Color = Color::rgb(238.0 / 256.0, 145.0 / 256.0, 2.0 / 256.0);
-pub const SKY_COLOR: Color = Color::rgb(220.0 / 255.0, 238.0 / 255.0, 255.0 / 255.0);
+pub const SKY_COLOR: Color = Color::rgb(220.0 / 255.0, 238.0 / 255.0, 1.0);
+
+// Buttons carrying an action of type `A` whose interaction changed this frame.
+pub type ButtonInteractions<'w, 's, A> = Query<
+    'w,
+    's,
+    (
+        &'static Interaction,
+        &'static mut BackgroundColor,
+        &'static A,
+    ),
+    (Changed<Interaction>, With<Button>),
+>;
 
 #[derive(Resource)]
 pub struct GameState {
     puzzle: level::Puzzle,
     solution: level::Solution,
+    // Stable id of the loaded level, see `GameLevel::id`.
+    id: String,
     name: String,
+    // Par move count of the loaded level, see `GameLevel::par`.
+    par: Option<usize>,
+    // Index into `level::all_levels()`, or `CustomLevel::index()` for the custom
+    // level. It stays flat across packs, so the next level is one further even at the
+    // end of a pack; `level::pack_of` and `level::level_index` convert to and from
+    // the pack and the place in it.
     current_level: usize,
-    hints: Vec<Vec<bool>>,
+    // Hint marker on each cell: 0 for none, otherwise the marker color, see
+    // `game_screen::HINT_COLORS`.
+    hints: Vec<Vec<u8>>,
+    // Hints left to ask for on the current level, see `hint::RequestHint`.
+    hints_remaining: usize,
+    // Kind of house the next placement builds, on puzzles with the house kinds rule.
+    house_kind: level::HouseKind,
+    // Index of the level `puzzle` was loaded from. While switching levels
+    // `current_level` already points at the next one.
+    loaded_level: usize,
+    // Placements and hint markers of levels left unfinished, by level index.
+    saved_progress: HashMap<usize, (Solution, Vec<Vec<u8>>)>,
 }
 
 impl GameState {
@@ -34,17 +127,53 @@ impl GameState {
         Self {
             puzzle,
             solution: Solution::default(),
+            id: game_level.id,
             name: game_level.name,
+            par: game_level.par,
             current_level,
-            hints: vec![vec![false; cols]; rows],
+            hints: vec![vec![0; cols]; rows],
+            hints_remaining: HINTS_PER_LEVEL,
+            house_kind: level::HouseKind::Small,
+            loaded_level: current_level,
+            saved_progress: HashMap::new(),
+        }
+    }
+
+    // Drops the placements and hint markers on the current level, so that switching
+    // to it again starts from an empty board.
+    pub fn clear_progress(&mut self) {
+        self.solution = Solution::default();
+        for line in self.hints.iter_mut() {
+            line.fill(0);
+        }
+    }
+}
+
+// A level from outside `all_levels()`, such as one made in the editor or pasted in.
+// It is played by setting `current_level` to `all_levels().len()`, so restarting it
+// works and the next/previous level controls treat it as following the last level.
+#[derive(Resource, Default)]
+pub struct CustomLevel(pub Option<level::GameLevel>);
+
+impl CustomLevel {
+    pub fn index() -> usize {
+        level::all_levels().len()
+    }
+
+    // The level to load for `index`: the custom level at `index()`, a built-in
+    // level otherwise.
+    pub fn level_at(&self, index: usize) -> level::GameLevel {
+        match &self.0 {
+            Some(custom_level) if index == Self::index() => custom_level.clone(),
+            _ => level::all_levels()[index].clone(),
         }
     }
 }
 
 #[derive(Resource)]
 pub struct TextureHandles {
-    #[allow(dead_code)]
-    textures: Vec<Handle<u32>>,
+    // Never read, only held so the textures stay loaded.
+    _textures: Vec<Handle<u32>>,
 }
 
 #[derive(Resource)]
@@ -52,26 +181,59 @@ pub struct GlobalVolumeSettings {
     pub volume: f32,
 }
 
+#[derive(Resource)]
+pub struct MusicVolume {
+    pub volume: f32,
+}
+
+#[derive(Resource)]
+pub struct SfxVolume {
+    pub volume: f32,
+}
+
+// Switches status feedback to colors that work with red-green color blindness.
+#[derive(Resource, Default, Clone, Copy)]
+pub struct ColorblindMode(pub bool);
+
+// Replaces animations with instant changes, for players who find motion uncomfortable.
+// Every animation system checks it.
+#[derive(Resource, Default, Clone, Copy)]
+pub struct ReduceMotion(pub bool);
+
+// The volume bus a sound is mixed into, on top of the global volume.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum VolumeBus {
+    Music,
+    Sfx,
+}
+
 #[derive(Component)]
 pub struct VolumeSettings {
     pub volume: f32,
+    pub bus: VolumeBus,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, States, Default, Copy)]
 pub enum AppState {
     InGame,
     SwitchLevel,
+    WorldSelectScreen,
     SelectLevelScreen,
+    SettingsScreen,
+    EditorScreen,
+    AchievementsScreen,
+    StatsScreen,
+    GameCompleteScreen,
     #[default]
     MainMenuScreen,
 }
 
-fn setup(mut commands: Commands, server: Res<AssetServer>) {
-    commands.spawn(Camera2dBundle::default());
-    let game_level = level::all_levels().swap_remove(0);
+fn setup(mut commands: Commands, server: Res<AssetServer>, save_data: Res<SaveData>) {
+    commands.spawn((Camera2dBundle::default(), GameCamera::default()));
+    let game_level = level::all_levels()[0].clone();
     commands.insert_resource(GameState::new(game_level, 0));
     commands.insert_resource(TextureHandles {
-        textures: [
+        _textures: [
             "cross_iso.png",
             "UI/button_play.png",
             "UI/button_levels.png",
@@ -84,7 +246,9 @@ fn setup(mut commands: Commands, server: Res<AssetServer>) {
             "forest_iso.png",
             "house_iso.png",
             "lake_iso.png",
+            "lake_iso_old.png",
             "mountain_iso.png",
+            "rock_iso.png",
             "grass_iso_dark_1.png",
             "grass_iso_dark_2.png",
             "grass_iso_dark_3.png",
@@ -100,25 +264,82 @@ fn setup(mut commands: Commands, server: Res<AssetServer>) {
         .to_vec(),
     });
 
-    commands.insert_resource(GlobalVolumeSettings { volume: 0.5 });
+    commands.insert_resource(GlobalVolumeSettings {
+        volume: save_data.volume,
+    });
+    commands.insert_resource(MusicVolume {
+        volume: save_data.music_volume,
+    });
+    commands.insert_resource(SfxVolume {
+        volume: save_data.sfx_volume,
+    });
+    commands.insert_resource(ColorblindMode(save_data.colorblind_mode));
+    commands.insert_resource(ReduceMotion(save_data.reduce_motion));
+    commands.insert_resource(Theme::by_name(&save_data.theme).unwrap_or_default());
+    commands.insert_resource(if save_data.nearest_filtering {
+        TileFiltering::Nearest
+    } else {
+        TileFiltering::Linear
+    });
+    commands.insert_resource(if save_data.strict_rules {
+        RuleMode::Strict
+    } else {
+        RuleMode::Peaceful
+    });
+    commands.insert_resource(Lang::by_code(&save_data.language).unwrap_or_default());
+    commands.insert_resource(Achievements::from_ids(&save_data.achievements));
+    commands.insert_resource(save_data.stats.clone());
 }
 
 fn update_sounds(
     mut audio_query: Query<(&mut AudioSink, &VolumeSettings)>,
     global_volume_settings: Res<GlobalVolumeSettings>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    music_duck: Res<MusicDuck>,
 ) {
     for (sink, volume_settings) in &mut audio_query.iter_mut() {
-        sink.set_volume(global_volume_settings.volume * volume_settings.volume);
+        let bus_volume = match volume_settings.bus {
+            VolumeBus::Music => music_volume.volume * music_duck.0,
+            VolumeBus::Sfx => sfx_volume.volume,
+        };
+        sink.set_volume(global_volume_settings.volume * bus_volume * volume_settings.volume);
     }
 }
 
-fn switch_levels(mut game_state: ResMut<GameState>, mut app_state: ResMut<NextState<AppState>>) {
-    let game_level = level::all_levels().swap_remove(game_state.current_level);
+fn switch_levels(mut game_state: ResMut<GameState>, custom_level: Res<CustomLevel>) {
+    // Keep the work on the level being left so coming back restores it. Custom levels
+    // can be replaced under the same index, so their progress is not kept.
+    let loaded_level = game_state.loaded_level;
+    let solution = std::mem::take(&mut game_state.solution);
+    let hints = std::mem::take(&mut game_state.hints);
+    let has_progress =
+        !solution.placements.is_empty() || hints.iter().flatten().any(|&hint| hint != 0);
+    if loaded_level != CustomLevel::index()
+        && has_progress
+        && !validate_solution(&solution, &game_state.puzzle).complete
+    {
+        game_state
+            .saved_progress
+            .insert(loaded_level, (solution, hints));
+    } else {
+        game_state.saved_progress.remove(&loaded_level);
+    }
+
+    let game_level = custom_level.level_at(game_state.current_level);
     game_state.puzzle = game_level.puzzle;
-    game_state.solution = Solution::default();
+    game_state.id = game_level.id;
     game_state.name = game_level.name;
-    game_state.hints = vec![vec![false; game_state.puzzle.cols()]; game_state.puzzle.rows()];
-    app_state.set(AppState::InGame);
+    game_state.par = game_level.par;
+    let current_level = game_state.current_level;
+    let (rows, cols) = game_state.puzzle.dims();
+    (game_state.solution, game_state.hints) = game_state
+        .saved_progress
+        .remove(&current_level)
+        .unwrap_or_else(|| (Solution::default(), vec![vec![0; cols]; rows]));
+    game_state.loaded_level = current_level;
+    game_state.hints_remaining = HINTS_PER_LEVEL;
+    game_state.house_kind = level::HouseKind::Small;
 }
 
 fn main() {
@@ -137,13 +358,63 @@ fn main() {
             ..default()
         }))
         .add_state::<AppState>()
+        .init_resource::<CustomLevel>()
         .add_systems(Startup, setup)
-        .add_systems(Update, close_on_esc)
+        .add_systems(
+            Update,
+            close_on_esc.run_if(in_state(AppState::MainMenuScreen)),
+        )
         .add_systems(Update, update_sounds)
         .add_systems(OnEnter(AppState::SwitchLevel), switch_levels)
         .add_plugins(MainMenuScreenPlugin(AppState::MainMenuScreen))
+        .add_plugins(WorldSelectScreenPlugin(AppState::WorldSelectScreen))
         .add_plugins(SelectLevelScreenPlugin(AppState::SelectLevelScreen))
+        .add_plugins(SettingsScreenPlugin(AppState::SettingsScreen))
+        .add_plugins(EditorScreenPlugin(AppState::EditorScreen))
+        .add_plugins(AchievementsScreenPlugin(AppState::AchievementsScreen))
+        .add_plugins(StatsScreenPlugin(AppState::StatsScreen))
+        .add_plugins(GameCompleteScreenPlugin(AppState::GameCompleteScreen))
+        .add_plugins(LoadingScreenPlugin(AppState::SwitchLevel))
         .add_plugins(GameScreenPlugin(AppState::InGame))
+        .add_plugins(TutorialPlugin(AppState::InGame))
+        .add_plugins(FadePlugin(AppState::InGame))
+        .add_plugins(HintPlugin(AppState::InGame))
+        .add_plugins(CheckReportPlugin(AppState::InGame))
+        .add_plugins(ReplayPlugin(AppState::InGame))
+        .add_plugins(StatsPlugin(AppState::InGame))
+        .add_plugins(TerrainTooltipPlugin(AppState::InGame))
+        .add_plugins(PauseMenuPlugin)
         .add_plugins(GameInputPlugin)
+        .add_plugins(GameCameraPlugin)
+        .add_plugins(SavePlugin)
+        .add_plugins(ToastPlugin)
+        .add_plugins(ConfirmDialogPlugin)
+        .add_plugins(DailyChallengePlugin)
+        .add_plugins(SnapshotPlugin)
+        .add_plugins(SharePlugin)
+        .add_plugins(PointerPlugin)
+        .add_plugins(DebugOverlayPlugin)
+        .add_plugins(MusicPlugin)
+        .add_plugins(AchievementsPlugin)
+        .add_plugins(ThemePlugin)
+        .add_plugins(TileAtlasPlugin)
+        .add_plugins(LangPlugin)
         .run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_load_by_their_index() {
+        let custom_level = level::busy_lake();
+        for custom in [CustomLevel(None), CustomLevel(Some(custom_level.clone()))] {
+            for (index, level) in level::all_levels().iter().enumerate() {
+                assert_eq!(&custom.level_at(index), level);
+            }
+        }
+        let custom = CustomLevel(Some(custom_level.clone()));
+        assert_eq!(custom.level_at(CustomLevel::index()), custom_level);
+    }
+}
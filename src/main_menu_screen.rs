@@ -1,7 +1,11 @@
 use bevy::app::AppExit;
 use bevy::prelude::*;
 
-use crate::AppState;
+use crate::daily::{start_daily_challenge, DailyChallenge};
+use crate::lang::Lang;
+use crate::level::all_levels;
+use crate::save::SaveData;
+use crate::{AppState, ButtonInteractions, CustomLevel, GameState};
 
 pub struct MainMenuScreenPlugin<S: States + Copy>(pub S);
 
@@ -19,19 +23,6 @@ impl<S: States + Copy> Plugin for MainMenuScreenPlugin<S> {
 const NORMAL_BUTTON: Color = Color::WHITE;
 const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
 const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
-const TEXT_COLOR: Color = Color::rgb(239.0 / 256.0, 167.0 / 256.0, 115.0 / 256.0);
-
-const RULES: &str = "
-Welcome to Skyland -- a flying island with green-green grass, forests, lakes, mountains, and... houses! 
-
-Your job is to place houses, following the following simple rules:
-- Houses cannot be placed in adjacent cells. Diagonal cells are OK. 
-- Each row/column should have a given number of houses (written next to it). 
-- Lake: exactly 3 houses around it (in the 8 cells surrounding the lake).
-- Mountain: exactly 2 houses on the diagonals crossing the mountain (in total). 
-
-Once the houses are placed, the neighbors can live peacefully and enjoy the surroundings! 
-";
 
 // Tag component used to tag entities added on the main menu screen
 #[derive(Component)]
@@ -41,16 +32,27 @@ struct OnMainMenuScreen;
 #[derive(Component)]
 enum MenuButtonAction {
     Play,
+    Continue,
     Levels,
+    Daily,
+    Settings,
+    Editor,
+    Achievements,
+    Stats,
     Quit,
 }
 
-fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
+fn create_main_menu_screen(
+    mut commands: Commands,
+    save_data: Res<SaveData>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
     // Common style for all buttons on the screen
     let button_style = Style {
         width: Val::Px(250.0),
         height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(20.0)),
+        margin: UiRect::axes(Val::Px(20.0), Val::Px(12.0)),
         justify_content: JustifyContent::Center,
         align_items: AlignItems::Center,
         ..default()
@@ -86,12 +88,11 @@ fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
                     // Display the game name
                     parent.spawn(
                         TextBundle::from_section(
-                            "Skyland",
+                            lang.t("main_menu.title"),
                             TextStyle {
                                 font: server.load(crate::TEXT_FONT_NAME),
                                 font_size: 200.0,
                                 color: crate::CUSTOM_ORANGE,
-                                ..default()
                             },
                         )
                         .with_style(Style {
@@ -100,19 +101,76 @@ fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
                         }),
                     );
 
-                    // Display three buttons for each action available from the main menu:
+                    // Display how many of the built-in levels have been completed
+                    let completed = all_levels()
+                        .iter()
+                        .filter(|level| save_data.is_completed(&level.id))
+                        .count();
+                    parent.spawn(TextBundle::from_section(
+                        lang.t_with(
+                            "main_menu.levels_complete",
+                            &[
+                                ("completed", completed.to_string()),
+                                ("total", all_levels().len().to_string()),
+                            ],
+                        ),
+                        TextStyle {
+                            font: server.load(crate::TEXT_FONT_NAME),
+                            font_size: 50.0,
+                            color: crate::CUSTOM_ORANGE,
+                        },
+                    ));
+
+                    // Display a button for each action available from the main menu:
                     // - Play
+                    // - Continue
                     // - Levels
+                    // - Daily
+                    // - Settings
+                    // - Editor
+                    // - Achievements
+                    // - Stats
                     // - Quit
-                    parent.spawn((
-                        ButtonBundle {
-                            style: button_style.clone(),
-                            background_color: NORMAL_BUTTON.into(),
-                            image: UiImage::new(server.load("UI/button_play.png")),
+                    // Continue sits next to Play, as the column has no room left.
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                ..default()
+                            },
                             ..default()
-                        },
-                        MenuButtonAction::Play,
-                    ));
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_play.png")),
+                                    ..default()
+                                },
+                                MenuButtonAction::Play,
+                            ));
+                            parent
+                                .spawn((
+                                    ButtonBundle {
+                                        style: button_style.clone(),
+                                        background_color: NORMAL_BUTTON.into(),
+                                        image: UiImage::new(server.load("UI/button_empty.png")),
+                                        ..default()
+                                    },
+                                    MenuButtonAction::Continue,
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        lang.t("main_menu.continue"),
+                                        TextStyle {
+                                            font: server.load(crate::TEXT_FONT_NAME),
+                                            font_size: 50.0,
+                                            color: Color::WHITE,
+                                        },
+                                    ));
+                                });
+                        });
                     parent.spawn((
                         ButtonBundle {
                             style: button_style.clone(),
@@ -122,6 +180,34 @@ fn create_main_menu_screen(mut commands: Commands, server: Res<AssetServer>) {
                         },
                         MenuButtonAction::Levels,
                     ));
+                    for (label, action) in [
+                        ("main_menu.daily", MenuButtonAction::Daily),
+                        ("main_menu.settings", MenuButtonAction::Settings),
+                        ("main_menu.editor", MenuButtonAction::Editor),
+                        ("main_menu.achievements", MenuButtonAction::Achievements),
+                        ("main_menu.stats", MenuButtonAction::Stats),
+                    ] {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: button_style.clone(),
+                                    background_color: NORMAL_BUTTON.into(),
+                                    image: UiImage::new(server.load("UI/button_empty.png")),
+                                    ..default()
+                                },
+                                action,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    lang.t(label),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ));
+                            });
+                    }
                     parent.spawn((
                         ButtonBundle {
                             style: button_style.clone(),
@@ -145,11 +231,12 @@ fn update_main_menu_screen() {}
 
 // This system handles changing all buttons color based on mouse interaction
 fn button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &MenuButtonAction),
-        (Changed<Interaction>, With<Button>),
-    >,
+    mut interaction_query: ButtonInteractions<MenuButtonAction>,
     mut app_state: ResMut<NextState<AppState>>,
+    mut daily_challenge: ResMut<DailyChallenge>,
+    mut game_state: ResMut<GameState>,
+    mut custom_level: ResMut<CustomLevel>,
+    save_data: Res<SaveData>,
     mut exit: EventWriter<AppExit>,
 ) {
     for (interaction, mut color, action) in &mut interaction_query {
@@ -164,8 +251,32 @@ fn button_system(
                 MenuButtonAction::Play => {
                     app_state.set(AppState::SwitchLevel);
                 }
+                MenuButtonAction::Continue => {
+                    game_state.current_level = save_data.continue_level();
+                    app_state.set(AppState::SwitchLevel);
+                }
                 MenuButtonAction::Levels => {
-                    app_state.set(AppState::SelectLevelScreen);
+                    app_state.set(AppState::WorldSelectScreen);
+                }
+                MenuButtonAction::Daily => {
+                    start_daily_challenge(
+                        &mut daily_challenge,
+                        &mut game_state,
+                        &mut custom_level,
+                        &mut app_state,
+                    );
+                }
+                MenuButtonAction::Settings => {
+                    app_state.set(AppState::SettingsScreen);
+                }
+                MenuButtonAction::Editor => {
+                    app_state.set(AppState::EditorScreen);
+                }
+                MenuButtonAction::Achievements => {
+                    app_state.set(AppState::AchievementsScreen);
+                }
+                MenuButtonAction::Stats => {
+                    app_state.set(AppState::StatsScreen);
                 }
                 MenuButtonAction::Quit => {
                     exit.send(AppExit);
@@ -0,0 +1,170 @@
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+
+use crate::{AppState, GlobalVolumeSettings, SfxVolume, VolumeBus, VolumeSettings};
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicController>()
+            .init_resource::<MusicDuck>()
+            .add_systems(
+                Update,
+                (switch_music_track, crossfade_music, duck_music).chain(),
+            );
+    }
+}
+
+// Time for one track to fade out while the next one fades in.
+const MUSIC_FADE_SECONDS: f32 = 1.0;
+// Music drops to this share of its volume when a sound effect starts, and comes back
+// over the recovery time.
+const DUCK_LEVEL: f32 = 0.4;
+const DUCK_RECOVERY_SECONDS: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicTrack {
+    Menu,
+    InGame,
+}
+
+impl MusicTrack {
+    // Switching levels keeps the in-game music going instead of fading it out and in.
+    fn for_state(state: AppState) -> Self {
+        match state {
+            AppState::InGame | AppState::SwitchLevel => MusicTrack::InGame,
+            AppState::MainMenuScreen
+            | AppState::WorldSelectScreen
+            | AppState::SelectLevelScreen
+            | AppState::SettingsScreen
+            | AppState::EditorScreen
+            | AppState::AchievementsScreen
+            | AppState::StatsScreen
+            | AppState::GameCompleteScreen => MusicTrack::Menu,
+        }
+    }
+
+    // There is only one recording so far, so the menu plays it more quietly.
+    fn source(self) -> &'static str {
+        match self {
+            MusicTrack::Menu => "ambient.mp3",
+            MusicTrack::InGame => "ambient.mp3",
+        }
+    }
+
+    // Volume of the track at full fade, before the volume settings are applied.
+    fn volume(self) -> f32 {
+        match self {
+            MusicTrack::Menu => 0.06,
+            MusicTrack::InGame => 0.1,
+        }
+    }
+}
+
+// The track that is playing or fading in.
+#[derive(Resource, Default)]
+pub struct MusicController {
+    pub current: Option<MusicTrack>,
+}
+
+// Extra factor on the music bus while sound effects play, from `DUCK_LEVEL` back up
+// to 1. `update_sounds` applies it on top of the global and music volumes, so muting
+// and the music volume setting work as before.
+#[derive(Resource)]
+pub struct MusicDuck(pub f32);
+
+impl Default for MusicDuck {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+#[derive(Component)]
+struct MusicFade {
+    track: MusicTrack,
+    // From 0 for silent to 1 for the track's full volume.
+    level: f32,
+    fading_in: bool,
+}
+
+fn switch_music_track(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    mut controller: ResMut<MusicController>,
+    mut fade_query: Query<&mut MusicFade>,
+    server: Res<AssetServer>,
+) {
+    let track = MusicTrack::for_state(*app_state.get());
+    if controller.current == Some(track) {
+        return;
+    }
+    controller.current = Some(track);
+
+    let mut already_playing = false;
+    for mut fade in fade_query.iter_mut() {
+        fade.fading_in = fade.track == track;
+        already_playing |= fade.fading_in;
+    }
+    // A track that is still fading out just fades back in.
+    if already_playing {
+        return;
+    }
+    commands.spawn((
+        AudioBundle {
+            source: server.load(track.source()),
+            settings: PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::new_absolute(0.0),
+                ..default()
+            },
+        },
+        VolumeSettings {
+            volume: 0.0,
+            bus: VolumeBus::Music,
+        },
+        MusicFade {
+            track,
+            level: 0.0,
+            fading_in: true,
+        },
+    ));
+}
+
+// Only changes the track's own volume, so `update_sounds` still applies the global
+// and music volumes on top.
+fn crossfade_music(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut music_query: Query<(Entity, &mut MusicFade, &mut VolumeSettings)>,
+) {
+    let step = time.delta_seconds() / MUSIC_FADE_SECONDS;
+    for (entity, mut fade, mut volume_settings) in music_query.iter_mut() {
+        fade.level = if fade.fading_in {
+            (fade.level + step).min(1.0)
+        } else {
+            (fade.level - step).max(0.0)
+        };
+        volume_settings.volume = fade.track.volume() * fade.level;
+        if !fade.fading_in && fade.level == 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// Ducks the music whenever a sound effect is spawned, unless effects can't be heard.
+fn duck_music(
+    time: Res<Time>,
+    global_volume_settings: Res<GlobalVolumeSettings>,
+    sfx_volume: Res<SfxVolume>,
+    sound_query: Query<&VolumeSettings, Added<VolumeSettings>>,
+    mut duck: ResMut<MusicDuck>,
+) {
+    let audible = global_volume_settings.volume * sfx_volume.volume > 0.0;
+    if audible && sound_query.iter().any(|sound| sound.bus == VolumeBus::Sfx) {
+        duck.0 = DUCK_LEVEL;
+    } else if duck.0 < 1.0 {
+        duck.0 =
+            (duck.0 + (1.0 - DUCK_LEVEL) * time.delta_seconds() / DUCK_RECOVERY_SECONDS).min(1.0);
+    }
+}
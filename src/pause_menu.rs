@@ -0,0 +1,187 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::lang::Lang;
+use crate::{AppState, ButtonInteractions, GameState};
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Paused>()
+            .add_systems(
+                Update,
+                (toggle_pause, update_pause_overlay, button_system)
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnExit(AppState::InGame), (unpause, destroy_pause_overlay));
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+// Whether gameplay is paused behind the pause overlay.
+#[derive(Resource, Default)]
+pub struct Paused(pub bool);
+
+// Run condition for systems that must not react to input while paused.
+pub fn not_paused(paused: Res<Paused>) -> bool {
+    !paused.0
+}
+
+// Tag component used to tag entities added for the pause overlay.
+#[derive(Component)]
+struct OnPauseOverlay;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum PauseButtonAction {
+    Resume,
+    Restart,
+    MainMenu,
+    Quit,
+}
+
+fn toggle_pause(keys: Res<Input<KeyCode>>, mut paused: ResMut<Paused>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        paused.0 ^= true;
+    }
+}
+
+fn unpause(mut paused: ResMut<Paused>) {
+    paused.0 = false;
+}
+
+fn update_pause_overlay(
+    commands: Commands,
+    paused: Res<Paused>,
+    overlay_query: Query<Entity, With<OnPauseOverlay>>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    if !paused.is_changed() {
+        return;
+    }
+    if paused.0 && overlay_query.is_empty() {
+        create_pause_overlay(commands, lang, server);
+    } else if !paused.0 {
+        destroy_pause_overlay(commands, overlay_query);
+    }
+}
+
+fn create_pause_overlay(mut commands: Commands, lang: Res<Lang>, server: Res<AssetServer>) {
+    let button_style = Style {
+        width: Val::Px(250.0),
+        height: Val::Px(65.0),
+        margin: UiRect::all(Val::Px(15.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let button_text_style = TextStyle {
+        font: server.load(crate::TEXT_FONT_NAME),
+        font_size: 50.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: OVERLAY_COLOR.into(),
+                // Keep clicks from reaching the HUD buttons underneath.
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            OnPauseOverlay,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                lang.t("pause.title"),
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 120.0,
+                    color: crate::CUSTOM_ORANGE,
+                },
+            ));
+
+            for (label, action) in [
+                ("pause.resume", PauseButtonAction::Resume),
+                ("pause.restart", PauseButtonAction::Restart),
+                ("pause.main_menu", PauseButtonAction::MainMenu),
+                ("pause.quit", PauseButtonAction::Quit),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: button_style.clone(),
+                            background_color: NORMAL_BUTTON.into(),
+                            image: UiImage::new(server.load("UI/button_empty.png")),
+                            ..default()
+                        },
+                        action,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            lang.t(label),
+                            button_text_style.clone(),
+                        ));
+                    });
+            }
+        });
+}
+
+fn destroy_pause_overlay(mut commands: Commands, query: Query<Entity, With<OnPauseOverlay>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<PauseButtonAction>,
+    mut paused: ResMut<Paused>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                PauseButtonAction::Resume => {
+                    paused.0 = false;
+                }
+                PauseButtonAction::Restart => {
+                    game_state.clear_progress();
+                    app_state.set(AppState::SwitchLevel);
+                }
+                PauseButtonAction::MainMenu => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+                PauseButtonAction::Quit => {
+                    exit.send(AppExit);
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::sprite::MaterialMesh2dBundle;
+
+use crate::game_screen::{iso_offset, GameScreenRoot, AXIS_LAYER, CELL_SIZE};
+use crate::level::Position;
+use crate::ReduceMotion;
+
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowPointer>()
+            .add_event::<DismissPointers>()
+            .add_systems(Update, (show_pointers, animate_pointers).chain());
+    }
+}
+
+const POINTER_COLOR: Color = crate::CUSTOM_ORANGE;
+const POINTER_SIZE: f32 = 30.0;
+// Height of the arrow tip above its target, and how far it bobs from there.
+const POINTER_HEIGHT: f32 = 60.0;
+const POINTER_BOB: f32 = 15.0;
+const POINTER_BOB_SPEED: f32 = 5.0;
+const POINTER_PULSE: f32 = 0.15;
+
+// What a pointer points at.
+#[derive(Clone, Copy)]
+pub enum PointerTarget {
+    // A cell of the board on the game screen, which follows the board around.
+    Cell(Position),
+}
+
+// Shows an arrow bobbing above the target, until `DismissPointers` is sent.
+#[derive(Event)]
+pub struct ShowPointer(pub PointerTarget);
+
+#[derive(Event)]
+pub struct DismissPointers;
+
+// Both pointer events, for systems that move the pointer from one target to another.
+#[derive(SystemParam)]
+pub struct Pointers<'w> {
+    show: EventWriter<'w, ShowPointer>,
+    dismiss: EventWriter<'w, DismissPointers>,
+}
+
+impl Pointers<'_> {
+    pub fn show(&mut self, target: PointerTarget) {
+        self.show.send(ShowPointer(target));
+    }
+
+    pub fn dismiss(&mut self) {
+        self.dismiss.send(DismissPointers);
+    }
+}
+
+#[derive(Component)]
+struct Pointer {
+    target: PointerTarget,
+    elapsed: f32,
+}
+
+fn show_pointers(
+    mut commands: Commands,
+    mut show_events: EventReader<ShowPointer>,
+    mut dismiss_events: EventReader<DismissPointers>,
+    pointer_query: Query<Entity, With<Pointer>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if dismiss_events.iter().count() > 0 {
+        for entity in pointer_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+    for ShowPointer(target) in show_events.iter() {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: meshes
+                    .add(shape::RegularPolygon::new(POINTER_SIZE, 3).into())
+                    .into(),
+                material: materials.add(ColorMaterial::from(POINTER_COLOR)),
+                // Hidden until animate_pointers has placed it.
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Pointer {
+                target: *target,
+                elapsed: 0.0,
+            },
+        ));
+    }
+}
+
+// Moves the pointers above their targets, bobbing up and down and pulsing in size.
+fn animate_pointers(
+    time: Res<Time>,
+    reduce_motion: Res<ReduceMotion>,
+    mut pointer_query: Query<(&mut Pointer, &mut Transform, &mut Visibility)>,
+    board_query: Query<&Transform, (With<GameScreenRoot>, Without<Pointer>)>,
+) {
+    for (mut pointer, mut transform, mut visibility) in pointer_query.iter_mut() {
+        pointer.elapsed += time.delta_seconds();
+        let PointerTarget::Cell(position) = pointer.target;
+        // Sprites are anchored on the left, so the cell center is half a cell right.
+        let target = board_query.get_single().ok().map(|board| {
+            board.translation.truncate()
+                + iso_offset(position.row, position.col, CELL_SIZE)
+                + Vec2::new(CELL_SIZE * 0.5, 0.0)
+        });
+        let Some(target) = target else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+        // A still pointer rests halfway through its bob.
+        let phase = if reduce_motion.0 {
+            0.0
+        } else {
+            pointer.elapsed * POINTER_BOB_SPEED
+        };
+        let height = POINTER_HEIGHT + POINTER_BOB * (0.5 + 0.5 * phase.sin());
+        *transform = Transform::from_xyz(target.x, target.y + height, AXIS_LAYER + 10.0)
+            // The triangle points up, turn it around to point at the target.
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::PI))
+            .with_scale(Vec3::splat(1.0 + POINTER_PULSE * (2.0 * phase).sin()));
+        *visibility = Visibility::Visible;
+    }
+}
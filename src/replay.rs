@@ -0,0 +1,360 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::confirm_dialog::no_confirm_dialog;
+use crate::game_screen::{CellInput, LevelStats, RuleMode};
+use crate::hint::{RequestHint, HINTS_PER_LEVEL};
+use crate::level::{HouseKind, Placement, Position, Solution};
+use crate::pause_menu::not_paused;
+use crate::save::level_file_name;
+use crate::toast::ShowToast;
+use crate::GameState;
+
+pub struct ReplayPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for ReplayPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayRecorder>()
+            .init_resource::<ReplayPlayer>()
+            .add_systems(OnEnter(self.0), start_recording)
+            .add_systems(
+                Update,
+                (
+                    save_replay,
+                    start_playback,
+                    (tick_replay_recorder, play_replay).run_if(not_paused),
+                )
+                    .chain()
+                    .run_if(in_state(self.0).and_then(no_confirm_dialog)),
+            )
+            .add_systems(OnExit(self.0), stop_playback);
+    }
+}
+
+// Something the player did on the board, in the order and at the time it happened.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ReplayAction {
+    // A click or button press on a cell, as passed to `CellInput::handle`.
+    Cell {
+        row: usize,
+        col: usize,
+        primary: bool,
+        secondary: bool,
+    },
+    Hint,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ReplayStep {
+    // Seconds since the recording started, not counting time spent paused.
+    pub time: f32,
+    pub action: ReplayAction,
+}
+
+// Everything needed to play an attempt at a level again. The houses on the board
+// at the start and the end are kept as (row, col), so playback can start from the
+// same board and check that it ended up on the same one.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    // Id of the level, see `GameLevel::id`.
+    pub level: String,
+    pub start: Vec<(usize, usize)>,
+    pub steps: Vec<ReplayStep>,
+    pub end: Vec<(usize, usize)>,
+}
+
+fn houses(solution: &Solution) -> Vec<(usize, usize)> {
+    let mut houses: Vec<(usize, usize)> = solution
+        .placements
+        .iter()
+        .map(|placement| (placement.position.row, placement.position.col))
+        .collect();
+    houses.sort();
+    houses
+}
+
+impl Replay {
+    // Puts the houses back the way they were when the recording started.
+    fn restore_start(&self, game_state: &mut GameState) {
+        game_state.clear_progress();
+        game_state.solution.placements = self
+            .start
+            .iter()
+            .map(|&(row, col)| Placement {
+                position: Position { row, col },
+                kind: HouseKind::Small,
+            })
+            .collect();
+        game_state.hints_remaining = HINTS_PER_LEVEL;
+    }
+
+    fn ended_on(&self, game_state: &GameState) -> bool {
+        houses(&game_state.solution) == self.end
+    }
+}
+
+// Records the actions on the current level since it was entered.
+#[derive(Resource, Default)]
+pub struct ReplayRecorder {
+    replay: Replay,
+    elapsed: f32,
+}
+
+impl ReplayRecorder {
+    fn start(&mut self, game_state: &GameState) {
+        self.replay = Replay {
+            level: game_state.id.clone(),
+            start: houses(&game_state.solution),
+            ..default()
+        };
+        self.elapsed = 0.0;
+    }
+
+    pub fn record(&mut self, action: ReplayAction) {
+        self.replay.steps.push(ReplayStep {
+            time: self.elapsed,
+            action,
+        });
+    }
+
+    // The recording so far, ending on the current board.
+    pub fn finish(&self, game_state: &GameState) -> Replay {
+        Replay {
+            end: houses(&game_state.solution),
+            ..self.replay.clone()
+        }
+    }
+}
+
+// The replay being played back, and how far along it is.
+#[derive(Resource, Default)]
+pub struct ReplayPlayer {
+    replay: Option<Replay>,
+    elapsed: f32,
+    next_step: usize,
+}
+
+impl ReplayPlayer {
+    // Moves playback on by `delta` seconds and returns the actions that came due.
+    fn advance(&mut self, delta: f32) -> Vec<ReplayAction> {
+        let Some(replay) = &self.replay else {
+            return Vec::new();
+        };
+        self.elapsed += delta;
+        let due = replay.steps[self.next_step..]
+            .iter()
+            .take_while(|step| step.time <= self.elapsed)
+            .map(|step| step.action)
+            .collect::<Vec<_>>();
+        self.next_step += due.len();
+        due
+    }
+
+    fn is_done(&self) -> bool {
+        self.replay
+            .as_ref()
+            .is_some_and(|replay| self.next_step == replay.steps.len())
+    }
+}
+
+// Run condition for board input, which would mix with a replay being played back.
+pub fn no_replay_playing(player: Res<ReplayPlayer>) -> bool {
+    player.replay.is_none()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_replay(replay: &Replay) -> Result<String, String> {
+    let file_name = level_file_name(&replay.level, "replay.json");
+    let contents = serde_json::to_string_pretty(replay).map_err(|err| err.to_string())?;
+    std::fs::write(&file_name, contents).map_err(|err| err.to_string())?;
+    Ok(file_name)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_replay(level_id: &str) -> Result<Replay, String> {
+    let file_name = level_file_name(level_id, "replay.json");
+    let contents = std::fs::read_to_string(&file_name).map_err(|err| err.to_string())?;
+    serde_json::from_str(&contents).map_err(|err| err.to_string())
+}
+
+// The web build has no file system to keep replays in.
+#[cfg(target_arch = "wasm32")]
+fn write_replay(_replay: &Replay) -> Result<String, String> {
+    Err("not supported in the browser".into())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_replay(_level_id: &str) -> Result<Replay, String> {
+    Err("not supported in the browser".into())
+}
+
+fn start_recording(mut recorder: ResMut<ReplayRecorder>, game_state: Res<GameState>) {
+    recorder.start(&game_state);
+}
+
+fn tick_replay_recorder(time: Res<Time>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.elapsed += time.delta_seconds();
+}
+
+// F5 saves the attempt at the current level so far to a JSON file named after its id.
+fn save_replay(
+    keys: Res<Input<KeyCode>>,
+    recorder: Res<ReplayRecorder>,
+    game_state: Res<GameState>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    if !keys.just_pressed(KeyCode::F5) {
+        return;
+    }
+    match write_replay(&recorder.finish(&game_state)) {
+        Ok(file_name) => toasts.send(ShowToast::with("toast.saved", vec![("file", file_name)])),
+        Err(err) => toasts.send(ShowToast::with(
+            "toast.replay_save_failed",
+            vec![("error", err)],
+        )),
+    }
+}
+
+// F6 loads the saved replay of the current level and plays it back on its starting
+// board. A replay is help like any other, so the attempt gets no stars.
+fn start_playback(
+    keys: Res<Input<KeyCode>>,
+    mut player: ResMut<ReplayPlayer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut game_state: ResMut<GameState>,
+    mut level_stats: ResMut<LevelStats>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    if !keys.just_pressed(KeyCode::F6) {
+        return;
+    }
+    let replay = match read_replay(&game_state.id) {
+        Ok(replay) => replay,
+        Err(err) => {
+            toasts.send(ShowToast::with(
+                "toast.replay_load_failed",
+                vec![("error", err)],
+            ));
+            return;
+        }
+    };
+    replay.restore_start(&mut game_state);
+    level_stats.assisted = true;
+    // Playing back goes through the same input handling, so it records itself again.
+    recorder.start(&game_state);
+    *player = ReplayPlayer {
+        replay: Some(replay),
+        ..default()
+    };
+}
+
+// Repeats every step whose time has come, and checks the board once all are done.
+fn play_replay(
+    time: Res<Time>,
+    mut player: ResMut<ReplayPlayer>,
+    mut cell_input: CellInput,
+    mut request_hint: EventWriter<RequestHint>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    for action in player.advance(time.delta_seconds()) {
+        match action {
+            ReplayAction::Cell {
+                row,
+                col,
+                primary,
+                secondary,
+            } => {
+                cell_input.handle_with_rules(
+                    Position { row, col },
+                    primary,
+                    secondary,
+                    // Recorded steps already passed the rules in force back then.
+                    RuleMode::Peaceful,
+                );
+            }
+            ReplayAction::Hint => request_hint.send(RequestHint),
+        }
+    }
+    if !player.is_done() {
+        return;
+    }
+    if player
+        .replay
+        .as_ref()
+        .is_some_and(|replay| replay.ended_on(&cell_input.game_state))
+    {
+        toasts.send(ShowToast::new("toast.replay_finished"));
+    } else {
+        toasts.send(ShowToast::new("toast.replay_differs"));
+    }
+    player.replay = None;
+}
+
+fn stop_playback(mut player: ResMut<ReplayPlayer>) {
+    player.replay = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_screen::apply_cell_input;
+    use crate::level::busy_lake;
+
+    fn click(position: Position, game_state: &mut GameState, recorder: &mut ReplayRecorder) {
+        apply_cell_input(position, true, false, RuleMode::Peaceful, game_state);
+        recorder.record(ReplayAction::Cell {
+            row: position.row,
+            col: position.col,
+            primary: true,
+            secondary: false,
+        });
+        recorder.elapsed += 0.5;
+    }
+
+    #[test]
+    fn playing_a_recording_back_ends_on_the_same_board() {
+        let mut game_state = GameState::new(busy_lake(), 0);
+        let grass: Vec<_> = game_state.puzzle.grass_positions().collect();
+        let mut recorder = ReplayRecorder::default();
+        recorder.start(&game_state);
+        click(grass[0], &mut game_state, &mut recorder);
+        click(grass[2], &mut game_state, &mut recorder);
+        // Taken off again, so the order of the steps matters.
+        click(grass[0], &mut game_state, &mut recorder);
+        click(grass[1], &mut game_state, &mut recorder);
+        let replay = recorder.finish(&game_state);
+        assert_eq!(replay.steps.len(), 4);
+
+        let mut played = GameState::new(busy_lake(), 0);
+        replay.restore_start(&mut played);
+        let mut player = ReplayPlayer {
+            replay: Some(replay.clone()),
+            ..default()
+        };
+        let mut actions = player.advance(0.25);
+        assert!(actions.len() == 1 && !player.is_done());
+        actions.extend(player.advance(10.0));
+        for action in actions {
+            let ReplayAction::Cell {
+                row,
+                col,
+                primary,
+                secondary,
+            } = action
+            else {
+                panic!("no hints were recorded");
+            };
+            let position = Position { row, col };
+            apply_cell_input(
+                position,
+                primary,
+                secondary,
+                RuleMode::Peaceful,
+                &mut played,
+            );
+        }
+        assert!(player.is_done());
+        assert!(replay.ended_on(&played));
+        assert_eq!(houses(&played.solution), houses(&game_state.solution));
+    }
+}
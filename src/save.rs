@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::Achievements;
+use crate::game_screen::{LevelCompleted, LevelStats, RuleMode};
+use crate::lang::Lang;
+use crate::level::{all_levels, all_packs, pack_of, pack_range, PACK_UNLOCK_SHARE};
+use crate::stats::Stats;
+use crate::theme::{Theme, TileFiltering};
+use crate::{
+    ColorblindMode, CustomLevel, GameState, GlobalVolumeSettings, MusicVolume, ReduceMotion,
+    SfxVolume,
+};
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveData::load())
+            .init_resource::<PendingSave>()
+            .add_systems(
+                PostUpdate,
+                (
+                    sync_volume,
+                    sync_colorblind_mode,
+                    sync_reduce_motion,
+                    sync_rule_mode,
+                    sync_theme,
+                    sync_tile_filtering,
+                    sync_language,
+                    sync_achievements,
+                    sync_stats,
+                    record_completed_levels,
+                    write_save_data,
+                )
+                    .chain(),
+            )
+            .add_systems(Last, write_save_data_on_exit);
+    }
+}
+
+pub const SAVE_FILE_NAME: &str = "save.json";
+
+// File named after a level id, next to the save file. Characters that are not safe
+// in file names are replaced.
+pub fn level_file_name(level_id: &str, extension: &str) -> String {
+    let name: String = level_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{name}.{extension}")
+}
+
+// Everything that is remembered across runs. Missing fields fall back to their
+// defaults, so older save files keep loading as fields are added.
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub colorblind_mode: bool,
+    pub reduce_motion: bool,
+    // Refuse houses that break a rule instead of flagging them, see `RuleMode`.
+    pub strict_rules: bool,
+    // Name of the board theme, see `THEMES`.
+    pub theme: String,
+    // Sample tiles with nearest instead of linear filtering.
+    pub nearest_filtering: bool,
+    // Code of the UI language, see `Lang`.
+    pub language: String,
+    // Ids of the levels that have been completed at least once.
+    pub completed_levels: Vec<String>,
+    // Fastest daily challenge time in seconds, keyed by the date of the challenge.
+    pub daily_best: BTreeMap<String, f32>,
+    // Best star rating of each completed level, keyed by level id.
+    pub level_stars: BTreeMap<String, u8>,
+    // Ids of the unlocked achievements, see `Achievement::id`.
+    pub achievements: Vec<String>,
+    // Totals over every level played, see `Stats`.
+    pub stats: Stats,
+    // Whether the first launch already went straight into the tutorial.
+    pub seen_onboarding: bool,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            colorblind_mode: false,
+            reduce_motion: false,
+            strict_rules: false,
+            theme: Theme::default().name.to_string(),
+            nearest_filtering: false,
+            language: Lang::default().code.to_string(),
+            completed_levels: Vec::new(),
+            daily_best: BTreeMap::new(),
+            level_stars: BTreeMap::new(),
+            achievements: Vec::new(),
+            stats: Stats::default(),
+            seen_onboarding: false,
+        }
+    }
+}
+
+impl SaveData {
+    pub fn is_completed(&self, level_id: &str) -> bool {
+        self.completed_levels.iter().any(|id| id == level_id)
+    }
+
+    // Index of the first built-in level that hasn't been completed, or None once all
+    // of them have.
+    pub fn first_unsolved(&self) -> Option<usize> {
+        all_levels()
+            .iter()
+            .position(|level| !self.is_completed(&level.id))
+    }
+
+    // Level that continuing the game plays: the first unsolved one, or the last one
+    // when everything is solved.
+    pub fn continue_level(&self) -> usize {
+        self.first_unsolved()
+            .unwrap_or(all_levels().len().saturating_sub(1))
+    }
+
+    pub fn completed_in_pack(&self, pack: usize) -> usize {
+        all_levels()[pack_range(pack)]
+            .iter()
+            .filter(|level| self.is_completed(&level.id))
+            .count()
+    }
+
+    // Built-in levels can be played once their pack is unlocked.
+    pub fn is_level_unlocked(&self, index: usize) -> bool {
+        pack_of(index).is_some_and(|(pack, _)| self.is_pack_unlocked(pack))
+    }
+
+    // The levels the next and previous level controls lead to from level `index`,
+    // if there is one and it is unlocked. A pack's last level leads on to the next
+    // pack's first.
+    pub fn next_level(&self, index: usize) -> Option<usize> {
+        Some(index + 1).filter(|&next| self.is_level_unlocked(next))
+    }
+
+    pub fn previous_level(&self, index: usize) -> Option<usize> {
+        index
+            .checked_sub(1)
+            .filter(|&previous| self.is_level_unlocked(previous))
+    }
+
+    // The first pack is always open, later ones once enough of the previous pack
+    // is complete.
+    pub fn is_pack_unlocked(&self, pack: usize) -> bool {
+        let Some(previous) = pack.checked_sub(1) else {
+            return true;
+        };
+        let needed = all_packs()[previous].levels.len() as f32 * PACK_UNLOCK_SHARE;
+        self.completed_in_pack(previous) as f32 >= needed.ceil()
+    }
+
+    // Older saves kept levels by display name. Names of built-in levels are
+    // replaced with their ids, anything else is kept as it is.
+    fn migrate_level_names(&mut self) {
+        let id_of = |key: &str| {
+            all_levels()
+                .iter()
+                .find(|level| level.name == key)
+                .map_or_else(|| key.to_string(), |level| level.id.clone())
+        };
+        for key in self.completed_levels.iter_mut() {
+            *key = id_of(key);
+        }
+        self.completed_levels.sort();
+        self.completed_levels.dedup();
+        self.level_stars = std::mem::take(&mut self.level_stars).into_iter().fold(
+            BTreeMap::new(),
+            |mut stars, (key, rating)| {
+                let best = stars.entry(id_of(&key)).or_insert(rating);
+                *best = (*best).max(rating);
+                stars
+            },
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Self {
+        let mut save_data: Self = std::fs::read_to_string(SAVE_FILE_NAME)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        save_data.migrate_level_names();
+        save_data
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::from)
+            .and_then(|contents| std::fs::write(SAVE_FILE_NAME, contents));
+        if let Err(err) = result {
+            warn!("Failed to write {SAVE_FILE_NAME}: {err}");
+        }
+    }
+
+    // The web build has no file system, so progress only lasts for the session.
+    #[cfg(target_arch = "wasm32")]
+    pub fn load() -> Self {
+        Self::default()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn write(&self) {}
+}
+
+fn sync_volume(
+    global_volume_settings: Res<GlobalVolumeSettings>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    mut save_data: ResMut<SaveData>,
+) {
+    if global_volume_settings.is_changed() && save_data.volume != global_volume_settings.volume {
+        save_data.volume = global_volume_settings.volume;
+    }
+    if music_volume.is_changed() && save_data.music_volume != music_volume.volume {
+        save_data.music_volume = music_volume.volume;
+    }
+    if sfx_volume.is_changed() && save_data.sfx_volume != sfx_volume.volume {
+        save_data.sfx_volume = sfx_volume.volume;
+    }
+}
+
+fn sync_colorblind_mode(colorblind_mode: Res<ColorblindMode>, mut save_data: ResMut<SaveData>) {
+    if colorblind_mode.is_changed() && save_data.colorblind_mode != colorblind_mode.0 {
+        save_data.colorblind_mode = colorblind_mode.0;
+    }
+}
+
+fn sync_reduce_motion(reduce_motion: Res<ReduceMotion>, mut save_data: ResMut<SaveData>) {
+    if reduce_motion.is_changed() && save_data.reduce_motion != reduce_motion.0 {
+        save_data.reduce_motion = reduce_motion.0;
+    }
+}
+
+fn sync_rule_mode(rule_mode: Res<RuleMode>, mut save_data: ResMut<SaveData>) {
+    let strict = *rule_mode == RuleMode::Strict;
+    if rule_mode.is_changed() && save_data.strict_rules != strict {
+        save_data.strict_rules = strict;
+    }
+}
+
+fn sync_theme(theme: Res<Theme>, mut save_data: ResMut<SaveData>) {
+    if theme.is_changed() && save_data.theme != theme.name {
+        save_data.theme = theme.name.to_string();
+    }
+}
+
+fn sync_tile_filtering(filtering: Res<TileFiltering>, mut save_data: ResMut<SaveData>) {
+    let nearest = *filtering == TileFiltering::Nearest;
+    if filtering.is_changed() && save_data.nearest_filtering != nearest {
+        save_data.nearest_filtering = nearest;
+    }
+}
+
+fn sync_language(lang: Res<Lang>, mut save_data: ResMut<SaveData>) {
+    if lang.is_changed() && save_data.language != lang.code {
+        save_data.language = lang.code.to_string();
+    }
+}
+
+fn sync_achievements(achievements: Res<Achievements>, mut save_data: ResMut<SaveData>) {
+    if achievements.is_changed() && save_data.achievements != achievements.ids() {
+        save_data.achievements = achievements.ids();
+    }
+}
+
+fn sync_stats(stats: Res<Stats>, mut save_data: ResMut<SaveData>) {
+    if stats.is_changed() && save_data.stats != *stats {
+        save_data.stats = stats.clone();
+    }
+}
+
+fn record_completed_levels(
+    mut level_completed: EventReader<LevelCompleted>,
+    game_state: Res<GameState>,
+    level_stats: Res<LevelStats>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for _ in level_completed.iter() {
+        // Custom levels have no stable identity to remember them by.
+        if game_state.current_level == CustomLevel::index() {
+            continue;
+        }
+        if !save_data.is_completed(&game_state.id) {
+            save_data.completed_levels.push(game_state.id.clone());
+        }
+        // Only a better rating replaces the stored one. Levels solved with help
+        // have no rating.
+        let Some(stars) = level_stats.stars(&game_state.puzzle) else {
+            continue;
+        };
+        if save_data.level_stars.get(&game_state.id) < Some(&stars) {
+            save_data.level_stars.insert(game_state.id.clone(), stars);
+        }
+    }
+}
+
+// Seconds the save data has to stay unchanged before it is written, so dragging a
+// slider writes the file once instead of every frame.
+const SAVE_DELAY_SECONDS: f32 = 0.5;
+
+// Counts down to writing changed save data. Every change starts it again.
+#[derive(Resource, Default)]
+struct PendingSave(Option<Timer>);
+
+fn write_save_data(
+    time: Res<Time>,
+    save_data: Res<SaveData>,
+    mut pending_save: ResMut<PendingSave>,
+) {
+    if save_data.is_changed() && !save_data.is_added() {
+        pending_save.0 = Some(Timer::from_seconds(SAVE_DELAY_SECONDS, TimerMode::Once));
+    }
+    let Some(timer) = &mut pending_save.0 else {
+        return;
+    };
+    if timer.tick(time.delta()).finished() {
+        save_data.write();
+        pending_save.0 = None;
+    }
+}
+
+// Writes changes that are still waiting when the game is closed.
+fn write_save_data_on_exit(
+    exit: EventReader<AppExit>,
+    save_data: Res<SaveData>,
+    mut pending_save: ResMut<PendingSave>,
+) {
+    if !exit.is_empty() && pending_save.0.take().is_some() {
+        save_data.write();
+    }
+}
@@ -1,46 +1,181 @@
+use bevy::ecs::system::SystemParam;
 use bevy::text::TextStyle;
 use bevy::utils::default;
 
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 use bevy::ui::{Style, UiRect, Val};
 
-use crate::level::{all_levels, GameLevel};
+use crate::game_screen::{board_extent, iso_offset, star_text, CELL_SIZE};
+use crate::input::FocusedButton;
+use crate::lang::Lang;
+use crate::level::{
+    all_levels, level_difficulties, level_index, pack_range, CellType, Difficulty, GameLevel,
+    Puzzle,
+};
+use crate::save::SaveData;
+use crate::theme::Theme;
 use crate::{AppState, GameState};
 
 pub struct SelectLevelScreenPlugin<S: States + Copy>(pub S);
 
 impl<S: States + Copy> Plugin for SelectLevelScreenPlugin<S> {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(self.0), create_select_level_screen)
-            .add_systems(Update, (handle_button_click).run_if(in_state(self.0)))
+        app.init_resource::<SelectedPack>()
+            .init_resource::<LevelSortMode>()
+            .init_resource::<ShowUncompletedOnly>()
+            .add_systems(OnEnter(self.0), create_select_level_screen)
+            .add_systems(
+                Update,
+                (
+                    handle_button_click,
+                    handle_toolbar_click,
+                    update_level_listing.after(handle_toolbar_click),
+                    scroll_level_grid,
+                    scroll_to_focused_level,
+                )
+                    .run_if(in_state(self.0)),
+            )
             .add_systems(OnExit(self.0), destroy_select_level_screen);
     }
 }
 
 const BUTTON_COLOR: Color = Color::rgb(239.0 / 256.0, 167.0 / 256.0, 115.0 / 256.0);
+// The selected sort and filter are shown by their color, as the border is where the
+// gamepad focus is drawn.
+const SELECTED_TOOLBAR_COLOR: Color = crate::CUSTOM_ORANGE;
+// Size of the box the board preview on each level button is scaled to fit.
+const PREVIEW_SIZE: Vec2 = Vec2::new(160.0, 90.0);
+const BADGE_FONT_SIZE: f32 = 22.0;
+const DIFFICULTY_PIP_SIZE: f32 = 16.0;
+const LEVEL_COLUMNS: usize = 4;
+// Rows of levels that fit on screen; the grid scrolls when there are more.
+const VISIBLE_ROWS: usize = 4;
+// Pixels scrolled per mouse wheel line.
+const SCROLL_LINE_HEIGHT: f32 = 40.0;
 
 #[derive(Resource)]
 pub struct SelectLevelScreenRoot {
     root: Entity,
 }
 
-pub fn create_select_level_screen(mut commands: Commands, server: Res<AssetServer>) {
+// The level grid inside its clipping viewport, offset by `position` pixels.
+#[derive(Component, Default)]
+pub struct ScrollingLevelGrid {
+    position: f32,
+    rows: usize,
+}
+
+// Order in which levels are listed on the select screen.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LevelSortMode {
+    #[default]
+    Default,
+    Size,
+    Difficulty,
+}
+
+// Index into `all_packs()` of the pack whose levels are listed.
+#[derive(Resource, Default)]
+pub struct SelectedPack(pub usize);
+
+// Hide the levels that have already been completed.
+#[derive(Resource, Default)]
+pub struct ShowUncompletedOnly(pub bool);
+
+// All actions that can be triggered from a toolbar button click.
+#[derive(Component)]
+enum ToolbarAction {
+    Sort(LevelSortMode),
+    ToggleUncompletedOnly,
+}
+
+// The levels of the pack to show, paired with their index into `all_levels()`.
+fn listed_levels(
+    pack: usize,
+    sort_mode: LevelSortMode,
+    uncompleted_only: bool,
+    save_data: &SaveData,
+) -> Vec<(usize, &'static GameLevel)> {
+    let mut levels: Vec<(usize, &GameLevel)> = all_levels()[pack_range(pack)]
+        .iter()
+        .enumerate()
+        .map(|(index, level)| (level_index(pack, index), level))
+        .filter(|(_, level)| !uncompleted_only || !save_data.is_completed(&level.id))
+        .collect();
+    match sort_mode {
+        LevelSortMode::Default => {}
+        LevelSortMode::Size => levels.sort_by_key(|(_, level)| {
+            let (rows, cols) = level.puzzle.dims();
+            rows * cols
+        }),
+        LevelSortMode::Difficulty => levels.sort_by_key(|&(index, _)| level_difficulties()[index]),
+    }
+    levels
+}
+
+// What the listing shows: the levels of the selected pack, in which order, and
+// whether completed ones are left out.
+#[derive(SystemParam)]
+struct Listing<'w> {
+    selected_pack: Res<'w, SelectedPack>,
+    sort_mode: Res<'w, LevelSortMode>,
+    uncompleted_only: Res<'w, ShowUncompletedOnly>,
+    save_data: Res<'w, SaveData>,
+}
+
+fn create_select_level_screen(
+    mut commands: Commands,
+    listing: Listing,
+    theme: Res<Theme>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    spawn_select_level_screen(&mut commands, &listing, &theme, &lang, &server);
+}
+
+// Rebuilds the screen with the new listing when a toolbar button was clicked.
+fn update_level_listing(
+    mut commands: Commands,
+    root: Res<SelectLevelScreenRoot>,
+    listing: Listing,
+    theme: Res<Theme>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    if !listing.sort_mode.is_changed() && !listing.uncompleted_only.is_changed() {
+        return;
+    }
+    commands.entity(root.root).despawn_recursive();
+    spawn_select_level_screen(&mut commands, &listing, &theme, &lang, &server);
+}
+
+fn spawn_select_level_screen(
+    commands: &mut Commands,
+    listing: &Listing,
+    theme: &Theme,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    let sort_mode = *listing.sort_mode;
+    let uncompleted_only = listing.uncompleted_only.0;
+    let save_data = &*listing.save_data;
     let id = commands
         .spawn(ImageBundle {
             style: Style {
-                /// Use the CSS Grid algorithm for laying out this node
+                // Use the CSS Grid algorithm for laying out this node
                 display: Display::Grid,
-                /// Make node fill the entirety it's parent (in this case the window)
+                // Make node fill the entirety it's parent (in this case the window)
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
-                /// Set the grid to have 2 columns with sizes [min-content, minmax(0, 1fr)]
-                ///   - The first column will size to the size of it's contents
-                ///   - The second column will take up the remaining available space
+                // Set the grid to have 2 columns with sizes [min-content, minmax(0, 1fr)]
+                //   - The first column will size to the size of it's contents
+                //   - The second column will take up the remaining available space
                 grid_template_columns: vec![GridTrack::min_content(), GridTrack::flex(1.0)],
-                /// Set the grid to have 3 rows with sizes [auto, minmax(0, 1fr), 20px]
-                ///  - The first row will size to the size of it's contents
-                ///  - The second row take up remaining available space (after rows 1 and 3 have both been sized)
-                ///  - The third row will be exactly 20px high
+                // Set the grid to have 3 rows with sizes [auto, minmax(0, 1fr), 20px]
+                //  - The first row will size to the size of it's contents
+                //  - The second row take up remaining available space (after rows 1 and 3 have both been sized)
+                //  - The third row will be exactly 20px high
                 grid_template_rows: vec![
                     GridTrack::auto(),
                     GridTrack::flex(1.0),
@@ -52,36 +187,112 @@ pub fn create_select_level_screen(mut commands: Commands, server: Res<AssetServe
             ..default()
         })
         .with_children(|builder| {
-            // Main content grid (auto placed in row 2, column 1)
+            let levels = listed_levels(
+                listing.selected_pack.0,
+                sort_mode,
+                uncompleted_only,
+                save_data,
+            );
+            let rows = levels.len().div_ceil(LEVEL_COLUMNS).max(1);
+            // Viewport clipping the level grid (auto placed in row 2, column 1)
             builder
                 .spawn(NodeBundle {
                     style: Style {
-                        /// Make the height of the node fill its parent
+                        // Make the height of the node fill its parent
                         height: Val::Percent(100.0),
-                        /// Make the grid have a 1:1 aspect ratio meaning it will scale as an exact square
-                        /// As the height is set explicitly, this means the width will adjust to match the height
+                        // Make the viewport have a 1:1 aspect ratio meaning it will scale as an exact square
+                        // As the height is set explicitly, this means the width will adjust to match the height
                         aspect_ratio: Some(1.0),
-                        /// Use grid layout for this node
-                        display: Display::Grid,
-                        // Add 24px of padding around the grid
+                        flex_direction: FlexDirection::Column,
+                        // Hide the rows scrolled out of view
+                        overflow: Overflow::clip_y(),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    // Main content grid, taller than the viewport when there are more rows than fit
+                    builder
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Percent(
+                                        100.0 * rows.max(VISIBLE_ROWS) as f32 / VISIBLE_ROWS as f32,
+                                    ),
+                                    flex_shrink: 0.0,
+                                    // Use grid layout for this node
+                                    display: Display::Grid,
+                                    // Add 24px of padding around the grid
+                                    padding: UiRect::all(Val::Px(24.0)),
+                                    // Set the grid to have LEVEL_COLUMNS columns all with sizes minmax(0, 1fr)
+                                    // This creates exactly evenly sized columns
+                                    grid_template_columns: RepeatedGridTrack::flex(
+                                        LEVEL_COLUMNS as u16,
+                                        1.0,
+                                    ),
+                                    // Enough evenly sized rows for all levels, at least VISIBLE_ROWS of them
+                                    grid_template_rows: RepeatedGridTrack::flex(
+                                        rows.max(VISIBLE_ROWS) as u16,
+                                        1.0,
+                                    ),
+                                    // Set a 12px gap/gutter between rows and columns
+                                    row_gap: Val::Px(12.0),
+                                    column_gap: Val::Px(12.0),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            ScrollingLevelGrid {
+                                rows: rows.max(VISIBLE_ROWS),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|builder| {
+                            for (slot, (index, level)) in levels.iter().enumerate() {
+                                let stars = save_data.level_stars.get(&level.id).copied();
+                                let level_index = LevelIndex {
+                                    index: *index,
+                                    slot,
+                                };
+                                item_level(builder, level_index, level, stars, theme, lang, server);
+                            }
+                        });
+                });
+
+            // Toolbar with the sort and filter buttons (auto placed in row 1, column 2)
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::FlexStart,
                         padding: UiRect::all(Val::Px(24.0)),
-                        /// Set the grid to have 4 columns all with sizes minmax(0, 1fr)
-                        /// This creates 4 exactly evenly sized columns
-                        grid_template_columns: RepeatedGridTrack::flex(4, 1.0),
-                        /// Set the grid to have 4 rows all with sizes minmax(0, 1fr)
-                        /// This creates 4 exactly evenly sized rows
-                        grid_template_rows: RepeatedGridTrack::flex(4, 1.0),
-                        /// Set a 12px gap/gutter between rows and columns
                         row_gap: Val::Px(12.0),
-                        column_gap: Val::Px(12.0),
                         ..default()
                     },
                     ..default()
                 })
                 .with_children(|builder| {
-                    for (index, level) in all_levels().iter().enumerate() {
-                        item_level(builder, index, level, server.load(crate::TEXT_FONT_NAME));
+                    for (key, mode) in [
+                        ("select_level.sort_default", LevelSortMode::Default),
+                        ("select_level.sort_size", LevelSortMode::Size),
+                        ("select_level.sort_difficulty", LevelSortMode::Difficulty),
+                    ] {
+                        item_toolbar_button(
+                            builder,
+                            lang.t(key),
+                            ToolbarAction::Sort(mode),
+                            sort_mode == mode,
+                            server,
+                        );
                     }
+                    item_toolbar_button(
+                        builder,
+                        lang.t("select_level.uncompleted_only"),
+                        ToolbarAction::ToggleUncompletedOnly,
+                        uncompleted_only,
+                        server,
+                    );
                 });
         })
         .id();
@@ -89,19 +300,88 @@ pub fn create_select_level_screen(mut commands: Commands, server: Res<AssetServe
     commands.insert_resource(SelectLevelScreenRoot { root: id });
 }
 
+fn item_toolbar_button(
+    builder: &mut ChildBuilder,
+    label: &str,
+    action: ToolbarAction,
+    selected: bool,
+    server: &Res<AssetServer>,
+) {
+    builder
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::axes(Val::Px(16.0), Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor(if selected {
+                    SELECTED_TOOLBAR_COLOR
+                } else {
+                    BUTTON_COLOR
+                }),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|builder| {
+            builder.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+fn handle_toolbar_click(
+    interaction_query: Query<(&Interaction, &ToolbarAction), Changed<Interaction>>,
+    mut sort_mode: ResMut<LevelSortMode>,
+    mut uncompleted_only: ResMut<ShowUncompletedOnly>,
+) {
+    for (interaction, action) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *action {
+            ToolbarAction::Sort(mode) => {
+                if *sort_mode != mode {
+                    *sort_mode = mode;
+                }
+            }
+            ToolbarAction::ToggleUncompletedOnly => {
+                uncompleted_only.0 ^= true;
+            }
+        }
+    }
+}
+
+// Index into `all_levels()`, independent of the order the levels are listed in,
+// and the place of the level's button in the grid.
 #[derive(Component, Debug)]
 pub struct LevelIndex {
     index: usize,
+    slot: usize,
 }
 
-fn item_level(builder: &mut ChildBuilder, index: usize, level: &GameLevel, font: Handle<Font>) {
+fn item_level(
+    builder: &mut ChildBuilder,
+    level_index: LevelIndex,
+    level: &GameLevel,
+    stars: Option<u8>,
+    theme: &Theme,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    let difficulty = level_difficulties()[level_index.index];
     builder
         .spawn((
             ButtonBundle {
                 style: Style {
-                    display: Display::Grid,
+                    flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
-                    justify_items: JustifyItems::Center,
+                    justify_content: JustifyContent::Center,
                     padding: UiRect::all(Val::Px(3.0)),
                     ..default()
                 },
@@ -109,20 +389,155 @@ fn item_level(builder: &mut ChildBuilder, index: usize, level: &GameLevel, font:
                 border_color: BorderColor(Color::BLACK),
                 ..default()
             },
-            LevelIndex { index },
+            level_index,
         ))
         .with_children(|builder| {
+            let (rows, cols) = level.puzzle.dims();
+            builder.spawn(
+                TextBundle::from_section(
+                    format!("{rows}×{cols}"),
+                    TextStyle {
+                        font: server.load("NotoSerif-SemiBold.ttf"),
+                        font_size: BADGE_FONT_SIZE,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(6.0),
+                    top: Val::Px(4.0),
+                    ..default()
+                }),
+            );
+            builder.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(8.0),
+                    top: Val::Px(8.0),
+                    width: Val::Px(DIFFICULTY_PIP_SIZE),
+                    height: Val::Px(DIFFICULTY_PIP_SIZE),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: difficulty_color(difficulty).into(),
+                border_color: BorderColor(Color::WHITE),
+                ..default()
+            });
+            item_level_preview(builder, &level.puzzle, theme, server);
             builder.spawn(TextBundle::from_section(
-                level.name.clone(),
+                lang.level_name(&level.id, &level.name),
                 TextStyle {
-                    font,
-                    font_size: 48.0,
+                    font: server.load(crate::TEXT_FONT_NAME),
+                    font_size: 36.0,
                     color: Color::WHITE,
                 },
             ));
+            // Best rating, once the level has been completed.
+            if let Some(stars) = stars {
+                builder.spawn(TextBundle::from_section(
+                    star_text(stars),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 30.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                ));
+            }
         });
 }
 
+fn difficulty_color(difficulty: Difficulty) -> Color {
+    match difficulty {
+        Difficulty::Easy => Color::rgb(0.2, 0.8, 0.2),
+        Difficulty::Medium => Color::rgb(1.0, 0.8, 0.1),
+        Difficulty::Hard => Color::rgb(1.0, 0.3, 0.2),
+    }
+}
+
+// Draws a small isometric picture of the level terrain, laid out like the game board.
+// Boards of any shape are scaled to fit PREVIEW_SIZE and centered in it, so every
+// level button lines up the same way.
+fn item_level_preview(
+    builder: &mut ChildBuilder,
+    puzzle: &Puzzle,
+    theme: &Theme,
+    server: &Res<AssetServer>,
+) {
+    let extent = board_extent(puzzle);
+    let scale = (PREVIEW_SIZE / extent).min_element();
+    let cell_size = CELL_SIZE * scale;
+
+    builder
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(PREVIEW_SIZE.x),
+                height: Val::Px(PREVIEW_SIZE.y),
+                margin: UiRect::bottom(Val::Px(6.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(extent.x * scale),
+                        height: Val::Px(extent.y * scale),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    item_level_preview_cells(builder, puzzle, cell_size, theme, server);
+                });
+        });
+}
+
+fn item_level_preview_cells(
+    builder: &mut ChildBuilder,
+    puzzle: &Puzzle,
+    cell_size: f32,
+    theme: &Theme,
+    server: &Res<AssetServer>,
+) {
+    let (rows, cols) = puzzle.dims();
+    // UI coordinates grow downwards, so measure from the topmost cell corner.
+    let top = iso_offset(0, cols - 1, cell_size).y;
+
+    // Cells further down the screen are spawned later so they are drawn on top.
+    let mut cells: Vec<(usize, usize)> = (0..rows)
+        .flat_map(|r| (0..cols).map(move |c| (r, c)))
+        .collect();
+    cells.sort_by_key(|&(r, c)| (r as i32 - c as i32, r));
+
+    for (r, c) in cells {
+        let offset = iso_offset(r, c, cell_size);
+        let style = Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(offset.x),
+            top: Val::Px(top - offset.y),
+            width: Val::Px(cell_size),
+            height: Val::Px(cell_size),
+            ..default()
+        };
+        builder.spawn(ImageBundle {
+            style: style.clone(),
+            image: UiImage::new(theme.grass(server, r, c, 1)),
+            ..default()
+        });
+        let cell_type = puzzle.field[r][c];
+        if cell_type != CellType::Grass {
+            builder.spawn(ImageBundle {
+                style,
+                image: UiImage::new(theme.cell_texture(server, cell_type)),
+                ..default()
+            });
+        }
+    }
+}
+
 pub fn handle_button_click(
     mut interaction_query: Query<(&Interaction, &LevelIndex), Changed<Interaction>>,
     mut app_state: ResMut<NextState<AppState>>,
@@ -141,7 +556,74 @@ pub fn handle_button_click(
     }
 }
 
+// Clamps the scroll position so the grid never leaves a gap in the viewport.
+fn set_scroll_position(
+    grid: &mut ScrollingLevelGrid,
+    style: &mut Style,
+    position: f32,
+    grid_height: f32,
+    viewport_height: f32,
+) {
+    let max_scroll = (grid_height - viewport_height).max(0.0);
+    grid.position = position.clamp(-max_scroll, 0.0);
+    style.top = Val::Px(grid.position);
+}
+
+fn scroll_level_grid(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut grid_query: Query<(&mut ScrollingLevelGrid, &mut Style, &Parent, &Node)>,
+    node_query: Query<&Node>,
+) {
+    for event in mouse_wheel_events.iter() {
+        let dy = match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for (mut grid, mut style, parent, node) in grid_query.iter_mut() {
+            let viewport_height = node_query.get(parent.get()).unwrap().size().y;
+            let position = grid.position + dy;
+            set_scroll_position(
+                &mut grid,
+                &mut style,
+                position,
+                node.size().y,
+                viewport_height,
+            );
+        }
+    }
+}
+
+// Keeps the level focused with a gamepad inside the viewport.
+fn scroll_to_focused_level(
+    focused: Res<FocusedButton>,
+    level_query: Query<&LevelIndex>,
+    mut grid_query: Query<(&mut ScrollingLevelGrid, &mut Style, &Parent, &Node)>,
+    node_query: Query<&Node>,
+) {
+    if !focused.is_changed() {
+        return;
+    }
+    let Some(level_index) = focused.0.and_then(|entity| level_query.get(entity).ok()) else {
+        return;
+    };
+    for (mut grid, mut style, parent, node) in grid_query.iter_mut() {
+        let viewport_height = node_query.get(parent.get()).unwrap().size().y;
+        let row_height = node.size().y / grid.rows as f32;
+        let row_top = (level_index.slot / LEVEL_COLUMNS) as f32 * row_height;
+        let position = grid
+            .position
+            .max(-row_top)
+            .min(viewport_height - row_top - row_height);
+        set_scroll_position(
+            &mut grid,
+            &mut style,
+            position,
+            node.size().y,
+            viewport_height,
+        );
+    }
+}
+
 pub fn destroy_select_level_screen(mut commands: Commands, root: Res<SelectLevelScreenRoot>) {
-    let mut root_entity = commands.entity(root.root);
-    root_entity.despawn();
+    commands.entity(root.root).despawn_recursive();
 }
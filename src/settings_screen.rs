@@ -0,0 +1,705 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use crate::achievements::Achievements;
+use crate::confirm_dialog::{no_confirm_dialog, ConfirmAction, DialogConfirmed, ShowConfirmDialog};
+use crate::game_screen::RuleMode;
+use crate::lang::{Lang, Translated};
+use crate::save::SaveData;
+use crate::stats::Stats;
+use crate::theme::{Theme, TileFiltering};
+use crate::toast::ShowToast;
+use crate::{
+    AppState, ButtonInteractions, ColorblindMode, GameState, GlobalVolumeSettings, MusicVolume,
+    ReduceMotion, SfxVolume,
+};
+
+pub struct SettingsScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for SettingsScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_settings_screen)
+            .add_systems(
+                Update,
+                (
+                    button_system.run_if(no_confirm_dialog),
+                    drag_sliders.run_if(no_confirm_dialog),
+                    update_sliders,
+                    update_colorblind_mode_text,
+                    update_reduce_motion_text,
+                    update_rule_mode_text,
+                    update_theme_text,
+                    update_filtering_text,
+                    reset_progress_on_confirm,
+                )
+                    .run_if(in_state(self.0)),
+            )
+            .add_systems(OnExit(self.0), destroy_settings_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const SLIDER_TRACK_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+
+// Tag component used to tag entities added on the settings screen.
+#[derive(Component)]
+struct OnSettingsScreen;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum SettingsButtonAction {
+    Back,
+    ToggleColorblindMode,
+    ToggleReduceMotion,
+    ToggleRuleMode,
+    NextTheme,
+    ToggleFiltering,
+    NextLanguage,
+    ResetProgress,
+}
+
+#[derive(Component)]
+struct ColorblindModeText;
+
+#[derive(Component)]
+struct ReduceMotionText;
+
+#[derive(Component)]
+struct RuleModeText;
+
+fn reduce_motion_label(lang: &Lang, reduce_motion: ReduceMotion) -> String {
+    let key = if reduce_motion.0 {
+        "settings.motion_reduced"
+    } else {
+        "settings.motion_full"
+    };
+    lang.t(key).to_string()
+}
+
+#[derive(Component)]
+struct ThemeText;
+
+fn theme_label(lang: &Lang, theme: Theme) -> String {
+    lang.t_with("settings.theme", &[("theme", theme.name.to_string())])
+}
+
+#[derive(Component)]
+struct FilteringText;
+
+fn filtering_label(lang: &Lang, filtering: TileFiltering) -> String {
+    let filtering = lang.t(filtering.label_key()).to_string();
+    lang.t_with("settings.tiles", &[("filtering", filtering)])
+}
+
+fn colorblind_mode_label(lang: &Lang, colorblind_mode: ColorblindMode) -> String {
+    let key = if colorblind_mode.0 {
+        "settings.colorblind_on"
+    } else {
+        "settings.colorblind_off"
+    };
+    lang.t(key).to_string()
+}
+
+// The setting a slider is bound to.
+#[derive(Component, Clone, Copy)]
+enum SettingsSlider {
+    Volume,
+    Music,
+    Sfx,
+}
+
+// The settings the toggle buttons are bound to.
+#[derive(SystemParam)]
+struct ToggleSettings<'w> {
+    colorblind_mode: ResMut<'w, ColorblindMode>,
+    reduce_motion: ResMut<'w, ReduceMotion>,
+    rule_mode: ResMut<'w, RuleMode>,
+    theme: ResMut<'w, Theme>,
+    filtering: ResMut<'w, TileFiltering>,
+}
+
+// The resources the sliders are bound to.
+#[derive(SystemParam)]
+struct SliderValues<'w> {
+    global_volume_settings: ResMut<'w, GlobalVolumeSettings>,
+    music_volume: ResMut<'w, MusicVolume>,
+    sfx_volume: ResMut<'w, SfxVolume>,
+}
+
+impl SliderValues<'_> {
+    fn get(&self, slider: SettingsSlider) -> f32 {
+        match slider {
+            SettingsSlider::Volume => self.global_volume_settings.volume,
+            SettingsSlider::Music => self.music_volume.volume,
+            SettingsSlider::Sfx => self.sfx_volume.volume,
+        }
+    }
+
+    fn set(&mut self, slider: SettingsSlider, value: f32) {
+        match slider {
+            SettingsSlider::Volume => self.global_volume_settings.volume = value,
+            SettingsSlider::Music => self.music_volume.volume = value,
+            SettingsSlider::Sfx => self.sfx_volume.volume = value,
+        }
+    }
+}
+
+// The filled part of a slider track, sized to the slider value.
+#[derive(Component)]
+struct SliderFill;
+
+fn label_style(server: &Res<AssetServer>) -> TextStyle {
+    TextStyle {
+        font: server.load(crate::TEXT_FONT_NAME),
+        font_size: 60.0,
+        color: crate::CUSTOM_ORANGE,
+    }
+}
+
+fn item_slider(
+    builder: &mut ChildBuilder,
+    label_key: &'static str,
+    slider: SettingsSlider,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    builder
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(lang.t(label_key), label_style(server)).with_style(
+                    Style {
+                        width: Val::Px(200.0),
+                        ..default()
+                    },
+                ),
+                Translated(label_key),
+            ));
+            builder
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(400.0),
+                            height: Val::Px(30.0),
+                            border: UiRect::all(Val::Px(3.0)),
+                            ..default()
+                        },
+                        background_color: SLIDER_TRACK_COLOR.into(),
+                        border_color: BorderColor(crate::CUSTOM_ORANGE),
+                        ..default()
+                    },
+                    RelativeCursorPosition::default(),
+                    slider,
+                ))
+                .with_children(|builder| {
+                    builder.spawn((
+                        NodeBundle {
+                            style: Style {
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: crate::CUSTOM_ORANGE.into(),
+                            ..default()
+                        },
+                        SliderFill,
+                    ));
+                });
+        });
+}
+
+fn create_settings_screen(
+    mut commands: Commands,
+    settings: ToggleSettings,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                image: UiImage::new(server.load("full.png")),
+                ..default()
+            },
+            OnSettingsScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    lang.t("settings.title"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 120.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+                Translated("settings.title"),
+            ));
+
+            item_slider(
+                builder,
+                "settings.volume",
+                SettingsSlider::Volume,
+                &lang,
+                &server,
+            );
+            item_slider(
+                builder,
+                "settings.music",
+                SettingsSlider::Music,
+                &lang,
+                &server,
+            );
+            item_slider(
+                builder,
+                "settings.sounds",
+                SettingsSlider::Sfx,
+                &lang,
+                &server,
+            );
+
+            // The toggles wrap into two columns so the screen fits in the window.
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(660.0),
+                        flex_direction: FlexDirection::Row,
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::ToggleColorblindMode,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    colorblind_mode_label(&lang, *settings.colorblind_mode),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                ColorblindModeText,
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::ToggleReduceMotion,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    reduce_motion_label(&lang, *settings.reduce_motion),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                ReduceMotionText,
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::ToggleRuleMode,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    lang.t(settings.rule_mode.label_key()),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                RuleModeText,
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::NextTheme,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    theme_label(&lang, *settings.theme),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                ThemeText,
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::ToggleFiltering,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    filtering_label(&lang, *settings.filtering),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                FilteringText,
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::NextLanguage,
+                        ))
+                        .with_children(|builder| {
+                            // Each language names itself, so it can be found from any other.
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    lang.t("language.name"),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                Translated("language.name"),
+                            ));
+                        });
+
+                    builder
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Px(300.0),
+                                    height: Val::Px(65.0),
+                                    margin: UiRect::all(Val::Px(10.0)),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                background_color: NORMAL_BUTTON.into(),
+                                image: UiImage::new(server.load("UI/button_empty.png")),
+                                ..default()
+                            },
+                            SettingsButtonAction::ResetProgress,
+                        ))
+                        .with_children(|builder| {
+                            builder.spawn((
+                                TextBundle::from_section(
+                                    lang.t("settings.reset_progress"),
+                                    TextStyle {
+                                        font: server.load(crate::TEXT_FONT_NAME),
+                                        font_size: 50.0,
+                                        color: Color::WHITE,
+                                    },
+                                ),
+                                Translated("settings.reset_progress"),
+                            ));
+                        });
+                });
+
+            builder.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(50.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Px(30.0)),
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    image: UiImage::new(server.load("UI/button_back.png")),
+                    ..default()
+                },
+                SettingsButtonAction::Back,
+            ));
+        });
+}
+
+fn destroy_settings_screen(mut commands: Commands, query: Query<Entity, With<OnSettingsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// Moves a slider to the cursor while it is held down.
+fn drag_sliders(
+    slider_query: Query<(&Interaction, &RelativeCursorPosition, &SettingsSlider)>,
+    mut values: SliderValues,
+) {
+    for (interaction, cursor, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(position) = cursor.normalized {
+            let value = position.x.clamp(0.0, 1.0);
+            if values.get(*slider) != value {
+                values.set(*slider, value);
+            }
+        }
+    }
+}
+
+fn update_sliders(
+    slider_query: Query<(&SettingsSlider, &Children)>,
+    mut fill_query: Query<&mut Style, With<SliderFill>>,
+    values: SliderValues,
+) {
+    for (slider, children) in slider_query.iter() {
+        for &child in children.iter() {
+            if let Ok(mut style) = fill_query.get_mut(child) {
+                style.width = Val::Percent(100.0 * values.get(*slider));
+            }
+        }
+    }
+}
+
+fn update_colorblind_mode_text(
+    colorblind_mode: Res<ColorblindMode>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<ColorblindModeText>>,
+) {
+    if !colorblind_mode.is_changed() && !lang.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = colorblind_mode_label(&lang, *colorblind_mode);
+    }
+}
+
+fn update_reduce_motion_text(
+    reduce_motion: Res<ReduceMotion>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<ReduceMotionText>>,
+) {
+    if !reduce_motion.is_changed() && !lang.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = reduce_motion_label(&lang, *reduce_motion);
+    }
+}
+
+fn update_rule_mode_text(
+    rule_mode: Res<RuleMode>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<RuleModeText>>,
+) {
+    if !rule_mode.is_changed() && !lang.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = lang.t(rule_mode.label_key()).to_string();
+    }
+}
+
+fn update_theme_text(
+    theme: Res<Theme>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<ThemeText>>,
+) {
+    if !theme.is_changed() && !lang.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = theme_label(&lang, *theme);
+    }
+}
+
+fn update_filtering_text(
+    filtering: Res<TileFiltering>,
+    lang: Res<Lang>,
+    mut text_query: Query<&mut Text, With<FilteringText>>,
+) {
+    if !filtering.is_changed() && !lang.is_changed() {
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = filtering_label(&lang, *filtering);
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<SettingsButtonAction>,
+    mut settings: ToggleSettings,
+    mut lang: ResMut<Lang>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut confirm_dialog: EventWriter<ShowConfirmDialog>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                SettingsButtonAction::Back => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+                SettingsButtonAction::ToggleColorblindMode => {
+                    settings.colorblind_mode.0 ^= true;
+                }
+                SettingsButtonAction::ToggleReduceMotion => {
+                    settings.reduce_motion.0 ^= true;
+                }
+                SettingsButtonAction::ToggleRuleMode => {
+                    *settings.rule_mode = settings.rule_mode.toggled();
+                }
+                SettingsButtonAction::NextTheme => {
+                    *settings.theme = settings.theme.next();
+                }
+                SettingsButtonAction::ToggleFiltering => {
+                    *settings.filtering = settings.filtering.toggled();
+                }
+                SettingsButtonAction::NextLanguage => {
+                    *lang = lang.next();
+                }
+                SettingsButtonAction::ResetProgress => {
+                    confirm_dialog.send(ShowConfirmDialog {
+                        message: "dialog.reset_progress",
+                        action: ConfirmAction::ResetProgress,
+                    });
+                }
+            };
+        }
+    }
+}
+
+// Forgets completed levels, their ratings, achievements, stats and any boards left part way, and starts
+// again from the first level. Changing the save data writes it out.
+fn reset_progress_on_confirm(
+    mut confirmed: EventReader<DialogConfirmed>,
+    mut game_state: ResMut<GameState>,
+    mut save_data: ResMut<SaveData>,
+    mut achievements: ResMut<Achievements>,
+    mut stats: ResMut<Stats>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    for DialogConfirmed(action) in confirmed.iter() {
+        if *action == ConfirmAction::ResetProgress {
+            save_data.completed_levels.clear();
+            save_data.level_stars.clear();
+            achievements.clear();
+            *stats = Stats::default();
+            game_state.saved_progress.clear();
+            game_state.clear_progress();
+            game_state.current_level = 0;
+            toasts.send(ShowToast::new("toast.progress_reset"));
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::level::{GameLevel, Puzzle, PuzzleError};
+use crate::toast::ShowToast;
+use crate::{AppState, CustomLevel, GameState};
+
+pub struct SharePlugin;
+
+impl Plugin for SharePlugin {
+    fn build(&self, app: &mut App) {
+        // After the game state is set up, so the linked level can replace it.
+        app.add_systems(PostStartup, open_linked_level);
+    }
+}
+
+// Start of the URL fragment holding a level code, as in `#level=<code>`.
+const LEVEL_FRAGMENT: &str = "#level=";
+
+#[cfg(target_arch = "wasm32")]
+fn page_url() -> Option<String> {
+    web_sys::window()?.location().href().ok()
+}
+
+// Outside the browser there is no page to link to.
+#[cfg(not(target_arch = "wasm32"))]
+fn page_url() -> Option<String> {
+    None
+}
+
+// Link that opens the game on `puzzle`. Outside the browser it is only the fragment
+// to add to the game's address.
+pub fn share_link(puzzle: &Puzzle) -> Result<String, PuzzleError> {
+    let code = puzzle.encode()?;
+    let url = page_url().unwrap_or_default();
+    let page = url.split('#').next().unwrap_or_default();
+    Ok(format!("{page}{LEVEL_FRAGMENT}{code}"))
+}
+
+// Plays the level in the page URL, if it has one.
+pub fn open_linked_level(
+    mut game_state: ResMut<GameState>,
+    mut custom_level: ResMut<CustomLevel>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    let Some(url) = page_url() else {
+        return;
+    };
+    let Some((_, code)) = url.split_once(LEVEL_FRAGMENT) else {
+        return;
+    };
+    match Puzzle::decode(code) {
+        Ok(puzzle) => {
+            custom_level.0 = Some(GameLevel::imported(puzzle));
+            game_state.current_level = CustomLevel::index();
+            app_state.set(AppState::SwitchLevel);
+        }
+        Err(err) => {
+            toasts.send(ShowToast::with(
+                "toast.link_failed",
+                vec![("error", err.to_string())],
+            ));
+        }
+    }
+}
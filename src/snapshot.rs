@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::ScreenshotManager;
+use bevy::window::PrimaryWindow;
+
+use crate::confirm_dialog::no_confirm_dialog;
+use crate::pause_menu::not_paused;
+use crate::save::level_file_name;
+use crate::toast::ShowToast;
+use crate::{AppState, GameState};
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingSnapshot>().add_systems(
+            Update,
+            (
+                restore_hidden_ui,
+                take_board_snapshot.run_if(
+                    in_state(AppState::InGame)
+                        .and_then(not_paused)
+                        .and_then(no_confirm_dialog),
+                ),
+            )
+                .chain(),
+        );
+    }
+}
+
+// File name of the snapshot taken last frame, announced once the UI is back so the
+// toast doesn't end up in the image.
+#[derive(Resource, Default)]
+struct PendingSnapshot(Option<String>);
+
+// UI roots hidden for the frame a snapshot is taken in, so only the board is captured.
+#[derive(Component)]
+struct HiddenForSnapshot;
+
+// Root UI nodes, hidden while the snapshot is taken.
+type UiRoot = (With<Node>, Without<Parent>);
+
+// S saves the board to a PNG named after the level. The web build downloads it.
+fn take_board_snapshot(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    game_state: Res<GameState>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut ui_query: Query<(Entity, &mut Visibility), UiRoot>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    mut pending_snapshot: ResMut<PendingSnapshot>,
+) {
+    if !keys.just_pressed(KeyCode::S) {
+        return;
+    }
+    let file_name = level_file_name(&game_state.id, "png");
+    if screenshot_manager
+        .save_screenshot_to_disk(window_query.single(), &file_name)
+        .is_err()
+    {
+        return;
+    }
+    for (entity, mut visibility) in ui_query.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            commands.entity(entity).insert(HiddenForSnapshot);
+        }
+    }
+    pending_snapshot.0 = Some(file_name);
+}
+
+fn restore_hidden_ui(
+    mut commands: Commands,
+    mut hidden_query: Query<(Entity, &mut Visibility), With<HiddenForSnapshot>>,
+    mut pending_snapshot: ResMut<PendingSnapshot>,
+    mut toasts: EventWriter<ShowToast>,
+) {
+    for (entity, mut visibility) in hidden_query.iter_mut() {
+        *visibility = Visibility::Inherited;
+        commands.entity(entity).remove::<HiddenForSnapshot>();
+    }
+    if let Some(file_name) = pending_snapshot.0.take() {
+        toasts.send(ShowToast::with("toast.saved", vec![("file", file_name)]));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::level::*;
+
+/// Returns the first solution found for the puzzle, if there is any.
+pub fn solve(puzzle: &Puzzle) -> Option<Solution> {
+    let mut found = None;
+    search(puzzle, &mut |solution| {
+        found = Some(solution);
+        false
+    });
+    found
+}
+
+/// Counts the solutions of the puzzle, stopping once `limit` of them are found.
+pub fn count_solutions(puzzle: &Puzzle, limit: usize) -> usize {
+    let mut count = 0;
+    search(puzzle, &mut |_| {
+        count += 1;
+        count < limit
+    });
+    count
+}
+
+/// Number of search steps needed to enumerate every solution. Puzzles that need more
+/// backtracking take more steps, so this is a rough measure of how hard they are.
+pub fn search_effort(puzzle: &Puzzle) -> usize {
+    search(puzzle, &mut |_| true)
+}
+
+struct SearchState {
+    has_house: Vec<Vec<bool>>,
+    // Kind of each placed house, which decides what it adds to the line counts.
+    kinds: Vec<Vec<HouseKind>>,
+    row_houses: Vec<usize>,
+    col_houses: Vec<usize>,
+    // Number of cells decided so far, counting revisits after backtracking.
+    steps: usize,
+}
+
+impl SearchState {
+    fn set_house(&mut self, row: usize, col: usize, kind: Option<HouseKind>) {
+        self.has_house[row][col] = kind.is_some();
+        if let Some(kind) = kind {
+            self.kinds[row][col] = kind;
+            self.row_houses[row] += kind.weight();
+            self.col_houses[col] += kind.weight();
+        } else {
+            let weight = self.kinds[row][col].weight();
+            self.row_houses[row] -= weight;
+            self.col_houses[col] -= weight;
+        }
+    }
+}
+
+// Enumerates all solutions, calling `on_solution` for each one until it returns false.
+// Returns the number of search steps taken.
+fn search(puzzle: &Puzzle, on_solution: &mut dyn FnMut(Solution) -> bool) -> usize {
+    let (rows, cols) = puzzle.dims();
+    let mut state = SearchState {
+        has_house: vec![vec![false; cols]; rows],
+        kinds: vec![vec![HouseKind::Small; cols]; rows],
+        row_houses: vec![0; rows],
+        col_houses: vec![0; cols],
+        steps: 0,
+    };
+    for (row, line) in puzzle.field.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            if cell == CellType::House {
+                state.set_house(row, col, Some(HouseKind::Small));
+            }
+        }
+    }
+    search_cell(puzzle, 0, &mut state, on_solution);
+    state.steps
+}
+
+// Decides whether cell number `index` (in row-major order) holds a house.
+// Returns false once `on_solution` asked to stop the search.
+fn search_cell(
+    puzzle: &Puzzle,
+    index: usize,
+    state: &mut SearchState,
+    on_solution: &mut dyn FnMut(Solution) -> bool,
+) -> bool {
+    state.steps += 1;
+    let (rows, cols) = puzzle.dims();
+    if index == rows * cols {
+        return report_if_valid(puzzle, state, on_solution);
+    }
+    let (row, col) = (index / cols, index % cols);
+
+    if can_place(puzzle, row, col, state) {
+        let kinds: &[HouseKind] = if puzzle.house_kinds {
+            &[HouseKind::Small, HouseKind::Large]
+        } else {
+            &[HouseKind::Small]
+        };
+        for &kind in kinds {
+            state.set_house(row, col, Some(kind));
+            let keep_going = !is_feasible(puzzle, row, col, state)
+                || search_cell(puzzle, index + 1, state, on_solution);
+            state.set_house(row, col, None);
+            if !keep_going {
+                return false;
+            }
+        }
+    }
+
+    !is_feasible(puzzle, row, col, state) || search_cell(puzzle, index + 1, state, on_solution)
+}
+
+fn can_place(puzzle: &Puzzle, row: usize, col: usize, state: &SearchState) -> bool {
+    // Only the neighbors above and to the left have been decided so far. The cells
+    // below and to the right can only hold given houses.
+    let (rows, cols) = puzzle.dims();
+    if !variant_neighbor_houses(row, col, &state.has_house, puzzle).is_empty() {
+        return false;
+    }
+    puzzle.field[row][col] == CellType::Grass
+        && !(row > 0 && state.has_house[row - 1][col])
+        && !(col > 0 && state.has_house[row][col - 1])
+        && !(row + 1 < rows && state.has_house[row + 1][col])
+        && !(col + 1 < cols && state.has_house[row][col + 1])
+}
+
+// Checks that the line counts can still be met after deciding cell (row, col).
+fn is_feasible(puzzle: &Puzzle, row: usize, col: usize, state: &SearchState) -> bool {
+    let (rows, cols) = puzzle.dims();
+    // Each cell left can still take a house of the largest kind.
+    let room_left_in_row = (cols - col - 1) * puzzle.max_house_weight();
+    let room_left_in_col = (rows - row - 1) * puzzle.max_house_weight();
+    let row_houses = state.row_houses[row];
+    let col_houses = state.col_houses[col];
+    row_houses <= puzzle.row_count[row]
+        && row_houses + room_left_in_row >= puzzle.row_count[row]
+        && col_houses <= puzzle.col_count[col]
+        && col_houses + room_left_in_col >= puzzle.col_count[col]
+        && !overflows_constraints(puzzle, row, col, state)
+}
+
+// Checks whether a house at (row, col) gives a lake or mountain too many houses.
+fn overflows_constraints(puzzle: &Puzzle, row: usize, col: usize, state: &SearchState) -> bool {
+    if !state.has_house[row][col] {
+        return false;
+    }
+    for nrow in 0..puzzle.rows() {
+        for ncol in 0..puzzle.cols() {
+            let count = match puzzle.field[nrow][ncol] {
+                CellType::Lake(_) if nrow.abs_diff(row) <= 1 && ncol.abs_diff(col) <= 1 => {
+                    count_houses_in_3x3(nrow, ncol, &state.has_house, puzzle)
+                }
+                CellType::Mountain(_) if nrow.abs_diff(row) == ncol.abs_diff(col) => {
+                    count_diagnoal_houses(nrow, ncol, &state.has_house, puzzle)
+                }
+                _ => continue,
+            };
+            if Some(count) > puzzle.field[nrow][ncol].required_count() {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn report_if_valid(
+    puzzle: &Puzzle,
+    state: &SearchState,
+    on_solution: &mut dyn FnMut(Solution) -> bool,
+) -> bool {
+    let mut solution = Solution::default();
+    for (row, line) in state.has_house.iter().enumerate() {
+        for (col, &has_house) in line.iter().enumerate() {
+            // Given houses are part of the puzzle, not of the solution.
+            if has_house && puzzle.field[row][col] == CellType::Grass {
+                solution.placements.push(Placement {
+                    position: Position { row, col },
+                    kind: state.kinds[row][col],
+                });
+            }
+        }
+    }
+    if validate_solution(&solution, puzzle).complete {
+        on_solution(solution)
+    } else {
+        true
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game_screen::{LevelCompleted, LevelStats};
+
+pub struct StatsPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for StatsPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, record_completed_level.run_if(in_state(self.0)))
+            // Time and houses are added once per visit, rather than every frame, so the
+            // save file is not written all the time.
+            .add_systems(OnExit(self.0), record_level_visit);
+    }
+}
+
+// Totals over every level played, loaded from and written back to `SaveData`.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub struct Stats {
+    pub levels_completed: usize,
+    pub houses_placed: usize,
+    // Seconds spent on levels, not counting time after they were complete.
+    pub play_time: f32,
+    // Moves over all completed levels, for the average.
+    pub completed_moves: usize,
+    // Quickest completion in seconds. Levels solved with help don't count.
+    pub fastest_solve: Option<f32>,
+}
+
+impl Stats {
+    pub fn average_moves(&self) -> Option<f32> {
+        (self.levels_completed > 0)
+            .then(|| self.completed_moves as f32 / self.levels_completed as f32)
+    }
+}
+
+fn record_completed_level(
+    mut level_completed: EventReader<LevelCompleted>,
+    level_stats: Res<LevelStats>,
+    mut stats: ResMut<Stats>,
+) {
+    for _ in level_completed.iter() {
+        stats.levels_completed += 1;
+        stats.completed_moves += level_stats.moves;
+        let time = level_stats.elapsed.as_secs_f32();
+        if !level_stats.assisted && stats.fastest_solve.is_none_or(|best| time < best) {
+            stats.fastest_solve = Some(time);
+        }
+    }
+}
+
+fn record_level_visit(level_stats: Res<LevelStats>, mut stats: ResMut<Stats>) {
+    stats.play_time += level_stats.elapsed.as_secs_f32();
+    stats.houses_placed += level_stats.houses_placed;
+}
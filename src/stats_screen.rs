@@ -0,0 +1,174 @@
+use bevy::prelude::*;
+
+use crate::lang::Lang;
+use crate::stats::Stats;
+use crate::{AppState, ButtonInteractions};
+
+pub struct StatsScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for StatsScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_stats_screen)
+            .add_systems(Update, button_system.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_stats_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+
+// Tag component used to tag entities added on the stats screen.
+#[derive(Component)]
+struct OnStatsScreen;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum StatsButtonAction {
+    Back,
+}
+
+// Whole seconds, with minutes and hours once there are any.
+pub fn format_seconds(seconds: f32) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds:02}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+fn item_stat(builder: &mut ChildBuilder, label: &str, value: String, server: &Res<AssetServer>) {
+    builder
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Px(600.0),
+                justify_content: JustifyContent::SpaceBetween,
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|builder| {
+            for (text, color) in [
+                (label.to_string(), Color::BLACK),
+                (value, crate::CUSTOM_ORANGE),
+            ] {
+                builder.spawn(TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 60.0,
+                        color,
+                    },
+                ));
+            }
+        });
+}
+
+fn create_stats_screen(
+    mut commands: Commands,
+    stats: Res<Stats>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    let none = || lang.t("stats.none").to_string();
+    let rows = [
+        ("stats.levels_completed", stats.levels_completed.to_string()),
+        ("stats.houses_placed", stats.houses_placed.to_string()),
+        ("stats.play_time", format_seconds(stats.play_time)),
+        (
+            "stats.average_moves",
+            stats
+                .average_moves()
+                .map_or_else(none, |moves| format!("{moves:.1}")),
+        ),
+        (
+            "stats.fastest_solve",
+            stats.fastest_solve.map_or_else(none, format_seconds),
+        ),
+    ];
+
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                image: UiImage::new(server.load("full.png")),
+                ..default()
+            },
+            OnStatsScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn(
+                TextBundle::from_section(
+                    lang.t("stats.title"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 120.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+
+            for (label, value) in rows {
+                item_stat(builder, lang.t(label), value, &server);
+            }
+
+            builder.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(50.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Px(30.0)),
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    image: UiImage::new(server.load("UI/button_back.png")),
+                    ..default()
+                },
+                StatsButtonAction::Back,
+            ));
+        });
+}
+
+fn destroy_stats_screen(mut commands: Commands, query: Query<Entity, With<OnStatsScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<StatsButtonAction>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                StatsButtonAction::Back => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::asset::HandleId;
+use bevy::prelude::*;
+use bevy::render::texture::ImageSampler;
+
+use crate::level::{CellType, HouseKind};
+use crate::tile_atlas::TileAtlas;
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_tile_filtering);
+    }
+}
+
+// Number of random variants of the grass and marker tiles.
+const TILE_VARIANTS: u32 = 3;
+
+// Texture names of the board tiles. `{variant}` in a grass or marker name is replaced
+// with the cell's random variant, from 1 to 3.
+#[derive(Resource, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    // Grass alternates between the two in a checkerboard pattern.
+    pub grass_dark: &'static str,
+    pub grass_light: &'static str,
+    pub tree: &'static str,
+    // A lake with more than one frame is animated.
+    pub lake: &'static [&'static str],
+    pub mountain: &'static str,
+    pub rock: &'static str,
+    pub house: &'static str,
+    // Large houses on puzzles with the house kinds rule.
+    pub house_large: &'static str,
+    pub cross: &'static str,
+    pub marker: &'static str,
+}
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "Classic",
+        grass_dark: "grass_iso_dark_{variant}.png",
+        grass_light: "grass_iso_light_{variant}.png",
+        tree: "forest_iso.png",
+        lake: &["lake_iso.png", "lake_iso_old.png"],
+        mountain: "mountain_iso.png",
+        rock: "rock_iso.png",
+        house: "house_iso.png",
+        house_large: "house_iso_old.png",
+        cross: "cross_iso.png",
+        marker: "marker_iso_{variant}.png",
+    },
+    // The tiles from the first version of the game.
+    Theme {
+        name: "Retro",
+        grass_dark: "grass_iso_1.png",
+        grass_light: "grass_iso_2.png",
+        tree: "forest_iso.png",
+        lake: &["lake_iso_old.png"],
+        mountain: "mountain_iso_old.png",
+        rock: "rock_iso.png",
+        house: "house_iso_old.png",
+        house_large: "house_iso.png",
+        cross: "cross_iso.png",
+        marker: "marker_iso.png",
+    },
+];
+
+impl Default for Theme {
+    fn default() -> Self {
+        THEMES[0]
+    }
+}
+
+fn with_variant(pattern: &str, variant: u32) -> String {
+    pattern.replace("{variant}", &variant.to_string())
+}
+
+impl Theme {
+    // Every texture the theme uses, with the variants spelled out.
+    pub fn texture_names(&self) -> Vec<String> {
+        let patterns = [
+            self.grass_dark,
+            self.grass_light,
+            self.tree,
+            self.mountain,
+            self.rock,
+            self.house,
+            self.house_large,
+            self.cross,
+            self.marker,
+        ];
+        let mut names: Vec<String> = patterns
+            .iter()
+            .chain(self.lake)
+            .flat_map(|pattern| (1..=TILE_VARIANTS).map(|variant| with_variant(pattern, variant)))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        THEMES.iter().find(|theme| theme.name == name).copied()
+    }
+
+    // The theme after this one in `THEMES`, wrapping around.
+    pub fn next(self) -> Theme {
+        let index = THEMES.iter().position(|theme| *theme == self).unwrap_or(0);
+        THEMES[(index + 1) % THEMES.len()]
+    }
+
+    pub fn grass_name(&self, r: usize, c: usize, variant: u32) -> String {
+        let pattern = if (r + c).is_multiple_of(2) {
+            self.grass_dark
+        } else {
+            self.grass_light
+        };
+        with_variant(pattern, variant)
+    }
+
+    pub fn grass(&self, server: &AssetServer, r: usize, c: usize, variant: u32) -> Handle<Image> {
+        server.load(self.grass_name(r, c, variant))
+    }
+
+    pub fn marker(&self, server: &AssetServer, variant: u32) -> Handle<Image> {
+        server.load(with_variant(self.marker, variant))
+    }
+
+    pub fn cell_texture_name(&self, cell_type: CellType) -> String {
+        match cell_type {
+            CellType::Grass => with_variant(self.grass_light, 1),
+            CellType::Tree => self.tree.to_string(),
+            CellType::Lake(_) => self.lake[0].to_string(),
+            CellType::Mountain(_) => self.mountain.to_string(),
+            CellType::Rock => self.rock.to_string(),
+            CellType::House => self.house.to_string(),
+        }
+    }
+
+    pub fn house_texture(&self, server: &AssetServer, kind: HouseKind) -> Handle<Image> {
+        server.load(match kind {
+            HouseKind::Small => self.house,
+            HouseKind::Large => self.house_large,
+        })
+    }
+
+    pub fn cell_texture(&self, server: &AssetServer, cell_type: CellType) -> Handle<Image> {
+        server.load(self.cell_texture_name(cell_type))
+    }
+
+    // Textures that are drawn on every board and never animated, the ones worth
+    // packing into `TileAtlas`.
+    pub fn static_tile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = (1..=TILE_VARIANTS)
+            .flat_map(|variant| {
+                [
+                    with_variant(self.grass_dark, variant),
+                    with_variant(self.grass_light, variant),
+                ]
+            })
+            .chain([self.tree, self.mountain, self.rock, self.house].map(String::from))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // Frames of the cell types that are animated, see `AnimatedTile`.
+    pub fn animation_frames(
+        &self,
+        server: &AssetServer,
+        cell_type: CellType,
+    ) -> Option<Vec<Handle<Image>>> {
+        match cell_type {
+            CellType::Lake(_) if self.lake.len() > 1 => {
+                Some(self.lake.iter().map(|&name| server.load(name)).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
+// How the tile textures are sampled when scaled. Nearest keeps pixel art sharp,
+// linear smooths it.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TileFiltering {
+    #[default]
+    Linear,
+    Nearest,
+}
+
+impl TileFiltering {
+    // Translation key of the name shown in the settings.
+    pub fn label_key(self) -> &'static str {
+        match self {
+            TileFiltering::Linear => "settings.tiles_smooth",
+            TileFiltering::Nearest => "settings.tiles_sharp",
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            TileFiltering::Linear => TileFiltering::Nearest,
+            TileFiltering::Nearest => TileFiltering::Linear,
+        }
+    }
+
+    pub fn sampler(self) -> ImageSampler {
+        match self {
+            TileFiltering::Linear => ImageSampler::linear(),
+            TileFiltering::Nearest => ImageSampler::nearest(),
+        }
+    }
+}
+
+// Sets the sampler of the theme's tile images and the tile atlas as they load, and of
+// every loaded one when the filtering or theme changes. Other images, like the UI and
+// fonts, keep the default sampler.
+fn apply_tile_filtering(
+    filtering: Res<TileFiltering>,
+    theme: Res<Theme>,
+    tile_atlas: Res<TileAtlas>,
+    server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+) {
+    let loaded: Vec<HandleId> = image_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } => Some(handle.id()),
+            _ => None,
+        })
+        .collect();
+    let ids: Vec<HandleId> = if filtering.is_changed() || theme.is_changed() {
+        images.ids().collect()
+    } else {
+        loaded
+    };
+    if ids.is_empty() {
+        return;
+    }
+
+    let names = theme.texture_names();
+    for id in ids {
+        let is_atlas = tile_atlas
+            .texture
+            .as_ref()
+            .is_some_and(|texture| texture.id() == id);
+        let is_tile = is_atlas
+            || server.get_handle_path(id).is_some_and(|path| {
+                path.path()
+                    .to_str()
+                    .is_some_and(|path| names.iter().any(|name| name == path))
+            });
+        if !is_tile {
+            continue;
+        }
+        if let Some(image) = images.get_mut(&Handle::weak(id)) {
+            image.sampler_descriptor = filtering.sampler();
+        }
+    }
+}
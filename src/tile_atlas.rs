@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use bevy::asset::LoadState;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+use crate::theme::{Theme, TileFiltering};
+
+pub struct TileAtlasPlugin;
+
+impl Plugin for TileAtlasPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TileAtlas>()
+            .add_systems(Update, (toggle_tile_atlas, build_tile_atlas));
+    }
+}
+
+// Big enough for the largest theme, whose tiles are up to 1280 pixels wide.
+const TILE_ATLAS_MAX_SIZE: f32 = 4096.0;
+
+// The static tiles of the current theme packed into one texture. Sprites sharing a
+// texture are drawn in one batch, while the board interleaves six grass textures
+// and the terrain, which otherwise breaks the batch at almost every tile.
+#[derive(Resource, Default)]
+pub struct TileAtlas {
+    // Whether boards take their tiles from the atlas. It is off until the atlas is
+    // measured to be faster: F4 switches it for the next board, and the F3 overlay
+    // shows the frame rate to compare. The atlas is only built while it is on.
+    pub enabled: bool,
+    atlas: Option<Handle<TextureAtlas>>,
+    pub texture: Option<Handle<Image>>,
+    // Index of each tile in the atlas, by texture name.
+    indices: HashMap<String, usize>,
+    // The theme the atlas is built or being loaded for.
+    theme: Option<Theme>,
+    // Tiles still loading before the atlas can be built.
+    pending: Vec<(String, Handle<Image>)>,
+}
+
+impl TileAtlas {
+    // Whether the atlas of the current theme is built, or was given up on after a
+    // tile failed to load.
+    pub fn is_ready(&self) -> bool {
+        self.theme.is_some() && self.pending.is_empty()
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.atlas.is_some()
+    }
+}
+
+// Spawns a sprite of the named tile, taken from the atlas when it has the tile and
+// from the tile's own texture otherwise.
+pub fn spawn_tile<'w, 's, 'a>(
+    builder: &'a mut ChildBuilder<'w, 's, '_>,
+    tile_atlas: &TileAtlas,
+    server: &AssetServer,
+    name: &str,
+    size: Vec2,
+    transform: Transform,
+) -> EntityCommands<'w, 's, 'a> {
+    let atlas_index = tile_atlas
+        .atlas
+        .as_ref()
+        .filter(|_| tile_atlas.enabled)
+        .zip(tile_atlas.indices.get(name));
+    match atlas_index {
+        Some((atlas, &index)) => builder.spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index,
+                custom_size: Some(size),
+                anchor: Anchor::CenterLeft,
+                ..default()
+            },
+            texture_atlas: atlas.clone(),
+            transform,
+            ..default()
+        }),
+        None => builder.spawn(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(size),
+                anchor: Anchor::CenterLeft,
+                ..default()
+            },
+            texture: server.load(name),
+            transform,
+            ..default()
+        }),
+    }
+}
+
+fn toggle_tile_atlas(keys: Res<Input<KeyCode>>, mut tile_atlas: ResMut<TileAtlas>) {
+    if keys.just_pressed(KeyCode::F4) {
+        tile_atlas.enabled ^= true;
+    }
+}
+
+// Loads the tiles of the current theme and packs them once all of them are loaded.
+// Boards spawned before that use the separate textures. Nothing is loaded while the
+// atlas is off, so the first F4 press builds it.
+fn build_tile_atlas(
+    theme: Res<Theme>,
+    filtering: Res<TileFiltering>,
+    server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    mut tile_atlas: ResMut<TileAtlas>,
+) {
+    if !tile_atlas.enabled {
+        return;
+    }
+    if tile_atlas.theme != Some(*theme) {
+        *tile_atlas = TileAtlas {
+            enabled: tile_atlas.enabled,
+            theme: Some(*theme),
+            pending: theme
+                .static_tile_names()
+                .into_iter()
+                .map(|name| {
+                    let handle = server.load(name.as_str());
+                    (name, handle)
+                })
+                .collect(),
+            ..default()
+        };
+    }
+    if tile_atlas.pending.is_empty() {
+        return;
+    }
+    let load_state =
+        server.get_group_load_state(tile_atlas.pending.iter().map(|(_, handle)| handle.id()));
+    match load_state {
+        LoadState::Loaded => {}
+        LoadState::Failed => {
+            warn!("Failed to load the tiles of the {} theme", theme.name);
+            tile_atlas.pending.clear();
+            return;
+        }
+        _ => return,
+    }
+
+    let mut builder = TextureAtlasBuilder::default().max_size(Vec2::splat(TILE_ATLAS_MAX_SIZE));
+    for (_, handle) in &tile_atlas.pending {
+        if let Some(image) = images.get(handle) {
+            builder.add_texture(handle.clone(), image);
+        }
+    }
+    let atlas = match builder.finish(&mut images) {
+        Ok(atlas) => atlas,
+        Err(err) => {
+            warn!("Failed to build the tile atlas: {err:?}");
+            tile_atlas.pending.clear();
+            return;
+        }
+    };
+    let pending = std::mem::take(&mut tile_atlas.pending);
+    tile_atlas.indices = pending
+        .iter()
+        .filter_map(|(name, handle)| Some((name.clone(), atlas.get_texture_index(handle)?)))
+        .collect();
+    if let Some(image) = images.get_mut(&atlas.texture) {
+        image.sampler_descriptor = filtering.sampler();
+    }
+    tile_atlas.texture = Some(atlas.texture.clone());
+    tile_atlas.atlas = Some(atlases.add(atlas));
+}
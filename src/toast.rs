@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+use crate::lang::Lang;
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ShowToast>()
+            .add_systems(Update, (show_toasts, expire_toasts));
+    }
+}
+
+const TOAST_SECONDS: f32 = 3.0;
+const TOAST_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+
+// Shows a short message at the bottom of the screen, on top of every screen. The
+// message is a `Lang` key, with the values for its `{name}` placeholders.
+#[derive(Event)]
+pub struct ShowToast {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl ShowToast {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with(key: &'static str, args: Vec<(&'static str, String)>) -> Self {
+        Self { key, args }
+    }
+}
+
+#[derive(Component)]
+struct Toast(Timer);
+
+fn show_toasts(
+    mut commands: Commands,
+    mut events: EventReader<ShowToast>,
+    toast_query: Query<Entity, With<Toast>>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    let Some(toast) = events.iter().last() else {
+        return;
+    };
+    let message = lang.t_with(toast.key, &toast.args);
+    // A new message replaces the one on screen.
+    for entity in toast_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                z_index: ZIndex::Global(200),
+                ..default()
+            },
+            Toast(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: TOAST_BACKGROUND.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn(TextBundle::from_section(
+                        message,
+                        TextStyle {
+                            font: server.load("NotoSerif-SemiBold.ttf"),
+                            font_size: 28.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+fn expire_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut Toast)>,
+) {
+    for (entity, mut toast) in toast_query.iter_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::game_screen::{world_to_board, GameScreenRoot};
+use crate::lang::Lang;
+use crate::level::{CellType, Position, Puzzle};
+use crate::GameState;
+
+pub struct TerrainTooltipPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for TerrainTooltipPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_terrain_tooltip)
+            .add_systems(Update, update_terrain_tooltip.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_terrain_tooltip);
+    }
+}
+
+const TOOLTIP_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+// Distance from the cursor to the tooltip's top left corner, so it doesn't hide the cell.
+const TOOLTIP_OFFSET: Vec2 = Vec2::new(20.0, 20.0);
+
+#[derive(Component)]
+struct TerrainTooltip;
+
+#[derive(Component)]
+struct TerrainTooltipText;
+
+// The rule of a cell that constrains the houses around it.
+fn terrain_rule(cell: CellType, puzzle: &Puzzle, lang: &Lang) -> Option<String> {
+    let (key, count) = match cell {
+        CellType::Lake(count) => ("tooltip.lake", count),
+        CellType::Mountain(count) if puzzle.blocked_diagonals => {
+            ("tooltip.mountain_blocked", count)
+        }
+        CellType::Mountain(count) => ("tooltip.mountain", count),
+        _ => return None,
+    };
+    Some(lang.t_with(key, &[("count", count.to_string())]))
+}
+
+fn create_terrain_tooltip(mut commands: Commands, server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                    ..default()
+                },
+                background_color: TOOLTIP_BACKGROUND.into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(40),
+                ..default()
+            },
+            TerrainTooltip,
+        ))
+        .with_children(|builder| {
+            builder.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: server.load("NotoSerif-SemiBold.ttf"),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                TerrainTooltipText,
+            ));
+        });
+}
+
+// Follows the cursor while it is over a lake or mountain.
+fn update_terrain_tooltip(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    game_screen_query: Query<&Transform, With<GameScreenRoot>>,
+    game_state: Res<GameState>,
+    lang: Res<Lang>,
+    mut tooltip_query: Query<(&mut Style, &mut Visibility), With<TerrainTooltip>>,
+    mut text_query: Query<&mut Text, With<TerrainTooltipText>>,
+) {
+    let Ok((mut style, mut visibility)) = tooltip_query.get_single_mut() else {
+        return;
+    };
+    let (camera, camera_global_transform) = camera_query.single();
+    let window = window_query.single();
+    let board_transform = game_screen_query.single();
+    let puzzle = &game_state.puzzle;
+    let (rows, cols) = puzzle.dims();
+
+    let hovered = window.cursor_position().and_then(|cursor| {
+        let world = camera.viewport_to_world_2d(camera_global_transform, cursor)?;
+        let p = world_to_board(world, board_transform.translation);
+        if !(p.cmpge(Vec2::ZERO).all() && p.cmplt(Vec2::new(cols as f32, rows as f32)).all()) {
+            return None;
+        }
+        let position = Position {
+            row: p.y as usize,
+            col: p.x as usize,
+        };
+        let rule = terrain_rule(puzzle.field[position.row][position.col], puzzle, &lang)?;
+        Some((cursor, rule))
+    });
+
+    let Some((cursor, rule)) = hovered else {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+    *visibility = Visibility::Visible;
+    style.left = Val::Px(cursor.x + TOOLTIP_OFFSET.x);
+    style.top = Val::Px(cursor.y + TOOLTIP_OFFSET.y);
+    for mut text in text_query.iter_mut() {
+        if text.sections[0].value != rule {
+            text.sections[0].value = rule.clone();
+        }
+    }
+}
+
+fn destroy_terrain_tooltip(mut commands: Commands, query: Query<Entity, With<TerrainTooltip>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
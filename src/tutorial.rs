@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::game_screen::LevelCompleted;
+use crate::lang::Lang;
+use crate::level::{all_levels, Position};
+use crate::pointer::{DismissPointers, PointerTarget, Pointers};
+use crate::save::SaveData;
+use crate::share::open_linked_level;
+use crate::{AppState, GameState};
+
+pub struct TutorialPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for TutorialPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_systems(PostStartup, start_onboarding.after(open_linked_level))
+            .add_systems(OnEnter(self.0), start_tutorial)
+            .add_systems(
+                Update,
+                (advance_tutorial, update_tutorial_overlay)
+                    .chain()
+                    .run_if(in_state(self.0)),
+            )
+            .add_systems(OnExit(self.0), destroy_tutorial_overlay);
+    }
+}
+
+const TUTORIAL_BACKGROUND: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+
+// The player action that finishes a tutorial step.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StepTrigger {
+    PlaceHouse,
+    PlaceMarker,
+    CompleteLevel,
+}
+
+struct TutorialStep {
+    // `Lang` key of the text shown.
+    text: &'static str,
+    trigger: StepTrigger,
+    // Cell to point an arrow at while the step is shown.
+    pointer: Option<Position>,
+}
+
+const FIRST_STEPS: &[TutorialStep] = &[TutorialStep {
+    text: "tutorial.first",
+    trigger: StepTrigger::PlaceHouse,
+    pointer: Some(Position { row: 0, col: 0 }),
+}];
+
+const NEIGHBORS_STEPS: &[TutorialStep] = &[TutorialStep {
+    text: "tutorial.neighbors",
+    trigger: StepTrigger::CompleteLevel,
+    pointer: None,
+}];
+
+const TREES_STEPS: &[TutorialStep] = &[
+    TutorialStep {
+        text: "tutorial.trees.grass",
+        trigger: StepTrigger::PlaceHouse,
+        pointer: None,
+    },
+    TutorialStep {
+        text: "tutorial.trees.markers",
+        trigger: StepTrigger::PlaceMarker,
+        pointer: None,
+    },
+];
+
+const LAKE_STEPS: &[TutorialStep] = &[TutorialStep {
+    text: "tutorial.lake",
+    trigger: StepTrigger::CompleteLevel,
+    pointer: None,
+}];
+
+const MOUNTAIN_STEPS: &[TutorialStep] = &[TutorialStep {
+    text: "tutorial.mountain",
+    trigger: StepTrigger::CompleteLevel,
+    pointer: None,
+}];
+
+// Level that the first launch starts on, skipping the main menu.
+const ONBOARDING_LEVEL: &str = "first";
+
+// The levels that introduce a rule, by id so reordering the levels keeps them.
+fn tutorial_steps(level_id: &str) -> Option<&'static [TutorialStep]> {
+    match level_id {
+        "first" => Some(FIRST_STEPS),
+        "neighbors" => Some(NEIGHBORS_STEPS),
+        "trees" => Some(TREES_STEPS),
+        "lake" => Some(LAKE_STEPS),
+        "mountain" => Some(MOUNTAIN_STEPS),
+        _ => None,
+    }
+}
+
+// Tutorial steps keyed by level index and the progress through the current level's.
+#[derive(Resource)]
+pub struct Tutorial {
+    steps: HashMap<usize, &'static [TutorialStep]>,
+    step: usize,
+    // Houses and markers on the board when last checked, to notice new ones.
+    placed: usize,
+    marked: usize,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            steps: all_levels()
+                .iter()
+                .enumerate()
+                .filter_map(|(index, level)| Some((index, tutorial_steps(&level.id)?)))
+                .collect(),
+            step: 0,
+            placed: 0,
+            marked: 0,
+        }
+    }
+}
+
+impl Tutorial {
+    fn current_step(&self, level: usize) -> Option<&'static TutorialStep> {
+        self.steps.get(&level)?.get(self.step)
+    }
+}
+
+// Sends brand-new players straight into the first tutorial level instead of the
+// menu. Players with completed levels from before onboarding existed, and ones
+// opening a shared level, skip it. The flag is written with the next save.
+fn start_onboarding(
+    mut save_data: ResMut<SaveData>,
+    mut game_state: ResMut<GameState>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    if save_data.seen_onboarding {
+        return;
+    }
+    save_data.seen_onboarding = true;
+    if !save_data.completed_levels.is_empty() || app_state.0.is_some() {
+        return;
+    }
+    let Some(index) = all_levels()
+        .iter()
+        .position(|level| level.id == ONBOARDING_LEVEL)
+    else {
+        return;
+    };
+    game_state.current_level = index;
+    app_state.set(AppState::SwitchLevel);
+}
+
+#[derive(Component)]
+struct TutorialOverlay;
+
+#[derive(Component)]
+struct TutorialText;
+
+fn count_markers(game_state: &GameState) -> usize {
+    game_state
+        .hints
+        .iter()
+        .flatten()
+        .filter(|&&hint| hint != 0)
+        .count()
+}
+
+fn start_tutorial(
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    game_state: Res<GameState>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    tutorial.step = 0;
+    tutorial.placed = game_state.solution.placements.len();
+    tutorial.marked = count_markers(&game_state);
+    // Levels without a tutorial get no overlay at all.
+    let Some(step) = tutorial.current_step(game_state.current_level) else {
+        return;
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(120.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                z_index: ZIndex::Global(50),
+                ..default()
+            },
+            TutorialOverlay,
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(NodeBundle {
+                    style: Style {
+                        padding: UiRect::axes(Val::Px(20.0), Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: TUTORIAL_BACKGROUND.into(),
+                    ..default()
+                })
+                .with_children(|builder| {
+                    builder.spawn((
+                        TextBundle::from_section(
+                            lang.t(step.text),
+                            TextStyle {
+                                font: server.load("NotoSerif-SemiBold.ttf"),
+                                font_size: 28.0,
+                                color: Color::WHITE,
+                            },
+                        )
+                        .with_text_alignment(TextAlignment::Center),
+                        TutorialText,
+                    ));
+                });
+        });
+}
+
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    game_state: Res<GameState>,
+    mut level_completed: EventReader<LevelCompleted>,
+) {
+    let completed = level_completed.iter().count() > 0;
+    if !game_state.is_changed() && !completed {
+        return;
+    }
+    let placed = game_state.solution.placements.len();
+    let marked = count_markers(&game_state);
+    let Some(step) = tutorial.current_step(game_state.current_level) else {
+        return;
+    };
+    let done = match step.trigger {
+        StepTrigger::PlaceHouse => placed > tutorial.placed,
+        StepTrigger::PlaceMarker => marked > tutorial.marked,
+        StepTrigger::CompleteLevel => completed,
+    };
+    if tutorial.placed != placed || tutorial.marked != marked {
+        tutorial.placed = placed;
+        tutorial.marked = marked;
+    }
+    if done {
+        tutorial.step += 1;
+    }
+}
+
+// Shows the text and pointer of the current step, and removes the overlay after the
+// last one.
+fn update_tutorial_overlay(
+    mut commands: Commands,
+    tutorial: Res<Tutorial>,
+    game_state: Res<GameState>,
+    overlay_query: Query<Entity, With<TutorialOverlay>>,
+    mut text_query: Query<&mut Text, With<TutorialText>>,
+    mut pointers: Pointers,
+    lang: Res<Lang>,
+) {
+    if !tutorial.is_changed() {
+        return;
+    }
+    pointers.dismiss();
+    match tutorial.current_step(game_state.current_level) {
+        Some(step) => {
+            for mut text in text_query.iter_mut() {
+                text.sections[0].value = lang.t(step.text).to_string();
+            }
+            if let Some(position) = step.pointer {
+                pointers.show(PointerTarget::Cell(position));
+            }
+        }
+        None => {
+            for entity in overlay_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+fn destroy_tutorial_overlay(
+    mut commands: Commands,
+    query: Query<Entity, With<TutorialOverlay>>,
+    mut dismiss_pointers: EventWriter<DismissPointers>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    dismiss_pointers.send(DismissPointers);
+}
@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{DragEvent, File, FileReader};
+
+use crate::input::DroppedFile;
+
+pub struct WebDropPlugin;
+
+impl Plugin for WebDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WebDrops>()
+            .add_systems(Update, listen_for_drops);
+    }
+}
+
+// Bevy doesn't report files dropped on the canvas in the browser, so the page
+// listens for them itself. The browser reads them in the background, and they wait
+// here for the next frame to pick them up.
+#[derive(Resource, Clone, Default)]
+pub struct WebDrops(Arc<Mutex<Vec<DroppedFile>>>);
+
+impl WebDrops {
+    fn push(&self, file: DroppedFile) {
+        if let Ok(mut files) = self.0.lock() {
+            files.push(file);
+        }
+    }
+
+    pub fn take(&self) -> Vec<DroppedFile> {
+        self.0
+            .lock()
+            .map(|mut files| std::mem::take(&mut *files))
+            .unwrap_or_default()
+    }
+}
+
+// Adds the drop listeners to the canvas, once it is on the page.
+fn listen_for_drops(web_drops: Res<WebDrops>, mut listening: Local<bool>) {
+    if *listening {
+        return;
+    }
+    let Some(canvas) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector("canvas").ok().flatten())
+    else {
+        // The canvas is added when the window is created, after startup.
+        return;
+    };
+    // Without this the browser opens the dropped file itself instead of dropping it.
+    let drag_over = Closure::<dyn FnMut(DragEvent)>::new(|event: DragEvent| {
+        event.prevent_default();
+    });
+    let web_drops = web_drops.clone();
+    let drop = Closure::<dyn FnMut(DragEvent)>::new(move |event: DragEvent| {
+        event.prevent_default();
+        let Some(files) = event.data_transfer().and_then(|data| data.files()) else {
+            return;
+        };
+        for file in (0..files.length()).filter_map(|index| files.get(index)) {
+            read_file(file, web_drops.clone());
+        }
+    });
+    for (name, listener) in [("dragover", drag_over), ("drop", drop)] {
+        if let Err(err) =
+            canvas.add_event_listener_with_callback(name, listener.as_ref().unchecked_ref())
+        {
+            warn!("Failed to listen for dropped files: {err:?}");
+        }
+        // The listeners stay for as long as the page does.
+        listener.forget();
+    }
+    *listening = true;
+}
+
+fn read_file(file: File, web_drops: WebDrops) {
+    let name = file.name();
+    let reader = match FileReader::new() {
+        Ok(reader) => reader,
+        Err(err) => {
+            web_drops.push(DroppedFile {
+                name,
+                contents: Err(format!("{err:?}")),
+            });
+            return;
+        }
+    };
+    // Called once reading is done, whether it worked or not.
+    let finished_reader = reader.clone();
+    let finished_name = name.clone();
+    let finished_drops = web_drops.clone();
+    let on_load_end = Closure::<dyn FnMut()>::once(move || {
+        let contents = finished_reader
+            .result()
+            .ok()
+            .and_then(|result| result.as_string())
+            .ok_or_else(|| "couldn't read the file".to_string());
+        finished_drops.push(DroppedFile {
+            name: finished_name,
+            contents,
+        });
+    });
+    reader.set_onloadend(Some(on_load_end.as_ref().unchecked_ref()));
+    on_load_end.forget();
+    if let Err(err) = reader.read_as_text(&file) {
+        web_drops.push(DroppedFile {
+            name,
+            contents: Err(format!("{err:?}")),
+        });
+    }
+}
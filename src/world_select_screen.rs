@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use crate::lang::Lang;
+use crate::level::{all_packs, LevelPack};
+use crate::save::SaveData;
+use crate::select_level_screen::SelectedPack;
+use crate::{AppState, ButtonInteractions};
+
+pub struct WorldSelectScreenPlugin<S: States + Copy>(pub S);
+
+impl<S: States + Copy> Plugin for WorldSelectScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.0), create_world_select_screen)
+            .add_systems(Update, button_system.run_if(in_state(self.0)))
+            .add_systems(OnExit(self.0), destroy_world_select_screen);
+    }
+}
+
+const NORMAL_BUTTON: Color = Color::WHITE;
+const HOVERED_BUTTON: Color = Color::rgb(0.8, 0.8, 0.8);
+const PRESSED_BUTTON: Color = Color::rgb(0.7, 0.7, 0.7);
+const LOCKED_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+
+// Tag component used to tag entities added on the world select screen.
+#[derive(Component)]
+struct OnWorldSelectScreen;
+
+// All actions that can be triggered from a button click.
+#[derive(Component)]
+enum WorldSelectButtonAction {
+    OpenPack(usize),
+    Back,
+}
+
+// A pack with its progress. Locked packs are shown greyed out and can't be opened.
+fn item_pack(
+    builder: &mut ChildBuilder,
+    index: usize,
+    pack: &LevelPack,
+    save_data: &SaveData,
+    lang: &Lang,
+    server: &Res<AssetServer>,
+) {
+    let unlocked = save_data.is_pack_unlocked(index);
+    let progress = if unlocked {
+        lang.t_with(
+            "main_menu.levels_complete",
+            &[
+                ("completed", save_data.completed_in_pack(index).to_string()),
+                ("total", pack.levels.len().to_string()),
+            ],
+        )
+    } else {
+        lang.t("world_select.locked").to_string()
+    };
+    let style = Style {
+        width: Val::Px(500.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        padding: UiRect::all(Val::Px(10.0)),
+        margin: UiRect::all(Val::Px(15.0)),
+        ..default()
+    };
+    let image = UiImage::new(server.load("UI/button_empty.png"));
+    let mut entity = if unlocked {
+        builder.spawn((
+            ButtonBundle {
+                style,
+                background_color: NORMAL_BUTTON.into(),
+                image,
+                ..default()
+            },
+            WorldSelectButtonAction::OpenPack(index),
+        ))
+    } else {
+        builder.spawn(ImageBundle {
+            style,
+            background_color: LOCKED_COLOR.into(),
+            image,
+            ..default()
+        })
+    };
+    entity.with_children(|builder| {
+        builder.spawn(TextBundle::from_section(
+            pack.name.as_str(),
+            TextStyle {
+                font: server.load(crate::TEXT_FONT_NAME),
+                font_size: 60.0,
+                color: Color::WHITE,
+            },
+        ));
+        builder.spawn(TextBundle::from_section(
+            progress,
+            TextStyle {
+                font: server.load(crate::TEXT_FONT_NAME),
+                font_size: 36.0,
+                color: Color::WHITE,
+            },
+        ));
+    });
+}
+
+fn create_world_select_screen(
+    mut commands: Commands,
+    save_data: Res<SaveData>,
+    lang: Res<Lang>,
+    server: Res<AssetServer>,
+) {
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                image: UiImage::new(server.load("full.png")),
+                ..default()
+            },
+            OnWorldSelectScreen,
+        ))
+        .with_children(|builder| {
+            builder.spawn(
+                TextBundle::from_section(
+                    lang.t("world_select.title"),
+                    TextStyle {
+                        font: server.load(crate::TEXT_FONT_NAME),
+                        font_size: 120.0,
+                        color: crate::CUSTOM_ORANGE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                }),
+            );
+
+            for (index, pack) in all_packs().iter().enumerate() {
+                item_pack(builder, index, pack, &save_data, &lang, &server);
+            }
+
+            builder.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(50.0),
+                        height: Val::Px(50.0),
+                        margin: UiRect::all(Val::Px(30.0)),
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    image: UiImage::new(server.load("UI/button_back.png")),
+                    ..default()
+                },
+                WorldSelectButtonAction::Back,
+            ));
+        });
+}
+
+fn destroy_world_select_screen(
+    mut commands: Commands,
+    query: Query<Entity, With<OnWorldSelectScreen>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// This system handles changing all buttons color based on mouse interaction
+fn button_system(
+    mut interaction_query: ButtonInteractions<WorldSelectButtonAction>,
+    mut selected_pack: ResMut<SelectedPack>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, mut color, action) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed => PRESSED_BUTTON.into(),
+            Interaction::Hovered => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+
+        if *interaction == Interaction::Pressed {
+            match *action {
+                WorldSelectButtonAction::OpenPack(pack) => {
+                    selected_pack.0 = pack;
+                    app_state.set(AppState::SelectLevelScreen);
+                }
+                WorldSelectButtonAction::Back => {
+                    app_state.set(AppState::MainMenuScreen);
+                }
+            };
+        }
+    }
+}